@@ -0,0 +1,39 @@
+// Minimal stand-in for the SMS gateway used by the backend's OTP verification.
+// Logs every message it receives and answers 200, so codes can be read from the console.
+//
+// HTTPS outcalls are sent once per replica, so like a real gateway it de-duplicates on the
+// Idempotency-Key header (falling back to the idempotency_key in the body) and only logs the
+// first request for each key.
+//
+// Usage: node scripts/mock-sms-gateway.mjs [port]
+// Then, as an admin: set_verification_settings with sms_endpoint_url = "http://localhost:<port>/sms"
+
+import { createServer } from "node:http";
+
+const port = Number(process.argv[2] ?? 8085);
+const seenKeys = new Set();
+
+function idempotencyKey(req, body) {
+  const header = req.headers["idempotency-key"];
+  if (header) return header;
+  try {
+    return JSON.parse(body).idempotency_key;
+  } catch {
+    return undefined;
+  }
+}
+
+createServer((req, res) => {
+  let body = "";
+  req.on("data", (chunk) => (body += chunk));
+  req.on("end", () => {
+    const key = idempotencyKey(req, body);
+    const duplicate = key !== undefined && seenKeys.has(key);
+    if (key !== undefined) seenKeys.add(key);
+    if (!duplicate) {
+      console.log(`[${new Date().toISOString()}] ${req.method} ${req.url} ${body}`);
+    }
+    res.writeHead(200, { "Content-Type": "application/json" });
+    res.end(duplicate ? '{"status":"duplicate"}' : '{"status":"queued"}');
+  });
+}).listen(port, () => console.log(`Mock SMS gateway listening on http://localhost:${port}`));
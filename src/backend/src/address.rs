@@ -0,0 +1,82 @@
+use crate::{
+    errors::{FieldError, FieldErrorKind},
+    models::{AddressInput, SavedAddress},
+};
+
+/// Maximum number of saved addresses per customer.
+pub const MAX_SAVED_ADDRESSES: u64 = 10;
+
+/// Validates and trims an address submitted by a customer.
+pub fn validate_address_input(input: AddressInput) -> Result<AddressInput, Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let label = input.label.trim().to_string();
+    if label.is_empty() {
+        errors.push(FieldError::new(
+            "label",
+            FieldErrorKind::Required,
+            "Address label cannot be empty.",
+        ));
+    }
+    let address_lines: Vec<String> = input
+        .address_lines
+        .iter()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+    if address_lines.is_empty() {
+        errors.push(FieldError::new(
+            "address_lines",
+            FieldErrorKind::Required,
+            "Address must have at least one non-empty line.",
+        ));
+    }
+    if let Some(geo) = &input.geo {
+        if !(-90.0..=90.0).contains(&geo.latitude) {
+            errors.push(FieldError::new(
+                "geo.latitude",
+                FieldErrorKind::OutOfRange,
+                "Latitude must be between -90 and 90.",
+            ));
+        }
+        if !(-180.0..=180.0).contains(&geo.longitude) {
+            errors.push(FieldError::new(
+                "geo.longitude",
+                FieldErrorKind::OutOfRange,
+                "Longitude must be between -180 and 180.",
+            ));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let non_empty = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    Ok(AddressInput {
+        label,
+        address_lines,
+        landmark: non_empty(input.landmark),
+        zone: non_empty(input.zone),
+        geo: input.geo,
+    })
+}
+
+/// Returns the next free address ID for a list of saved addresses.
+pub fn next_address_id(addresses: &[SavedAddress]) -> u64 {
+    addresses.iter().map(|a| a.id + 1).max().unwrap_or(0)
+}
+
+/// Formats a saved address as the free-text delivery address stored on orders.
+pub fn format_address(address: &SavedAddress) -> String {
+    let mut formatted = address.address_lines.join(", ");
+    if let Some(landmark) = &address.landmark {
+        formatted.push_str(&format!(" (Landmark: {})", landmark));
+    }
+    if let Some(zone) = &address.zone {
+        formatted.push_str(&format!(", {}", zone));
+    }
+    formatted
+}
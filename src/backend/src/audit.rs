@@ -0,0 +1,56 @@
+use crate::{hex, models::AuditOutcome, phone, store};
+use candid::Principal;
+use sha2::{Digest, Sha256};
+use std::fmt::Debug;
+
+/// Records the outcome of a guarded update call in the append-only audit log.
+///
+/// Guarded queries are not recorded: state written during a query call is discarded.
+///
+/// Entries are never rewritten or pruned, as that would break the hash chain, so anonymising
+/// a customer can't remove their phone number from earlier entries. Keep personal data out of
+/// `args_summary` (see [`phone_ref`]) and out of errors recorded with the outcome.
+pub fn record<T, E: Debug>(method: &str, args_summary: String, result: &Result<T, E>) {
+    record_for(ic_cdk::caller(), method, args_summary, result);
+}
+
+/// Same as [`record`] but with an explicit caller, for async endpoints that capture the
+/// caller before their first `await`.
+pub fn record_for<T, E: Debug>(
+    caller: Principal,
+    method: &str,
+    args_summary: String,
+    result: &Result<T, E>,
+) {
+    record_at(caller, method, args_summary, result, ic_cdk::api::time());
+}
+
+/// Same as [`record_for`] but at an explicit time.
+pub fn record_at<T, E: Debug>(
+    caller: Principal,
+    method: &str,
+    args_summary: String,
+    result: &Result<T, E>,
+    now: u64,
+) {
+    let outcome = match result {
+        Ok(_) => AuditOutcome::Success,
+        Err(e) => AuditOutcome::Failure(format!("{:?}", e)),
+    };
+    // Failing to write the audit trail must not hide the result of the admin action itself,
+    // but it should be visible in the canister logs.
+    if let Err(e) =
+        store::append_audit_entry(caller, method.to_string(), args_summary, outcome, now)
+    {
+        ic_cdk::println!("{}", e);
+    }
+}
+
+/// Stands in for a phone number in audit summaries: entries about the same number can be
+/// matched by hashing it again, without the log holding the number itself. Numbers are
+/// normalised first where possible, so any way of writing one gives the same reference.
+pub fn phone_ref(phone_number: &str) -> String {
+    let normalized = phone::normalize_phone_number(phone_number, &store::get_phone_settings())
+        .unwrap_or_else(|_| phone_number.trim().to_string());
+    hex::encode(&Sha256::digest(normalized.as_bytes())[..8])
+}
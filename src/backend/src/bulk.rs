@@ -0,0 +1,505 @@
+// Bulk CSV import and export of products, customer profiles and orders.
+//
+// Imports validate every row on its own: valid rows are written, invalid ones are reported
+// and skipped. With `dry_run` the same validation runs without writing anything.
+use crate::{
+    csv, date,
+    errors::CsvError,
+    i18n,
+    models::{
+        ImportReport, ImportRowResult, ImportRowStatus, Order, PhoneSettings, Product,
+        ProductTranslation, UserProfile,
+    },
+    phone, pricing, store,
+};
+use candid::{Encode, Principal};
+use std::{cell::RefCell, collections::BTreeMap, collections::BTreeSet};
+
+/// Upper bound for a chunked upload, well below the heap limit.
+pub const MAX_UPLOAD_BYTES: usize = 8 * 1024 * 1024;
+
+thread_local! {
+    // Chunked CSV uploads in progress: (uploader, upload ID) -> content so far.
+    // Kept on the heap only; an upgrade discards unfinished uploads.
+    static UPLOADS: RefCell<BTreeMap<(Principal, String), String>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Appends a chunk to an upload and returns its total size in bytes.
+pub fn append_upload_chunk(
+    caller: Principal,
+    upload_id: String,
+    chunk: &str,
+) -> Result<u64, CsvError> {
+    UPLOADS.with(|uploads| {
+        let mut uploads = uploads.borrow_mut();
+        let content = uploads.entry((caller, upload_id)).or_default();
+        if content.len() + chunk.len() > MAX_UPLOAD_BYTES {
+            return Err(CsvError::UploadTooLarge(MAX_UPLOAD_BYTES as u64));
+        }
+        content.push_str(chunk);
+        Ok(content.len() as u64)
+    })
+}
+
+pub fn get_upload(caller: Principal, upload_id: &str) -> Option<String> {
+    UPLOADS.with(|uploads| {
+        uploads
+            .borrow()
+            .get(&(caller, upload_id.to_string()))
+            .cloned()
+    })
+}
+
+pub fn remove_upload(caller: Principal, upload_id: &str) {
+    UPLOADS.with(|uploads| {
+        uploads
+            .borrow_mut()
+            .remove(&(caller, upload_id.to_string()))
+    });
+}
+
+/// Positions of the known columns in the header row, matched by name in any order.
+struct Columns {
+    positions: BTreeMap<&'static str, usize>,
+}
+
+impl Columns {
+    fn from_header(
+        header: &[String],
+        required: &[&'static str],
+        optional: &[&'static str],
+    ) -> Result<Self, String> {
+        let mut positions = BTreeMap::new();
+        for name in required.iter().chain(optional) {
+            if let Some(position) = header
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(name))
+            {
+                positions.insert(*name, position);
+            }
+        }
+        let missing: Vec<&str> = required
+            .iter()
+            .filter(|name| !positions.contains_key(*name))
+            .copied()
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("Missing column(s): {}", missing.join(", ")));
+        }
+        Ok(Columns { positions })
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.positions.contains_key(name)
+    }
+
+    fn get<'a>(&self, record: &'a [String], name: &str) -> &'a str {
+        self.positions
+            .get(name)
+            .and_then(|&position| record.get(position))
+            .map(|value| value.trim())
+            .unwrap_or("")
+    }
+}
+
+fn parse_with_header(
+    input: &str,
+    required: &[&'static str],
+    optional: &[&'static str],
+) -> Result<(Columns, Vec<Vec<String>>), CsvError> {
+    let mut records = csv::parse(input).map_err(CsvError::InvalidCsv)?.into_iter();
+    let header = records
+        .next()
+        .ok_or(CsvError::InvalidCsv("The CSV file is empty.".to_string()))?;
+    let columns =
+        Columns::from_header(&header, required, optional).map_err(CsvError::InvalidCsv)?;
+    Ok((columns, records.collect()))
+}
+
+impl ImportReport {
+    fn push(&mut self, row: usize, key: String, status: ImportRowStatus) {
+        match status {
+            ImportRowStatus::Created => self.created += 1,
+            ImportRowStatus::Updated => self.updated += 1,
+            ImportRowStatus::Invalid(_) => self.invalid += 1,
+        }
+        self.rows.push(ImportRowResult {
+            // Data rows start after the header, which is row 1
+            row: row as u64 + 2,
+            key,
+            status,
+        });
+    }
+}
+
+/// Imports products from `id,name,description,price,unit`. Rows without an ID create new
+/// products; rows with the ID of an existing product update it. The optional `name_hi`,
+/// `description_hi` and `unit_hi` columns set the Hindi texts; without them an update keeps
+/// the product's translations. Price changes are recorded in the price history at `now`.
+pub fn import_products(input: &str, dry_run: bool, now: u64) -> Result<ImportReport, CsvError> {
+    let (columns, records) = parse_with_header(
+        input,
+        &["name", "description", "price", "unit"],
+        &["id", "name_hi", "description_hi", "unit_hi"],
+    )?;
+    let mut report = ImportReport {
+        dry_run,
+        ..Default::default()
+    };
+    let mut seen_ids = BTreeSet::new();
+
+    for (row, record) in records.iter().enumerate() {
+        let id_field = columns.get(record, "id");
+        let key = if id_field.is_empty() {
+            "new".to_string()
+        } else {
+            id_field.to_string()
+        };
+        let existing_id = match id_field {
+            "" => None,
+            value => match value.parse::<u64>() {
+                Ok(id) if store::get_product_by_id(id).is_some() => Some(id),
+                Ok(id) => {
+                    let error = format!("Product {} does not exist.", id);
+                    report.push(row, key, ImportRowStatus::Invalid(error));
+                    continue;
+                }
+                Err(_) => {
+                    let error = format!("Invalid product ID '{}'.", value);
+                    report.push(row, key, ImportRowStatus::Invalid(error));
+                    continue;
+                }
+            },
+        };
+        if let Some(id) = existing_id {
+            if !seen_ids.insert(id) {
+                let error = "Product appears more than once in the file.".to_string();
+                report.push(row, key, ImportRowStatus::Invalid(error));
+                continue;
+            }
+        }
+
+        // Category, tags, image and variants aren't part of the file; updates keep them
+        let current = existing_id
+            .and_then(store::get_product_by_id)
+            .unwrap_or_default();
+        let product = match validate_product(&columns, record, existing_id.unwrap_or(0))
+            .map(|product| Product {
+                category_id: current.category_id,
+                tags: current.tags,
+                image_id: current.image_id,
+                variants: current.variants,
+                quantity_step: current.quantity_step,
+                ..product
+            })
+            .and_then(|product| with_hindi_texts(&columns, record, product, current.translations))
+        {
+            Ok(product) => product,
+            Err(error) => {
+                report.push(row, key, ImportRowStatus::Invalid(error));
+                continue;
+            }
+        };
+
+        let status = match existing_id {
+            Some(_) => ImportRowStatus::Updated,
+            None => ImportRowStatus::Created,
+        };
+        let key = if dry_run {
+            key
+        } else {
+            let stored = match existing_id {
+                Some(id) => {
+                    if let Some(current) = store::get_product_by_id(id) {
+                        if current.price != product.price {
+                            pricing::record_price_change(&current, product.price, now, now);
+                        }
+                    }
+                    store::update_product(id, product)
+                        .map(|stored| stored.id)
+                        .map_err(|e| e.to_string())
+                }
+                None => store::add_product(product).map_err(|e| e.to_string()),
+            };
+            match stored {
+                Ok(id) => id.to_string(),
+                Err(error) => {
+                    report.push(row, key, ImportRowStatus::Invalid(error));
+                    continue;
+                }
+            }
+        };
+        report.push(row, key, status);
+    }
+    Ok(report)
+}
+
+fn validate_product(columns: &Columns, record: &[String], id: u64) -> Result<Product, String> {
+    let name = columns.get(record, "name");
+    let unit = columns.get(record, "unit");
+    if name.is_empty() || unit.is_empty() {
+        return Err("Name and unit cannot be empty.".to_string());
+    }
+    let price_field = columns.get(record, "price");
+    let price = match price_field.parse::<f64>() {
+        Ok(price) if price.is_finite() && price >= 0.0 => price,
+        _ => return Err(format!("Invalid price '{}'.", price_field)),
+    };
+    let product = Product {
+        id,
+        name: name.to_string(),
+        description: columns.get(record, "description").to_string(),
+        price,
+        unit: unit.to_string(),
+        ..Default::default()
+    };
+    let encoded_size = Encode!(&product)
+        .map(|bytes| bytes.len())
+        .unwrap_or(usize::MAX);
+    if encoded_size > store::MAX_PRODUCT_SIZE as usize {
+        return Err("Name, description and unit are too long.".to_string());
+    }
+    Ok(product)
+}
+
+/// Imports customer profiles from `phone_number,name,address`. Numbers are normalised;
+/// existing profiles get their name and address updated, everything else is kept.
+pub fn import_customers(
+    input: &str,
+    dry_run: bool,
+    settings: &PhoneSettings,
+) -> Result<ImportReport, CsvError> {
+    let (columns, records) = parse_with_header(input, &["phone_number", "name", "address"], &[])?;
+    let mut report = ImportReport {
+        dry_run,
+        ..Default::default()
+    };
+    let mut seen_numbers = BTreeSet::new();
+
+    for (row, record) in records.iter().enumerate() {
+        let raw_phone = columns.get(record, "phone_number");
+        let phone_number = match phone::normalize_phone_number(raw_phone, settings) {
+            Ok(phone_number) => phone_number,
+            Err(e) => {
+                let error = format!("Invalid phone number: {:?}", e);
+                report.push(row, raw_phone.to_string(), ImportRowStatus::Invalid(error));
+                continue;
+            }
+        };
+        let name = columns.get(record, "name");
+        let address = columns.get(record, "address");
+        let invalid = if name.is_empty() || address.is_empty() {
+            Some("Name and address cannot be empty.".to_string())
+        } else if !seen_numbers.insert(phone_number.clone()) {
+            Some("Phone number appears more than once in the file.".to_string())
+        } else {
+            None
+        };
+        if let Some(error) = invalid {
+            report.push(row, phone_number, ImportRowStatus::Invalid(error));
+            continue;
+        }
+
+        let existing = store::get_user_profile(&phone_number);
+        let (profile, status) = match existing {
+            Some(profile) => (
+                UserProfile {
+                    name: name.to_string(),
+                    address: address.to_string(),
+                    ..profile
+                },
+                ImportRowStatus::Updated,
+            ),
+            None => (
+                UserProfile {
+                    phone_number: phone_number.clone(),
+                    name: name.to_string(),
+                    address: address.to_string(),
+                    ..Default::default()
+                },
+                ImportRowStatus::Created,
+            ),
+        };
+        // Storing a profile over the bound would trap and roll back the whole import
+        if let Err(e) = store::check_bounded_size(&profile) {
+            let error = format!("Name and address are too long: {}", e);
+            report.push(row, phone_number, ImportRowStatus::Invalid(error));
+            continue;
+        }
+        if !dry_run {
+            if status == ImportRowStatus::Created {
+                // A recycled number no longer redirects to its previous owner
+                store::remove_phone_redirect(&phone_number);
+            }
+            store::update_user_profile(profile);
+        }
+        report.push(row, phone_number, status);
+    }
+    Ok(report)
+}
+
+/// Sets the Hindi translation from the `*_hi` columns, if the file has them. An empty
+/// `name_hi` removes the translation.
+fn with_hindi_texts(
+    columns: &Columns,
+    record: &[String],
+    product: Product,
+    current_translations: Option<Vec<ProductTranslation>>,
+) -> Result<Product, String> {
+    if !columns.has("name_hi") {
+        return Ok(Product {
+            translations: current_translations,
+            ..product
+        });
+    }
+    let mut translations: Vec<ProductTranslation> = current_translations
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|translation| translation.lang != "hi")
+        .collect();
+    let name = columns.get(record, "name_hi");
+    if !name.is_empty() {
+        translations.push(ProductTranslation {
+            lang: "hi".to_string(),
+            name: name.to_string(),
+            description: columns.get(record, "description_hi").to_string(),
+            unit: Some(columns.get(record, "unit_hi").to_string()),
+        });
+    }
+    let translations = i18n::validate_translations(translations)
+        .map_err(|errors| crate::errors::describe_field_errors(&errors))?;
+    Ok(Product {
+        translations: (!translations.is_empty()).then_some(translations),
+        ..product
+    })
+}
+
+pub fn export_products() -> String {
+    let mut out = String::new();
+    csv::write_row(
+        &mut out,
+        &[
+            "id",
+            "name",
+            "description",
+            "price",
+            "unit",
+            "name_hi",
+            "description_hi",
+            "unit_hi",
+        ],
+    );
+    for product in store::get_all_products() {
+        let hindi = product
+            .translations
+            .iter()
+            .flatten()
+            .find(|translation| translation.lang == "hi")
+            .cloned()
+            .unwrap_or_default();
+        csv::write_row(
+            &mut out,
+            &[
+                product.id.to_string(),
+                product.name,
+                product.description,
+                product.price.to_string(),
+                product.unit,
+                hindi.name,
+                hindi.description,
+                hindi.unit.unwrap_or_default(),
+            ],
+        );
+    }
+    out
+}
+
+/// Exports customer profiles, leaving out anonymised ones.
+pub fn export_customers(utc_offset_minutes: i32) -> String {
+    let mut out = String::new();
+    csv::write_row(
+        &mut out,
+        &[
+            "phone_number",
+            "name",
+            "address",
+            "verified_at",
+            "order_count",
+        ],
+    );
+    for profile in store::get_all_user_profiles() {
+        if profile.deleted_at.is_some() {
+            continue;
+        }
+        csv::write_row(
+            &mut out,
+            &[
+                profile.phone_number,
+                profile.name,
+                profile.address,
+                profile
+                    .verified_at
+                    .map(|ts| date::format_timestamp(ts, utc_offset_minutes))
+                    .unwrap_or_default(),
+                profile.order_ids.len().to_string(),
+            ],
+        );
+    }
+    out
+}
+
+/// Orders placed between `start` (inclusive) and `end` (exclusive), sorted by ID.
+pub fn orders_between(start: u64, end: u64) -> Vec<Order> {
+    let mut orders: Vec<Order> = store::get_all_orders()
+        .into_iter()
+        .filter(|order| (start..end).contains(&order.timestamp))
+        .collect();
+    orders.sort_by_key(|order| order.id);
+    orders
+}
+
+pub fn export_orders(orders: Vec<Order>, utc_offset_minutes: i32) -> String {
+    let mut out = String::new();
+    csv::write_row(
+        &mut out,
+        &[
+            "order_id",
+            "date_time",
+            "customer_name",
+            "phone_number",
+            "status",
+            "items",
+            "total_amount",
+            "delivery_address",
+        ],
+    );
+    for order in orders {
+        let items: Vec<String> = order
+            .items
+            .iter()
+            .map(|item| {
+                let name = store::get_product_by_id(item.product_id)
+                    .map(|p| p.name)
+                    .unwrap_or_else(|| format!("#{}", item.product_id));
+                format!(
+                    "{} x {} @ {:.2}",
+                    item.quantity, name, item.price_per_unit_at_order
+                )
+            })
+            .collect();
+        csv::write_row(
+            &mut out,
+            &[
+                order.id.to_string(),
+                date::format_timestamp(order.timestamp, utc_offset_minutes),
+                order.customer_name,
+                order.user_phone_number,
+                format!("{:?}", order.status),
+                items.join("; "),
+                format!("{:.2}", order.total_amount),
+                order.delivery_address,
+            ],
+        );
+    }
+    out
+}
@@ -0,0 +1,192 @@
+// Server-side shopping carts.
+//
+// A cart is stored under the customer's normalised phone number, so it follows them from
+// one device to another. Each item remembers the price the customer last saw: views flag
+// items whose product price changed since, and checkout refuses to order at prices the
+// customer hasn't seen. Packs of different variants of a product are separate items.
+use crate::{
+    catalog,
+    errors::{CartError, FieldError, FieldErrorKind, QuantityError},
+    models::{Cart, CartItem, CartLine, CartView},
+    pricing, store,
+};
+
+/// Maximum number of different products in a cart.
+pub const MAX_CART_ITEMS: u64 = 50;
+
+// The customer's price for `quantity` of the product or variant at `now`, once the
+// quantity is checked
+fn current_price(
+    cart: &Cart,
+    product_id: u64,
+    variant_id: Option<u64>,
+    quantity: f64,
+    now: u64,
+) -> Result<f64, CartError> {
+    let product =
+        store::get_product_by_id(product_id).ok_or(CartError::ProductNotFound(product_id))?;
+    let variant =
+        catalog::check_quantity(&product, variant_id, quantity).map_err(|error| match error {
+            QuantityError::VariantNotFound(variant_id) => CartError::VariantNotFound {
+                product_id,
+                variant_id,
+            },
+            other => CartError::InvalidInput(vec![FieldError::new(
+                "quantity",
+                FieldErrorKind::OutOfRange,
+                other.to_string(),
+            )]),
+        })?;
+    let price_list_id = pricing::customer_price_list_id(&cart.phone_number);
+    Ok(
+        pricing::item_price(price_list_id, &product, variant.as_ref(), quantity, now)
+            .price_per_unit,
+    )
+}
+
+fn is_item(item: &CartItem, product_id: u64, variant_id: Option<u64>) -> bool {
+    item.product_id == product_id && item.variant_id == variant_id
+}
+
+/// Adds `quantity` of a product or packs of a variant, on top of what is already in the cart.
+pub fn add_item(
+    cart: &mut Cart,
+    product_id: u64,
+    variant_id: Option<u64>,
+    quantity: f64,
+    now: u64,
+) -> Result<(), CartError> {
+    // Checks the added quantity on its own, so that an invalid one isn't masked by the total
+    current_price(cart, product_id, variant_id, quantity, now)?;
+    let quantity = quantity
+        + cart
+            .items
+            .iter()
+            .find(|item| is_item(item, product_id, variant_id))
+            .map_or(0.0, |item| item.quantity);
+    let price_per_unit = current_price(cart, product_id, variant_id, quantity, now)?;
+    match cart
+        .items
+        .iter_mut()
+        .find(|item| is_item(item, product_id, variant_id))
+    {
+        Some(item) => {
+            item.quantity = quantity;
+            item.price_per_unit = price_per_unit;
+        }
+        None => {
+            if cart.items.len() as u64 >= MAX_CART_ITEMS {
+                return Err(CartError::TooManyItems(MAX_CART_ITEMS));
+            }
+            cart.items.push(CartItem {
+                product_id,
+                quantity,
+                price_per_unit,
+                variant_id,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the quantity of a product or variant that is already in the cart.
+pub fn update_item(
+    cart: &mut Cart,
+    product_id: u64,
+    variant_id: Option<u64>,
+    quantity: f64,
+    now: u64,
+) -> Result<(), CartError> {
+    let price_per_unit = current_price(cart, product_id, variant_id, quantity, now)?;
+    let item = cart
+        .items
+        .iter_mut()
+        .find(|item| is_item(item, product_id, variant_id))
+        .ok_or(CartError::ItemNotInCart(product_id))?;
+    item.quantity = quantity;
+    item.price_per_unit = price_per_unit;
+    Ok(())
+}
+
+pub fn remove_item(
+    cart: &mut Cart,
+    product_id: u64,
+    variant_id: Option<u64>,
+) -> Result<(), CartError> {
+    let len_before = cart.items.len();
+    cart.items
+        .retain(|item| !is_item(item, product_id, variant_id));
+    if cart.items.len() == len_before {
+        return Err(CartError::ItemNotInCart(product_id));
+    }
+    Ok(())
+}
+
+// The cart item at its current price; None if its product or variant is gone
+fn priced_line(cart_item: &CartItem, price_list_id: Option<u64>, now: u64) -> Option<CartLine> {
+    let product = store::get_product_by_id(cart_item.product_id)?;
+    let variant = match cart_item.variant_id {
+        Some(variant_id) => Some(
+            product
+                .variants
+                .iter()
+                .flatten()
+                .find(|variant| variant.id == variant_id)?
+                .clone(),
+        ),
+        None => None,
+    };
+    let price = pricing::item_price(
+        price_list_id,
+        &product,
+        variant.as_ref(),
+        cart_item.quantity,
+        now,
+    )
+    .price_per_unit;
+    Some(CartLine {
+        product_id: cart_item.product_id,
+        variant_id: cart_item.variant_id,
+        name: product.name,
+        variant_label: variant.map(|variant| variant.label),
+        unit: product.unit,
+        quantity: cart_item.quantity,
+        price_per_unit: price,
+        previous_price_per_unit: (price != cart_item.price_per_unit)
+            .then_some(cart_item.price_per_unit),
+        line_total: price * cart_item.quantity,
+    })
+}
+
+/// Takes over the prices at `now`. Returns the IDs of the products whose price changed.
+pub fn refresh_prices(cart: &mut Cart, now: u64) -> Vec<u64> {
+    let price_list_id = pricing::customer_price_list_id(&cart.phone_number);
+    let mut changed = Vec::new();
+    for item in cart.items.iter_mut() {
+        if let Some(line) = priced_line(item, price_list_id, now) {
+            if line.price_per_unit != item.price_per_unit {
+                item.price_per_unit = line.price_per_unit;
+                changed.push(item.product_id);
+            }
+        }
+    }
+    changed
+}
+
+/// The cart priced at `now`.
+pub fn view(cart: &Cart, now: u64) -> CartView {
+    let price_list_id = pricing::customer_price_list_id(&cart.phone_number);
+    let lines: Vec<CartLine> = cart
+        .items
+        .iter()
+        .filter_map(|item| priced_line(item, price_list_id, now))
+        .collect();
+    CartView {
+        total_amount: lines.iter().map(|line| line.line_total).sum(),
+        prices_changed: lines
+            .iter()
+            .any(|line| line.previous_price_per_unit.is_some()),
+        lines,
+        updated_at: cart.updated_at,
+    }
+}
@@ -0,0 +1,441 @@
+// Product catalog: categories, tags, search and variants.
+//
+// Categories form a tree through `parent_id` and products belong to at most one of them;
+// filtering by a category includes its subcategories. Tags are free-form lowercase labels.
+// Search matches the words of product names in every language through the inverted index
+// in the store, which finds words that start with or contain the search text.
+//
+// Products with variants are sold in whole packs of one of them, at the pack price, and
+// ordered packs are taken out of the variant's stock. Other products are sold loose, in
+// multiples of their quantity step if they have one.
+use crate::{
+    errors::{CategoryError, FieldError, FieldErrorKind, OrderError, QuantityError},
+    i18n,
+    models::{Category, OrderItem, Product, ProductSearch, ProductSort, ProductVariant},
+    store,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+const MAX_CATEGORY_NAME_LENGTH: usize = 64;
+const MAX_CATEGORY_DEPTH: usize = 4;
+const MAX_TAGS: usize = 10;
+const MAX_TAG_LENGTH: usize = 32;
+const MAX_VARIANTS: usize = 10;
+const MAX_SKU_LENGTH: usize = 32;
+const MAX_VARIANT_LABEL_LENGTH: usize = 32;
+// Tolerance for quantities that are multiples of a step up to floating point error
+const STEP_TOLERANCE: f64 = 1e-6;
+
+/// Tags are matched without regard to case or surrounding spaces.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Normalizes a product's tags and drops duplicates.
+pub fn validate_tags(tags: Vec<String>) -> Result<Vec<String>, Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let mut validated: Vec<String> = Vec::new();
+    for (index, tag) in tags.iter().enumerate() {
+        let tag = normalize_tag(tag);
+        if tag.is_empty() || tag.chars().count() > MAX_TAG_LENGTH {
+            errors.push(FieldError::new(
+                format!("tags[{}]", index),
+                FieldErrorKind::OutOfRange,
+                format!("Tags must be between 1 and {} characters.", MAX_TAG_LENGTH),
+            ));
+        } else if !validated.contains(&tag) {
+            validated.push(tag);
+        }
+    }
+    if validated.len() > MAX_TAGS {
+        errors.push(FieldError::new(
+            "tags",
+            FieldErrorKind::OutOfRange,
+            format!("At most {} tags.", MAX_TAGS),
+        ));
+    }
+    if errors.is_empty() {
+        Ok(validated)
+    } else {
+        Err(errors)
+    }
+}
+
+// The category and its ancestors, nearest first. Stops at a missing parent or a loop.
+fn category_path(id: u64, categories: &BTreeMap<u64, Category>) -> Vec<u64> {
+    let mut path = Vec::new();
+    let mut next = Some(id);
+    while let Some(id) = next {
+        if path.contains(&id) || !categories.contains_key(&id) {
+            break;
+        }
+        path.push(id);
+        next = categories[&id].parent_id;
+    }
+    path
+}
+
+fn categories_by_id() -> BTreeMap<u64, Category> {
+    store::get_all_categories()
+        .into_iter()
+        .map(|category| (category.id, category))
+        .collect()
+}
+
+/// The category and all categories below it.
+pub fn category_and_descendants(id: u64) -> BTreeSet<u64> {
+    let categories = categories_by_id();
+    categories
+        .keys()
+        .filter(|category_id| category_path(**category_id, &categories).contains(&id))
+        .copied()
+        .collect()
+}
+
+/// Whether a product in `category_id` falls under one of `category_ids`.
+pub fn in_categories(category_id: Option<u64>, category_ids: &[u64]) -> bool {
+    let Some(category_id) = category_id else {
+        return false;
+    };
+    category_path(category_id, &categories_by_id())
+        .iter()
+        .any(|id| category_ids.contains(id))
+}
+
+/// Checks a new or changed category and trims its name. Names are unique among siblings
+/// and the tree is at most `MAX_CATEGORY_DEPTH` levels deep.
+pub fn validate_category(mut category: Category) -> Result<Category, CategoryError> {
+    category.name = category.name.trim().to_string();
+    let categories = categories_by_id();
+    let mut errors = Vec::new();
+    if category.name.is_empty() {
+        errors.push(FieldError::new(
+            "name",
+            FieldErrorKind::Required,
+            "Name cannot be empty.",
+        ));
+    } else if category.name.chars().count() > MAX_CATEGORY_NAME_LENGTH {
+        errors.push(FieldError::new(
+            "name",
+            FieldErrorKind::TooLong,
+            format!("At most {} characters.", MAX_CATEGORY_NAME_LENGTH),
+        ));
+    } else if categories.values().any(|other| {
+        other.id != category.id
+            && other.parent_id == category.parent_id
+            && other.name.to_lowercase() == category.name.to_lowercase()
+    }) {
+        errors.push(FieldError::new(
+            "name",
+            FieldErrorKind::Duplicate,
+            format!("There already is a category '{}' here.", category.name),
+        ));
+    }
+    if let Some(parent_id) = category.parent_id {
+        let parents = category_path(parent_id, &categories);
+        // Levels below the category, for when it is moved with its subcategories
+        let levels_below = categories
+            .keys()
+            .map(|id| category_path(*id, &categories))
+            .filter_map(|path| path.iter().position(|id| *id == category.id))
+            .max()
+            .unwrap_or(0);
+        if parents.is_empty() {
+            errors.push(FieldError::new(
+                "parent_id",
+                FieldErrorKind::Unsupported,
+                format!("Category {} not found.", parent_id),
+            ));
+        } else if parents.contains(&category.id) {
+            errors.push(FieldError::new(
+                "parent_id",
+                FieldErrorKind::Unsupported,
+                "A category cannot be placed inside itself.",
+            ));
+        } else if parents.len() + 1 + levels_below > MAX_CATEGORY_DEPTH {
+            errors.push(FieldError::new(
+                "parent_id",
+                FieldErrorKind::OutOfRange,
+                format!("Categories are at most {} levels deep.", MAX_CATEGORY_DEPTH),
+            ));
+        }
+    }
+    if errors.is_empty() {
+        Ok(category)
+    } else {
+        Err(CategoryError::InvalidInput(errors))
+    }
+}
+
+/// Removes a category without subcategories. Its products move to the parent category,
+/// or become uncategorized.
+pub fn remove_category(id: u64) -> Result<Category, CategoryError> {
+    let category = store::get_category(id).ok_or(CategoryError::CategoryNotFound(id))?;
+    if store::get_all_categories()
+        .iter()
+        .any(|other| other.parent_id == Some(id))
+    {
+        return Err(CategoryError::HasSubcategories(id));
+    }
+    for product in store::get_all_products() {
+        if product.category_id == Some(id) {
+            let product = Product {
+                category_id: category.parent_id,
+                ..product
+            };
+            let _ = store::update_product(product.id, product);
+        }
+    }
+    store::remove_category(id);
+    Ok(category)
+}
+
+// How many of the search words start a word of the product's names
+fn word_start_matches(product: &Product, words: &[String]) -> usize {
+    let name_words: Vec<String> = std::iter::once(&product.name)
+        .chain(product.translations.iter().flatten().map(|t| &t.name))
+        .flat_map(|name| store::search_words(name))
+        .collect();
+    words
+        .iter()
+        .filter(|word| {
+            name_words
+                .iter()
+                .any(|name_word| name_word.starts_with(*word))
+        })
+        .count()
+}
+
+/// Products matching the search, sorted as requested.
+pub fn search_products(search: ProductSearch) -> Vec<Product> {
+    let words = search
+        .text
+        .as_deref()
+        .map(store::search_words)
+        .unwrap_or_default();
+    let products = match words
+        .iter()
+        .map(|word| store::find_products_by_word(word))
+        .reduce(|a, b| a.intersection(&b).copied().collect())
+    {
+        Some(ids) => ids
+            .into_iter()
+            .filter_map(store::get_product_by_id)
+            .collect(),
+        None => store::get_all_products(),
+    };
+    let categories = search.category_id.map(category_and_descendants);
+    let tags: Vec<String> = search
+        .tags
+        .iter()
+        .flatten()
+        .map(|tag| normalize_tag(tag))
+        .collect();
+
+    let mut results: Vec<(usize, Product)> = products
+        .into_iter()
+        .filter(|product| {
+            categories.as_ref().is_none_or(|categories| {
+                product
+                    .category_id
+                    .is_some_and(|id| categories.contains(&id))
+            })
+        })
+        .filter(|product| {
+            tags.iter()
+                .all(|tag| product.tags.iter().flatten().any(|t| t == tag))
+        })
+        .map(|product| {
+            let rank = word_start_matches(&product, &words);
+            match &search.lang {
+                Some(lang) => (rank, i18n::localize_product(product, lang)),
+                None => (rank, product),
+            }
+        })
+        .collect();
+    let by_name = |a: &Product, b: &Product| a.name.to_lowercase().cmp(&b.name.to_lowercase());
+    match search.sort.unwrap_or(ProductSort::Relevance) {
+        ProductSort::Relevance => {
+            results.sort_by(|(rank_a, a), (rank_b, b)| rank_b.cmp(rank_a).then(by_name(a, b)))
+        }
+        ProductSort::Name => results.sort_by(|(_, a), (_, b)| by_name(a, b)),
+        ProductSort::PriceAscending => {
+            results.sort_by(|(_, a), (_, b)| a.price.total_cmp(&b.price))
+        }
+        ProductSort::PriceDescending => {
+            results.sort_by(|(_, a), (_, b)| b.price.total_cmp(&a.price))
+        }
+    }
+    results.into_iter().map(|(_, product)| product).collect()
+}
+
+/// Checks that `quantity` of a product can be ordered: whole packs of one of its variants,
+/// or for products without variants a multiple of the quantity step. Returns the variant.
+pub fn check_quantity(
+    product: &Product,
+    variant_id: Option<u64>,
+    quantity: f64,
+) -> Result<Option<ProductVariant>, QuantityError> {
+    if !quantity.is_finite() || quantity <= 0.0 {
+        return Err(QuantityError::NotPositive);
+    }
+    let variants = product.variants.as_deref().unwrap_or_default();
+    match variant_id {
+        Some(variant_id) => {
+            let variant = variants
+                .iter()
+                .find(|variant| variant.id == variant_id)
+                .ok_or(QuantityError::VariantNotFound(variant_id))?;
+            if quantity.fract() != 0.0 {
+                return Err(QuantityError::NotWholePacks);
+            }
+            Ok(Some(variant.clone()))
+        }
+        None if !variants.is_empty() => Err(QuantityError::VariantRequired),
+        None => match product.quantity_step {
+            Some(step)
+                if ((quantity / step) - (quantity / step).round()).abs() > STEP_TOLERANCE =>
+            {
+                Err(QuantityError::NotAStep(step))
+            }
+            _ => Ok(None),
+        },
+    }
+}
+
+/// Checks the variants of the product with `product_id` (None for a new product). SKUs are
+/// trimmed, uppercased and unique across products.
+pub fn validate_variants(
+    product_id: Option<u64>,
+    variants: Vec<ProductVariant>,
+) -> Result<Vec<ProductVariant>, Vec<FieldError>> {
+    let other_skus: BTreeSet<String> = store::get_all_products()
+        .into_iter()
+        .filter(|product| Some(product.id) != product_id)
+        .flat_map(|product| product.variants.unwrap_or_default())
+        .map(|variant| variant.sku)
+        .collect();
+    let mut errors = Vec::new();
+    if variants.len() > MAX_VARIANTS {
+        errors.push(FieldError::new(
+            "variants",
+            FieldErrorKind::OutOfRange,
+            format!("At most {} variants.", MAX_VARIANTS),
+        ));
+    }
+    let mut validated: Vec<ProductVariant> = Vec::new();
+    for (index, variant) in variants.into_iter().enumerate() {
+        let field = |name: &str| format!("variants[{}].{}", index, name);
+        let variant = ProductVariant {
+            sku: variant.sku.trim().to_uppercase(),
+            label: variant.label.trim().to_string(),
+            ..variant
+        };
+        if validated.iter().any(|other| other.id == variant.id) {
+            errors.push(FieldError::new(
+                field("id"),
+                FieldErrorKind::Duplicate,
+                format!("Variant {} is listed more than once.", variant.id),
+            ));
+        }
+        if variant.sku.is_empty() || variant.sku.chars().count() > MAX_SKU_LENGTH {
+            errors.push(FieldError::new(
+                field("sku"),
+                FieldErrorKind::OutOfRange,
+                format!("SKUs must be between 1 and {} characters.", MAX_SKU_LENGTH),
+            ));
+        } else if other_skus.contains(&variant.sku)
+            || validated.iter().any(|other| other.sku == variant.sku)
+        {
+            errors.push(FieldError::new(
+                field("sku"),
+                FieldErrorKind::Duplicate,
+                format!("SKU {} is already in use.", variant.sku),
+            ));
+        }
+        if variant.label.is_empty() || variant.label.chars().count() > MAX_VARIANT_LABEL_LENGTH {
+            errors.push(FieldError::new(
+                field("label"),
+                FieldErrorKind::OutOfRange,
+                format!(
+                    "Labels must be between 1 and {} characters.",
+                    MAX_VARIANT_LABEL_LENGTH
+                ),
+            ));
+        }
+        if !variant.pack_size.is_finite() || variant.pack_size <= 0.0 {
+            errors.push(FieldError::new(
+                field("pack_size"),
+                FieldErrorKind::OutOfRange,
+                format!("Invalid pack size {}.", variant.pack_size),
+            ));
+        }
+        if !variant.price.is_finite() || variant.price < 0.0 {
+            errors.push(FieldError::new(
+                field("price"),
+                FieldErrorKind::OutOfRange,
+                format!("Invalid price {}.", variant.price),
+            ));
+        }
+        validated.push(variant);
+    }
+    if errors.is_empty() {
+        Ok(validated)
+    } else {
+        Err(errors)
+    }
+}
+
+// Adds `change` packs to the stock of the ordered variants, failing without changes if a
+// variant would go below zero. Variants without a stock count are left alone.
+fn adjust_stock(items: &[OrderItem], change: f64) -> Result<(), OrderError> {
+    let mut packs: BTreeMap<(u64, u64), f64> = BTreeMap::new();
+    for item in items {
+        if let Some(variant_id) = item.variant_id {
+            *packs.entry((item.product_id, variant_id)).or_default() += item.quantity;
+        }
+    }
+    let mut updated: BTreeMap<u64, Product> = BTreeMap::new();
+    for ((product_id, variant_id), quantity) in packs {
+        let Some(mut product) = updated
+            .remove(&product_id)
+            .or_else(|| store::get_product_by_id(product_id))
+        else {
+            continue;
+        };
+        if let Some(variant) = product
+            .variants
+            .iter_mut()
+            .flatten()
+            .find(|variant| variant.id == variant_id)
+        {
+            if let Some(stock) = variant.stock {
+                let new_stock = stock as f64 + change * quantity;
+                if new_stock < 0.0 {
+                    return Err(OrderError::OutOfStock {
+                        product_id,
+                        variant_id,
+                        available: stock,
+                    });
+                }
+                variant.stock = Some(new_stock as u64);
+            }
+        }
+        updated.insert(product_id, product);
+    }
+    for (product_id, product) in updated {
+        let _ = store::update_product(product_id, product);
+    }
+    Ok(())
+}
+
+/// Takes the ordered packs out of stock, or fails without changes if there aren't enough.
+pub fn take_stock(items: &[OrderItem]) -> Result<(), OrderError> {
+    adjust_stock(items, -1.0)
+}
+
+/// Puts the packs of a cancelled order back in stock.
+pub fn restock(items: &[OrderItem]) {
+    // Adding to the stock can't fail
+    let _ = adjust_stock(items, 1.0);
+}
@@ -0,0 +1,71 @@
+// Certified product catalogue.
+//
+// Every product is kept as a leaf of a Merkle tree (product ID -> Candid-encoded product)
+// under the label "products". The root hash is registered with `set_certified_data`, so a
+// query response can carry a subnet-signed certificate and a witness proving the prices.
+use crate::{errors::CertificationError, models::Product, store};
+use candid::Encode;
+use ic_certification::{labeled, labeled_hash, AsHashTree, HashTree, RbTree};
+use std::cell::RefCell;
+
+const PRODUCTS_LABEL: &[u8] = b"products";
+
+thread_local! {
+    // Rebuilt from the store after an upgrade; the certified data itself is just the root hash
+    static PRODUCT_TREE: RefCell<RbTree<Vec<u8>, Vec<u8>>> = const { RefCell::new(RbTree::new()) };
+}
+
+/// Tree key of a product: its ID as 8 big-endian bytes, so keys sort numerically.
+pub fn product_key(id: u64) -> Vec<u8> {
+    id.to_be_bytes().to_vec()
+}
+
+fn encode_product(product: &Product) -> Vec<u8> {
+    Encode!(product).expect("Failed to encode Product")
+}
+
+/// Root hash that the certificate's `certified_data` must match.
+pub fn certified_root_hash() -> [u8; 32] {
+    PRODUCT_TREE.with(|tree| labeled_hash(PRODUCTS_LABEL, &tree.borrow().root_hash()))
+}
+
+fn publish_root_hash() {
+    // Certified data can only be set from update calls and canister lifecycle hooks
+    #[cfg(target_arch = "wasm32")]
+    ic_cdk::api::set_certified_data(&certified_root_hash());
+}
+
+/// Inserts or replaces a product in the tree and publishes the new root hash.
+pub fn certify_product(product: &Product) {
+    PRODUCT_TREE.with(|tree| {
+        tree.borrow_mut()
+            .insert(product_key(product.id), encode_product(product))
+    });
+    publish_root_hash();
+}
+
+/// Rebuilds the tree from the stored products, e.g. after an upgrade.
+pub fn recertify_all_products() {
+    PRODUCT_TREE.with(|tree| {
+        let mut tree = tree.borrow_mut();
+        *tree = RbTree::new();
+        for product in store::get_all_products() {
+            tree.insert(product_key(product.id), encode_product(&product));
+        }
+    });
+    publish_root_hash();
+}
+
+/// Witness revealing every product leaf, serialised as self-describing CBOR as expected by
+/// the agent libraries.
+pub fn products_witness() -> Result<Vec<u8>, CertificationError> {
+    let tree: HashTree =
+        PRODUCT_TREE.with(|tree| labeled(PRODUCTS_LABEL, tree.borrow().as_hash_tree()));
+    let mut serializer = serde_cbor::Serializer::new(Vec::new());
+    serializer
+        .self_describe()
+        .map_err(|e| CertificationError::EncodingFailed(e.to_string()))?;
+    serde::Serialize::serialize(&tree, &mut serializer)
+        .map_err(|e| CertificationError::EncodingFailed(e.to_string()))?;
+    Ok(serializer.into_inner())
+}
@@ -0,0 +1,71 @@
+// Minimal RFC 4180 CSV support for the HTTP and import/export endpoints.
+
+/// Quotes a field if it contains a separator, quote or line break.
+pub fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Appends one CSV record (terminated by CRLF) to `out`.
+pub fn write_row<S: AsRef<str>>(out: &mut String, fields: &[S]) {
+    let escaped: Vec<String> = fields.iter().map(|f| escape_field(f.as_ref())).collect();
+    out.push_str(&escaped.join(","));
+    out.push_str("\r\n");
+}
+
+/// Parses CSV text into records of fields. Quoted fields may contain separators, doubled
+/// quotes and line breaks; both LF and CRLF line endings are accepted. Blank lines are skipped.
+pub fn parse(input: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut chars = input.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if !(record.len() == 1 && record[0].trim().is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+                line += 1;
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(format!(
+            "Unterminated quoted field starting before line {}",
+            line
+        ));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
@@ -0,0 +1,222 @@
+// Cycle balance monitoring.
+//
+// The `RecordCyclesBalances` job samples every monitored canister through the management
+// canister, keeps the samples as a time series and queues an alert when a balance drops
+// below the configured threshold. Reports project the remaining runway from that series.
+use crate::{
+    date,
+    errors::{CyclesError, FieldError, FieldErrorKind},
+    models::{
+        CyclesMonitorSettings, CyclesReport, CyclesSample, MonitoredCanister, NotificationEvent,
+    },
+    notifications, store, topup,
+};
+use candid::Principal;
+use ic_cdk::api::management_canister::main::{canister_status, CanisterIdRecord};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+pub const MAX_MONITORED_CANISTERS: usize = 20;
+const MAX_LABEL_LENGTH: usize = 64;
+
+/// Validates a monitored canister entry and trims its label.
+pub fn validate_monitored_canister(
+    mut canister: MonitoredCanister,
+) -> Result<MonitoredCanister, CyclesError> {
+    let mut errors = Vec::new();
+    canister.label = canister.label.trim().to_string();
+    if canister.label.is_empty() || canister.label.chars().count() > MAX_LABEL_LENGTH {
+        errors.push(FieldError::new(
+            "label",
+            FieldErrorKind::Required,
+            format!(
+                "Labels must be between 1 and {} characters.",
+                MAX_LABEL_LENGTH
+            ),
+        ));
+    }
+    if canister.canister_id == Principal::anonymous()
+        || canister.canister_id == Principal::management_canister()
+    {
+        errors.push(FieldError::new(
+            "canister_id",
+            FieldErrorKind::Unsupported,
+            "Not a canister ID.",
+        ));
+    }
+    if !errors.is_empty() {
+        return Err(CyclesError::InvalidInput(errors));
+    }
+    let known = store::get_monitored_canisters()
+        .iter()
+        .any(|existing| existing.canister_id == canister.canister_id);
+    if !known && store::get_monitored_canisters().len() >= MAX_MONITORED_CANISTERS {
+        return Err(CyclesError::TooManyCanisters(
+            MAX_MONITORED_CANISTERS as u64,
+        ));
+    }
+    Ok(canister)
+}
+
+/// Stores the monitored canisters passed at install time. Without any, the canister
+/// monitors itself so that a fresh install (e.g. on a local replica) works out of the box.
+pub fn init_monitored_canisters(
+    canisters: Option<Vec<MonitoredCanister>>,
+    self_id: Principal,
+) -> Result<(), CyclesError> {
+    for canister in canisters.unwrap_or_default() {
+        store::set_monitored_canister(validate_monitored_canister(canister)?);
+    }
+    ensure_monitored_canisters(self_id);
+    Ok(())
+}
+
+/// Monitors this canister if the list is empty, e.g. after upgrading from a release that
+/// had the canister IDs hardcoded.
+pub fn ensure_monitored_canisters(self_id: Principal) {
+    if store::get_monitored_canisters().is_empty() {
+        store::set_monitored_canister(MonitoredCanister {
+            canister_id: self_id,
+            label: "Backend".to_string(),
+        });
+    }
+}
+
+pub async fn fetch_sample(canister_id: Principal, now: u64) -> Result<CyclesSample, String> {
+    let (status,) = canister_status(CanisterIdRecord { canister_id })
+        .await
+        .map_err(|(code, msg)| {
+            format!(
+                "Failed to get status for {}: code={:?}, message={}",
+                canister_id, code, msg
+            )
+        })?;
+    let too_large = |field: &str| format!("{} of {} out of range", field, canister_id);
+    Ok(CyclesSample {
+        canister_id,
+        timestamp: now,
+        cycles: status
+            .cycles
+            .0
+            .try_into()
+            .map_err(|_| too_large("Cycles"))?,
+        memory_size: status
+            .memory_size
+            .0
+            .try_into()
+            .map_err(|_| too_large("Memory size"))?,
+        idle_cycles_burned_per_day: status
+            .idle_cycles_burned_per_day
+            .0
+            .try_into()
+            .map_err(|_| too_large("Idle burn"))?,
+        freezing_threshold_seconds: status
+            .settings
+            .freezing_threshold
+            .0
+            .try_into()
+            .map_err(|_| too_large("Freezing threshold"))?,
+        alerted: false,
+    })
+}
+
+/// Samples every monitored canister, queues low-balance alerts and prunes old samples.
+/// A canister that can't be queried is logged and skipped.
+pub async fn record_samples(now: u64) {
+    let settings = store::get_cycles_monitor_settings();
+    for MonitoredCanister { canister_id, label } in store::get_monitored_canisters() {
+        let mut sample = match fetch_sample(canister_id, now).await {
+            Ok(sample) => sample,
+            Err(e) => {
+                ic_cdk::println!("Cycles sample for {} failed: {}", label, e);
+                continue;
+            }
+        };
+        if should_alert(&settings, &sample, now) {
+            sample.alerted = true;
+            notifications::enqueue_alert(
+                settings.alert_phone_number.clone(),
+                NotificationEvent::LowCyclesBalance {
+                    canister_id,
+                    cycles: sample.cycles,
+                },
+                format!(
+                    "{} canister is low on cycles: {} left (threshold {}).",
+                    label, sample.cycles, settings.low_balance_threshold
+                ),
+            );
+        }
+        let cycles = sample.cycles;
+        store::add_cycles_sample(sample);
+        topup::auto_top_up(canister_id, cycles, now).await;
+    }
+
+    let retention = settings.retention_days * SECONDS_PER_DAY * date::NANOS_PER_SECOND;
+    store::remove_cycles_samples_before(now.saturating_sub(retention));
+}
+
+/// Alerts are repeated at most once per `alert_interval_seconds` for the same canister.
+fn should_alert(settings: &CyclesMonitorSettings, sample: &CyclesSample, now: u64) -> bool {
+    if sample.cycles >= settings.low_balance_threshold {
+        return false;
+    }
+    let since = now.saturating_sub(settings.alert_interval_seconds * date::NANOS_PER_SECOND);
+    !store::get_cycles_samples(sample.canister_id, since)
+        .iter()
+        .any(|previous| previous.alerted)
+}
+
+/// Cycles burned per day over `samples` (oldest first). Increases are top-ups and are not
+/// counted; the result is never below the idle burn reported by the latest sample.
+pub fn burn_per_day(samples: &[CyclesSample]) -> u128 {
+    let idle_burn = samples
+        .last()
+        .map(|sample| sample.idle_cycles_burned_per_day)
+        .unwrap_or(0);
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return idle_burn;
+    };
+    let elapsed = last.timestamp.saturating_sub(first.timestamp);
+    if elapsed == 0 {
+        return idle_burn;
+    }
+    let burned: u128 = samples
+        .windows(2)
+        .map(|pair| pair[0].cycles.saturating_sub(pair[1].cycles))
+        .sum();
+    let observed = burned * (SECONDS_PER_DAY * date::NANOS_PER_SECOND) as u128 / elapsed as u128;
+    observed.max(idle_burn)
+}
+
+/// Days until the balance reaches the freezing threshold at `burn_per_day`.
+pub fn projected_days_until_freeze(sample: &CyclesSample, burn_per_day: u128) -> Option<f64> {
+    if burn_per_day == 0 {
+        return None;
+    }
+    // The canister freezes once it can't pay for `freezing_threshold` seconds of idle time
+    let reserve = sample.idle_cycles_burned_per_day * sample.freezing_threshold_seconds as u128
+        / SECONDS_PER_DAY as u128;
+    Some(sample.cycles.saturating_sub(reserve) as f64 / burn_per_day as f64)
+}
+
+/// History and projection for every monitored canister since `since`.
+pub fn reports(since: u64) -> Vec<CyclesReport> {
+    let settings = store::get_cycles_monitor_settings();
+    store::get_monitored_canisters()
+        .into_iter()
+        .map(|MonitoredCanister { canister_id, label }| {
+            let samples = store::get_cycles_samples(canister_id, since);
+            let burn_per_day = burn_per_day(&samples);
+            let latest = samples.last();
+            CyclesReport {
+                canister_id,
+                name: label,
+                burn_per_day,
+                projected_days_until_freeze: latest
+                    .and_then(|sample| projected_days_until_freeze(sample, burn_per_day)),
+                below_threshold: latest
+                    .is_some_and(|sample| sample.cycles < settings.low_balance_threshold),
+                samples,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,78 @@
+// Calendar helpers for IC timestamps (nanoseconds since the Unix epoch).
+// Uses Howard Hinnant's days-from-civil algorithms; valid for all dates after 1970.
+
+pub const NANOS_PER_SECOND: u64 = 1_000_000_000;
+pub const NANOS_PER_DAY: u64 = 86_400 * NANOS_PER_SECOND;
+
+/// Offset of Indian Standard Time from UTC, used when no other offset is given.
+pub const DEFAULT_UTC_OFFSET_MINUTES: i32 = 330;
+
+/// Converts a civil date to the number of days since 1970-01-01.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Converts days since 1970-01-01 to a civil `(year, month, day)`.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Parses a `YYYY-MM-DD` date into days since 1970-01-01.
+pub fn parse_date(value: &str) -> Result<i64, String> {
+    let parts: Vec<&str> = value.trim().split('-').collect();
+    let invalid = || format!("Invalid date '{}', expected YYYY-MM-DD", value);
+    if parts.len() != 3 {
+        return Err(invalid());
+    }
+    let year: i64 = parts[0].parse().map_err(|_| invalid())?;
+    let month: u32 = parts[1].parse().map_err(|_| invalid())?;
+    let day: u32 = parts[2].parse().map_err(|_| invalid())?;
+    if !(1970..=9999).contains(&year) || !(1..=12).contains(&month) || day == 0 {
+        return Err(invalid());
+    }
+    let days = days_from_civil(year, month, day);
+    // Rejects e.g. 2024-02-30, which would silently roll over into March
+    if civil_from_days(days) != (year, month, day) {
+        return Err(invalid());
+    }
+    Ok(days)
+}
+
+/// Returns the `[start, end)` timestamp range of a local calendar day.
+pub fn day_range(days: i64, utc_offset_minutes: i32) -> (u64, u64) {
+    let start_seconds = days * 86_400 - utc_offset_minutes as i64 * 60;
+    let start = (start_seconds.max(0) as u64) * NANOS_PER_SECOND;
+    (start, start + NANOS_PER_DAY)
+}
+
+/// Formats a timestamp as local `YYYY-MM-DD HH:MM`.
+pub fn format_timestamp(timestamp: u64, utc_offset_minutes: i32) -> String {
+    let seconds = (timestamp / NANOS_PER_SECOND) as i64 + utc_offset_minutes as i64 * 60;
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    let second_of_day = seconds.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        second_of_day / 3600,
+        (second_of_day % 3600) / 60
+    )
+}
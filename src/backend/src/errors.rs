@@ -1,19 +1,679 @@
+use candid::Principal;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What is wrong with a single input field.
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum FieldErrorKind {
+    Required,
+    OutOfRange,
+    TooLong,
+    Duplicate,
+    Unsupported,
+}
+
+/// A validation failure for one field of the input, e.g. `items[2].quantity`.
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub kind: FieldErrorKind,
+    pub message: String, // English details, for logs and admin tools
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, kind: FieldErrorKind, message: impl Into<String>) -> Self {
+        FieldError {
+            field: field.into(),
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// All field errors in one line, for callers that report errors as text (CSV imports).
+pub fn describe_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Errors of the stable stores. Not returned by endpoints directly: each endpoint maps them
+/// into its own error type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StoreError {
+    NotFound(u64),
+    TooLarge { size: u64, max: u64 },
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StoreError::NotFound(id) => write!(f, "Record {} not found.", id),
+            StoreError::TooLarge { size, max } => {
+                write!(f, "Record is too large ({} bytes, at most {}).", size, max)
+            }
+        }
+    }
+}
+
+/// Why a quantity of a product can't be ordered. Not returned by endpoints directly:
+/// carts and orders map it into their own error types.
+#[derive(Clone, Debug, PartialEq)]
+pub enum QuantityError {
+    NotPositive,
+    VariantRequired,
+    VariantNotFound(u64),
+    NotWholePacks,
+    NotAStep(f64), // Quantity step of the product
+}
+
+impl fmt::Display for QuantityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QuantityError::NotPositive => write!(f, "Quantities must be above 0."),
+            QuantityError::VariantRequired => write!(f, "This product is sold in packs only."),
+            QuantityError::VariantNotFound(id) => write!(f, "Variant {} not found.", id),
+            QuantityError::NotWholePacks => write!(f, "Packs are ordered in whole numbers."),
+            QuantityError::NotAStep(step) => write!(f, "Quantities must be multiples of {}.", step),
+        }
+    }
+}
 
 #[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
 pub enum GetUserDataError {
     AnonymousCaller,
     DidntFindUserData,
     FailedToAddToList,
+    InvalidPhoneNumber(PhoneNumberError),
 }
 
 #[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
 pub enum OrderError {
-    InvalidInput(String),
+    InvalidInput(Vec<FieldError>),
     UserProfileNotFound,
     InvalidProductInOrder(u64),
     OrderNotFound,
     AccessDenied,
     StorageError(String),
     CannotCancelOrder(String),
+    CannotEditOrder(String),
+    InvalidPhoneNumber(PhoneNumberError),
+    AddressNotFound(u64),
+    PhoneNotVerified,
+    PricesChanged(Vec<u64>), // Products in the cart whose price changed, now updated
+    InvalidCoupon(CouponError),
+    VariantNotFound {
+        product_id: u64,
+        variant_id: u64,
+    },
+    OutOfStock {
+        product_id: u64,
+        variant_id: u64,
+        available: u64,
+    },
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum DataDeletionError {
+    InvalidInput(Vec<FieldError>),
+    InvalidPhoneNumber(PhoneNumberError),
+    ProfileNotFound,
+    AlreadyAnonymised,
+    AlreadyRequested,
+    HasActiveOrders(Vec<u64>),
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum PhoneNumberError {
+    Empty,
+    InvalidCharacter(String),
+    InvalidLength(u8), // Number of digits found
+    InvalidCountryCode,
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum ProfileError {
+    InvalidInput(Vec<FieldError>),
+    InvalidPhoneNumber(PhoneNumberError),
+    AlreadyExists(String), // Normalised phone number of the existing profile
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum AddressError {
+    InvalidInput(Vec<FieldError>),
+    InvalidPhoneNumber(PhoneNumberError),
+    ProfileNotFound,
+    AddressNotFound(u64),
+    TooManyAddresses(u64), // Maximum number of saved addresses per customer
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum CartError {
+    InvalidInput(Vec<FieldError>),
+    InvalidPhoneNumber(PhoneNumberError),
+    ProfileNotFound,
+    ProductNotFound(u64),
+    ItemNotInCart(u64),
+    TooManyItems(u64), // Maximum number of different products in a cart
+    VariantNotFound { product_id: u64, variant_id: u64 },
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum CouponError {
+    InvalidInput(Vec<FieldError>),
+    CouponNotFound(String),
+    Disabled,
+    NotYetValid,
+    Expired,
+    UsageLimitReached,
+    CustomerLimitReached,
+    NotApplicable, // None of the ordered products is covered by the coupon
+    MinimumOrderAmount(f64),
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum PriceListError {
+    InvalidInput(Vec<FieldError>),
+    PriceListNotFound(u64),
+    InvalidPhoneNumber(PhoneNumberError),
+    ProfileNotFound,
+    AlreadyAssigned(String), // Customer a custom price list is assigned to
+    InUse(u64),              // Number of customers the price list is assigned to
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum ProductError {
+    InvalidInput(Vec<FieldError>),
+    ProductNotFound(u64),
+    PriceChangeNotFound {
+        product_id: u64,
+        effective_from: u64,
+    },
+    VariantNotFound {
+        product_id: u64,
+        variant_id: u64,
+    },
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum CategoryError {
+    InvalidInput(Vec<FieldError>),
+    CategoryNotFound(u64),
+    HasSubcategories(u64),
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum ImageError {
+    InvalidInput(Vec<FieldError>),
+    UploadNotFound(String),
+    TooLarge { size: u64, max: u64 },
+    ChecksumMismatch, // The content does not have the given SHA-256 hash
+    ImageNotFound(u64),
+    InUse(u64), // Product showing the image
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum PhoneChangeError {
+    InvalidPhoneNumber(PhoneNumberError),
+    ProfileNotFound,
+    SameNumber,
+    NumberAlreadyInUse(String),
+    RequestNotFound,
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum OtpError {
+    InvalidPhoneNumber(PhoneNumberError),
+    ProfileNotFound,
+    NotConfigured,
+    PhoneChangeNotRequested,
+    TooManyRequests { retry_after_seconds: u64 },
+    RandomnessUnavailable(String),
+    SendFailed(String),
+    NoPendingCode,
+    Expired,
+    InvalidCode { attempts_left: u32 },
+    TooManyAttempts,
+    PhoneChangeFailed(PhoneChangeError),
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum CertificationError {
+    NotInQuery, // Certificates are only available in query calls
+    EncodingFailed(String),
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum SetupError {
+    AlreadyInitialized,
+    StorageError(String),
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum SettingsError {
+    InvalidInput(Vec<FieldError>),
+    StorageError(String),
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum NotificationError {
+    NotificationNotFound(u64),
+    NotFailed(u64), // Only failed notifications can be retried
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum JobError {
+    InvalidInput(Vec<FieldError>),
+    JobNotFound(u64),
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum SnapshotError {
+    EncodingFailed(String),
+    NotExported,
+    UnsupportedVersion(u32),
+    InvalidManifest,
+    NotFresh, // Snapshots are only imported into a freshly installed canister
+    NoImportInProgress,
+    WrongSnapshot(u64), // Chunk that belongs to a different snapshot
+    ChunkOutOfRange(u64),
+    ChecksumMismatch(Option<u64>), // Chunk with the wrong checksum, None for the whole snapshot
+    Incomplete { received: u64, expected: u64 },
+    InvalidSnapshot(String), // Content that cannot be decoded or restored
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum CsvError {
+    InvalidInput(Vec<FieldError>),
+    InvalidCsv(String), // Unparseable file, missing header or columns
+    UploadNotFound(String),
+    UploadTooLarge(u64), // Maximum size of an upload in bytes
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum ApiKeyError {
+    InvalidInput(Vec<FieldError>),
+    RandomnessUnavailable(String),
+    ApiKeyNotFound(u64),
+    AlreadyRevoked(u64),
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum CyclesError {
+    InvalidInput(Vec<FieldError>),
+    NotMonitored(Principal),
+    TooManyCanisters(u64), // Maximum number of monitored canisters
+    StorageError(String),
+}
+
+#[derive(candid::CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum TopUpError {
+    InvalidInput(Vec<FieldError>),
+    NotMonitored(Principal),
+    TopUpNotFound(u64),
+    NotRetryable(u64), // Only top-ups whose ICP was transferred can be retried
+    // The ICP was transferred but the cycles were not minted yet; retry_top_up_admin retries
+    NotMinted {
+        top_up_id: u64,
+        block_index: u64,
+        reason: String,
+    },
+    Failed {
+        top_up_id: u64,
+        reason: String,
+    },
+    StorageError(String),
+}
+
+/// Machine-readable code of an error, used by the frontend to look up a localized message
+/// (see `get_error_messages`). Errors wrapping another error report the inner code.
+pub trait ErrorCode {
+    fn code(&self) -> &'static str;
+}
+
+impl ErrorCode for FieldErrorKind {
+    fn code(&self) -> &'static str {
+        match self {
+            FieldErrorKind::Required => "FIELD_REQUIRED",
+            FieldErrorKind::OutOfRange => "FIELD_OUT_OF_RANGE",
+            FieldErrorKind::TooLong => "FIELD_TOO_LONG",
+            FieldErrorKind::Duplicate => "FIELD_DUPLICATE",
+            FieldErrorKind::Unsupported => "FIELD_UNSUPPORTED",
+        }
+    }
+}
+
+impl ErrorCode for FieldError {
+    fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+}
+
+impl ErrorCode for GetUserDataError {
+    fn code(&self) -> &'static str {
+        match self {
+            GetUserDataError::AnonymousCaller => "ANONYMOUS_CALLER",
+            GetUserDataError::DidntFindUserData => "PROFILE_NOT_FOUND",
+            GetUserDataError::FailedToAddToList => "FAILED_TO_ADD_TO_LIST",
+            GetUserDataError::InvalidPhoneNumber(e) => e.code(),
+        }
+    }
+}
+
+impl ErrorCode for OrderError {
+    fn code(&self) -> &'static str {
+        match self {
+            OrderError::InvalidInput(_) => "INVALID_INPUT",
+            OrderError::UserProfileNotFound => "PROFILE_NOT_FOUND",
+            OrderError::InvalidProductInOrder(_) => "INVALID_PRODUCT_IN_ORDER",
+            OrderError::OrderNotFound => "ORDER_NOT_FOUND",
+            OrderError::AccessDenied => "ACCESS_DENIED",
+            OrderError::StorageError(_) => "STORAGE_ERROR",
+            OrderError::CannotCancelOrder(_) => "CANNOT_CANCEL_ORDER",
+            OrderError::CannotEditOrder(_) => "CANNOT_EDIT_ORDER",
+            OrderError::InvalidPhoneNumber(e) => e.code(),
+            OrderError::AddressNotFound(_) => "ADDRESS_NOT_FOUND",
+            OrderError::PhoneNotVerified => "PHONE_NOT_VERIFIED",
+            OrderError::PricesChanged(_) => "CART_PRICES_CHANGED",
+            OrderError::InvalidCoupon(e) => e.code(),
+            OrderError::VariantNotFound { .. } => "VARIANT_NOT_FOUND",
+            OrderError::OutOfStock { .. } => "OUT_OF_STOCK",
+        }
+    }
+}
+
+impl ErrorCode for DataDeletionError {
+    fn code(&self) -> &'static str {
+        match self {
+            DataDeletionError::InvalidInput(_) => "INVALID_INPUT",
+            DataDeletionError::InvalidPhoneNumber(e) => e.code(),
+            DataDeletionError::ProfileNotFound => "PROFILE_NOT_FOUND",
+            DataDeletionError::AlreadyAnonymised => "ALREADY_ANONYMISED",
+            DataDeletionError::AlreadyRequested => "DELETION_ALREADY_REQUESTED",
+            DataDeletionError::HasActiveOrders(_) => "HAS_ACTIVE_ORDERS",
+        }
+    }
+}
+
+impl ErrorCode for PhoneNumberError {
+    fn code(&self) -> &'static str {
+        match self {
+            PhoneNumberError::Empty => "PHONE_EMPTY",
+            PhoneNumberError::InvalidCharacter(_) => "PHONE_INVALID_CHARACTER",
+            PhoneNumberError::InvalidLength(_) => "PHONE_INVALID_LENGTH",
+            PhoneNumberError::InvalidCountryCode => "PHONE_INVALID_COUNTRY_CODE",
+        }
+    }
+}
+
+impl ErrorCode for ProfileError {
+    fn code(&self) -> &'static str {
+        match self {
+            ProfileError::InvalidInput(_) => "INVALID_INPUT",
+            ProfileError::InvalidPhoneNumber(e) => e.code(),
+            ProfileError::AlreadyExists(_) => "PROFILE_ALREADY_EXISTS",
+        }
+    }
+}
+
+impl ErrorCode for AddressError {
+    fn code(&self) -> &'static str {
+        match self {
+            AddressError::InvalidInput(_) => "INVALID_INPUT",
+            AddressError::InvalidPhoneNumber(e) => e.code(),
+            AddressError::ProfileNotFound => "PROFILE_NOT_FOUND",
+            AddressError::AddressNotFound(_) => "ADDRESS_NOT_FOUND",
+            AddressError::TooManyAddresses(_) => "TOO_MANY_ADDRESSES",
+        }
+    }
+}
+
+impl ErrorCode for CartError {
+    fn code(&self) -> &'static str {
+        match self {
+            CartError::InvalidInput(_) => "INVALID_INPUT",
+            CartError::InvalidPhoneNumber(e) => e.code(),
+            CartError::ProfileNotFound => "PROFILE_NOT_FOUND",
+            CartError::ProductNotFound(_) => "PRODUCT_NOT_FOUND",
+            CartError::ItemNotInCart(_) => "CART_ITEM_NOT_FOUND",
+            CartError::TooManyItems(_) => "CART_TOO_MANY_ITEMS",
+            CartError::VariantNotFound { .. } => "VARIANT_NOT_FOUND",
+        }
+    }
+}
+
+impl ErrorCode for CouponError {
+    fn code(&self) -> &'static str {
+        match self {
+            CouponError::InvalidInput(_) => "INVALID_INPUT",
+            CouponError::CouponNotFound(_) => "COUPON_NOT_FOUND",
+            CouponError::Disabled => "COUPON_DISABLED",
+            CouponError::NotYetValid => "COUPON_NOT_YET_VALID",
+            CouponError::Expired => "COUPON_EXPIRED",
+            CouponError::UsageLimitReached => "COUPON_USAGE_LIMIT_REACHED",
+            CouponError::CustomerLimitReached => "COUPON_CUSTOMER_LIMIT_REACHED",
+            CouponError::NotApplicable => "COUPON_NOT_APPLICABLE",
+            CouponError::MinimumOrderAmount(_) => "COUPON_MINIMUM_NOT_REACHED",
+        }
+    }
+}
+
+impl ErrorCode for PriceListError {
+    fn code(&self) -> &'static str {
+        match self {
+            PriceListError::InvalidInput(_) => "INVALID_INPUT",
+            PriceListError::PriceListNotFound(_) => "PRICE_LIST_NOT_FOUND",
+            PriceListError::InvalidPhoneNumber(e) => e.code(),
+            PriceListError::ProfileNotFound => "PROFILE_NOT_FOUND",
+            PriceListError::AlreadyAssigned(_) => "PRICE_LIST_ALREADY_ASSIGNED",
+            PriceListError::InUse(_) => "PRICE_LIST_IN_USE",
+        }
+    }
+}
+
+impl ErrorCode for ProductError {
+    fn code(&self) -> &'static str {
+        match self {
+            ProductError::InvalidInput(_) => "INVALID_INPUT",
+            ProductError::ProductNotFound(_) => "PRODUCT_NOT_FOUND",
+            ProductError::PriceChangeNotFound { .. } => "PRICE_CHANGE_NOT_FOUND",
+            ProductError::VariantNotFound { .. } => "VARIANT_NOT_FOUND",
+        }
+    }
+}
+
+impl ErrorCode for CategoryError {
+    fn code(&self) -> &'static str {
+        match self {
+            CategoryError::InvalidInput(_) => "INVALID_INPUT",
+            CategoryError::CategoryNotFound(_) => "CATEGORY_NOT_FOUND",
+            CategoryError::HasSubcategories(_) => "CATEGORY_HAS_SUBCATEGORIES",
+        }
+    }
+}
+
+impl ErrorCode for ImageError {
+    fn code(&self) -> &'static str {
+        match self {
+            ImageError::InvalidInput(_) => "INVALID_INPUT",
+            ImageError::UploadNotFound(_) => "IMAGE_UPLOAD_NOT_FOUND",
+            ImageError::TooLarge { .. } => "IMAGE_TOO_LARGE",
+            ImageError::ChecksumMismatch => "IMAGE_CHECKSUM_MISMATCH",
+            ImageError::ImageNotFound(_) => "IMAGE_NOT_FOUND",
+            ImageError::InUse(_) => "IMAGE_IN_USE",
+        }
+    }
+}
+
+impl ErrorCode for PhoneChangeError {
+    fn code(&self) -> &'static str {
+        match self {
+            PhoneChangeError::InvalidPhoneNumber(e) => e.code(),
+            PhoneChangeError::ProfileNotFound => "PROFILE_NOT_FOUND",
+            PhoneChangeError::SameNumber => "PHONE_SAME_NUMBER",
+            PhoneChangeError::NumberAlreadyInUse(_) => "PHONE_ALREADY_IN_USE",
+            PhoneChangeError::RequestNotFound => "PHONE_CHANGE_NOT_REQUESTED",
+        }
+    }
+}
+
+impl ErrorCode for OtpError {
+    fn code(&self) -> &'static str {
+        match self {
+            OtpError::InvalidPhoneNumber(e) => e.code(),
+            OtpError::ProfileNotFound => "PROFILE_NOT_FOUND",
+            OtpError::NotConfigured => "OTP_NOT_CONFIGURED",
+            OtpError::PhoneChangeNotRequested => "PHONE_CHANGE_NOT_REQUESTED",
+            OtpError::TooManyRequests { .. } => "OTP_TOO_MANY_REQUESTS",
+            OtpError::RandomnessUnavailable(_) => "OTP_SEND_FAILED",
+            OtpError::SendFailed(_) => "OTP_SEND_FAILED",
+            OtpError::NoPendingCode => "OTP_NO_PENDING_CODE",
+            OtpError::Expired => "OTP_EXPIRED",
+            OtpError::InvalidCode { .. } => "OTP_INVALID_CODE",
+            OtpError::TooManyAttempts => "OTP_TOO_MANY_ATTEMPTS",
+            OtpError::PhoneChangeFailed(e) => e.code(),
+        }
+    }
+}
+
+impl ErrorCode for CertificationError {
+    fn code(&self) -> &'static str {
+        match self {
+            CertificationError::NotInQuery => "CERTIFICATE_UNAVAILABLE",
+            CertificationError::EncodingFailed(_) => "CERTIFICATE_UNAVAILABLE",
+        }
+    }
+}
+
+impl ErrorCode for SetupError {
+    fn code(&self) -> &'static str {
+        match self {
+            SetupError::AlreadyInitialized => "ALREADY_INITIALIZED",
+            SetupError::StorageError(_) => "STORAGE_ERROR",
+        }
+    }
+}
+
+impl ErrorCode for SettingsError {
+    fn code(&self) -> &'static str {
+        match self {
+            SettingsError::InvalidInput(_) => "INVALID_INPUT",
+            SettingsError::StorageError(_) => "STORAGE_ERROR",
+        }
+    }
+}
+
+impl ErrorCode for NotificationError {
+    fn code(&self) -> &'static str {
+        match self {
+            NotificationError::NotificationNotFound(_) => "NOTIFICATION_NOT_FOUND",
+            NotificationError::NotFailed(_) => "NOTIFICATION_NOT_FAILED",
+        }
+    }
+}
+
+impl ErrorCode for JobError {
+    fn code(&self) -> &'static str {
+        match self {
+            JobError::InvalidInput(_) => "INVALID_INPUT",
+            JobError::JobNotFound(_) => "JOB_NOT_FOUND",
+        }
+    }
+}
+
+impl ErrorCode for SnapshotError {
+    fn code(&self) -> &'static str {
+        match self {
+            SnapshotError::EncodingFailed(_) => "SNAPSHOT_ENCODING_FAILED",
+            SnapshotError::NotExported => "SNAPSHOT_NOT_EXPORTED",
+            SnapshotError::UnsupportedVersion(_) => "SNAPSHOT_UNSUPPORTED_VERSION",
+            SnapshotError::InvalidManifest => "SNAPSHOT_INVALID_MANIFEST",
+            SnapshotError::NotFresh => "SNAPSHOT_CANISTER_NOT_FRESH",
+            SnapshotError::NoImportInProgress => "SNAPSHOT_NO_IMPORT",
+            SnapshotError::WrongSnapshot(_) => "SNAPSHOT_CHUNK_MISMATCH",
+            SnapshotError::ChunkOutOfRange(_) => "SNAPSHOT_CHUNK_OUT_OF_RANGE",
+            SnapshotError::ChecksumMismatch(_) => "SNAPSHOT_CHECKSUM_MISMATCH",
+            SnapshotError::Incomplete { .. } => "SNAPSHOT_INCOMPLETE",
+            SnapshotError::InvalidSnapshot(_) => "SNAPSHOT_INVALID",
+        }
+    }
+}
+
+impl ErrorCode for CsvError {
+    fn code(&self) -> &'static str {
+        match self {
+            CsvError::InvalidInput(_) => "INVALID_INPUT",
+            CsvError::InvalidCsv(_) => "CSV_INVALID",
+            CsvError::UploadNotFound(_) => "CSV_UPLOAD_NOT_FOUND",
+            CsvError::UploadTooLarge(_) => "CSV_UPLOAD_TOO_LARGE",
+        }
+    }
+}
+
+impl ErrorCode for ApiKeyError {
+    fn code(&self) -> &'static str {
+        match self {
+            ApiKeyError::InvalidInput(_) => "INVALID_INPUT",
+            ApiKeyError::RandomnessUnavailable(_) => "API_KEY_CREATION_FAILED",
+            ApiKeyError::ApiKeyNotFound(_) => "API_KEY_NOT_FOUND",
+            ApiKeyError::AlreadyRevoked(_) => "API_KEY_ALREADY_REVOKED",
+        }
+    }
+}
+
+impl ErrorCode for CyclesError {
+    fn code(&self) -> &'static str {
+        match self {
+            CyclesError::InvalidInput(_) => "INVALID_INPUT",
+            CyclesError::NotMonitored(_) => "CANISTER_NOT_MONITORED",
+            CyclesError::TooManyCanisters(_) => "TOO_MANY_MONITORED_CANISTERS",
+            CyclesError::StorageError(_) => "STORAGE_ERROR",
+        }
+    }
+}
+
+impl ErrorCode for TopUpError {
+    fn code(&self) -> &'static str {
+        match self {
+            TopUpError::InvalidInput(_) => "INVALID_INPUT",
+            TopUpError::NotMonitored(_) => "CANISTER_NOT_MONITORED",
+            TopUpError::TopUpNotFound(_) => "TOP_UP_NOT_FOUND",
+            TopUpError::NotRetryable(_) => "TOP_UP_NOT_RETRYABLE",
+            TopUpError::NotMinted { .. } => "TOP_UP_NOT_MINTED",
+            TopUpError::Failed { .. } => "TOP_UP_FAILED",
+            TopUpError::StorageError(_) => "STORAGE_ERROR",
+        }
+    }
+}
+
+impl From<StoreError> for OrderError {
+    fn from(error: StoreError) -> Self {
+        match error {
+            StoreError::NotFound(_) => OrderError::OrderNotFound,
+            other => OrderError::StorageError(other.to_string()),
+        }
+    }
+}
+
+impl From<StoreError> for ProductError {
+    fn from(error: StoreError) -> Self {
+        match error {
+            StoreError::NotFound(id) => ProductError::ProductNotFound(id),
+            too_large @ StoreError::TooLarge { .. } => {
+                ProductError::InvalidInput(vec![FieldError::new(
+                    "product",
+                    FieldErrorKind::TooLong,
+                    too_large.to_string(),
+                )])
+            }
+        }
+    }
 }
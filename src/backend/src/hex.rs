@@ -0,0 +1,6 @@
+// Hex encoding for hashes shown to users and in HTTP headers.
+
+/// Lower-case hex encoding of `bytes`.
+pub fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
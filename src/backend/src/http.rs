@@ -0,0 +1,223 @@
+use crate::{bulk, date, hex, store};
+use candid::{CandidType, Deserialize};
+use sha2::{Digest, Sha256};
+
+const JSON: &str = "application/json";
+const CSV: &str = "text/csv; charset=utf-8";
+
+/// Request as delivered by the HTTP gateway to `http_request` / `http_request_update`.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// Response returned to the HTTP gateway. `upgrade = Some(true)` asks the gateway to
+/// replay the request as an update call.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct HttpResponse {
+    pub status_code: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub upgrade: Option<bool>,
+}
+
+impl HttpResponse {
+    fn new(status_code: u16, content_type: &str, body: Vec<u8>) -> Self {
+        HttpResponse {
+            status_code,
+            headers: vec![
+                ("Content-Type".to_string(), content_type.to_string()),
+                ("Cache-Control".to_string(), "no-store".to_string()),
+            ],
+            body,
+            upgrade: None,
+        }
+    }
+
+    fn error(status_code: u16, message: &str) -> Self {
+        let body = serde_json::json!({ "error": message }).to_string();
+        Self::new(status_code, JSON, body.into_bytes())
+    }
+
+    fn not_modified(headers: Vec<(String, String)>) -> Self {
+        HttpResponse {
+            status_code: 304,
+            headers,
+            body: Vec::new(),
+            upgrade: None,
+        }
+    }
+
+    fn upgrade() -> Self {
+        HttpResponse {
+            status_code: 200,
+            headers: Vec::new(),
+            body: Vec::new(),
+            upgrade: Some(true),
+        }
+    }
+}
+
+enum Route {
+    Products,
+    OrdersCsv,
+    Image(u64),
+}
+
+/// Splits the request URL into its route and query parameters.
+fn route(request: &HttpRequest) -> Result<(Route, Vec<(String, String)>), HttpResponse> {
+    let (path, query) = request
+        .url
+        .split_once('?')
+        .unwrap_or((request.url.as_str(), ""));
+    let route = match path.trim_end_matches('/') {
+        "/api/products" => Route::Products,
+        "/api/orders.csv" => Route::OrdersCsv,
+        path => match path
+            .strip_prefix("/api/images/")
+            .and_then(|id| id.parse().ok())
+        {
+            Some(id) => Route::Image(id),
+            None => return Err(HttpResponse::error(404, "Not found")),
+        },
+    };
+    if !request.method.eq_ignore_ascii_case("GET") {
+        return Err(HttpResponse::error(405, "Method not allowed"));
+    }
+    let params = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key.to_string(), value.to_string())
+        })
+        .collect();
+    Ok((route, params))
+}
+
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    request
+        .headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
+
+/// Handles a request in a query call. Routes that need to write state (API key usage)
+/// are upgraded to an update call.
+///
+/// Query responses carry no `IC-Certificate` header, so the certifying gateway at
+/// `<canister-id>.icp0.io` rejects them: `/api/products` and `/api/images/<id>` have to be
+/// fetched from `<canister-id>.raw.icp0.io`. Upgraded routes are certified by consensus.
+pub fn handle_query(request: &HttpRequest) -> HttpResponse {
+    match route(request) {
+        Ok((Route::Products, _)) => products_json(),
+        Ok((Route::OrdersCsv, _)) => HttpResponse::upgrade(),
+        Ok((Route::Image(id), _)) => image(request, id),
+        Err(response) => response,
+    }
+}
+
+/// Handles a request that was upgraded to an update call.
+pub fn handle_update(request: &HttpRequest, now: u64) -> HttpResponse {
+    let (route, params) = match route(request) {
+        Ok(routed) => routed,
+        Err(response) => return response,
+    };
+    match route {
+        Route::Products => products_json(),
+        Route::OrdersCsv => {
+            if !authenticate(request, now) {
+                return HttpResponse::error(401, "Missing or invalid API key");
+            }
+            orders_csv(&params)
+        }
+        Route::Image(id) => image(request, id),
+    }
+}
+
+/// Hashes an API key for storage and lookup.
+pub fn hash_api_key(key: &str) -> Vec<u8> {
+    Sha256::digest(key.as_bytes()).to_vec()
+}
+
+/// Accepts `Authorization: Bearer <key>` or `X-Api-Key: <key>` and records the key's use.
+fn authenticate(request: &HttpRequest, now: u64) -> bool {
+    let key = header(request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| header(request, "X-Api-Key"));
+    let Some(key) = key.map(str::trim).filter(|key| !key.is_empty()) else {
+        return false;
+    };
+    match store::find_active_api_key(&hash_api_key(key)) {
+        Some(mut api_key) => {
+            api_key.last_used_at = Some(now);
+            store::put_api_key(api_key);
+            true
+        }
+        None => false,
+    }
+}
+
+fn products_json() -> HttpResponse {
+    match serde_json::to_vec(&store::get_all_products()) {
+        Ok(body) => HttpResponse::new(200, JSON, body),
+        Err(e) => HttpResponse::error(500, &e.to_string()),
+    }
+}
+
+/// An uploaded image. Images never change under their ID, so they may be cached for good;
+/// the ETag is the content hash.
+fn image(request: &HttpRequest, id: u64) -> HttpResponse {
+    let (Some(image), Some(content)) = (store::get_image(id), store::get_image_content(id)) else {
+        return HttpResponse::error(404, "Image not found");
+    };
+    let etag = format!("\"{}\"", hex::encode(&image.sha256));
+    let headers = vec![
+        ("Content-Type".to_string(), image.content_type),
+        (
+            "Cache-Control".to_string(),
+            "public, max-age=31536000, immutable".to_string(),
+        ),
+        ("ETag".to_string(), etag.clone()),
+    ];
+    if header(request, "If-None-Match") == Some(etag.as_str()) {
+        return HttpResponse::not_modified(headers);
+    }
+    HttpResponse {
+        status_code: 200,
+        headers,
+        body: content,
+        upgrade: None,
+    }
+}
+
+/// Orders placed on one local calendar day: `?date=YYYY-MM-DD[&tz_offset=<minutes>]`.
+fn orders_csv(params: &[(String, String)]) -> HttpResponse {
+    let Some(date) = param(params, "date") else {
+        return HttpResponse::error(400, "Missing 'date' parameter (YYYY-MM-DD)");
+    };
+    let days = match date::parse_date(date) {
+        Ok(days) => days,
+        Err(e) => return HttpResponse::error(400, &e),
+    };
+    let utc_offset_minutes = match param(params, "tz_offset").map(str::parse::<i32>) {
+        None => date::DEFAULT_UTC_OFFSET_MINUTES,
+        Some(Ok(offset)) if (-720..=840).contains(&offset) => offset,
+        Some(_) => return HttpResponse::error(400, "Invalid 'tz_offset' parameter (minutes)"),
+    };
+
+    let (start, end) = date::day_range(days, utc_offset_minutes);
+    let body = bulk::export_orders(bulk::orders_between(start, end), utc_offset_minutes);
+    HttpResponse::new(200, CSV, body.into_bytes())
+}
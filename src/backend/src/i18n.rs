@@ -0,0 +1,573 @@
+// Localized content: product texts and error messages in English and Hindi.
+//
+// Products keep their English texts in `name`/`description`/`unit` and carry other languages
+// in `translations`. Errors are identified by the codes from `errors::ErrorCode`; the frontend
+// fetches the message table for its language once and looks codes up in it.
+use crate::{
+    errors::{FieldError, FieldErrorKind},
+    models::{ErrorMessage, Product, ProductTranslation},
+};
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+pub const SUPPORTED_LANGUAGES: &[&str] = &["en", "hi"];
+
+/// Maps a language tag such as "hi", "HI" or "hi-IN" to a supported language code.
+pub fn normalize_language(lang: &str) -> Option<&'static str> {
+    let primary = lang.trim().split(['-', '_']).next().unwrap_or("");
+    SUPPORTED_LANGUAGES
+        .iter()
+        .find(|supported| supported.eq_ignore_ascii_case(primary))
+        .copied()
+}
+
+/// Checks the translations of a product and normalizes their language codes.
+pub fn validate_translations(
+    translations: Vec<ProductTranslation>,
+) -> Result<Vec<ProductTranslation>, Vec<FieldError>> {
+    let mut validated: Vec<ProductTranslation> = Vec::new();
+    let mut errors = Vec::new();
+    for (index, translation) in translations.into_iter().enumerate() {
+        let field = |name: &str| format!("translations[{}].{}", index, name);
+        let lang = match normalize_language(&translation.lang) {
+            Some(lang) if lang != DEFAULT_LANGUAGE => lang,
+            Some(_) => {
+                errors.push(FieldError::new(
+                    field("lang"),
+                    FieldErrorKind::Unsupported,
+                    "English texts go into the product's own name and description.",
+                ));
+                continue;
+            }
+            None => {
+                errors.push(FieldError::new(
+                    field("lang"),
+                    FieldErrorKind::Unsupported,
+                    format!(
+                        "Unsupported language '{}', expected one of: {}",
+                        translation.lang,
+                        SUPPORTED_LANGUAGES.join(", ")
+                    ),
+                ));
+                continue;
+            }
+        };
+        if translation.name.trim().is_empty() {
+            errors.push(FieldError::new(
+                field("name"),
+                FieldErrorKind::Required,
+                format!("The {} name cannot be empty.", lang),
+            ));
+        }
+        if validated.iter().any(|existing| existing.lang == lang) {
+            errors.push(FieldError::new(
+                field("lang"),
+                FieldErrorKind::Duplicate,
+                format!("More than one translation for '{}'.", lang),
+            ));
+            continue;
+        }
+        validated.push(ProductTranslation {
+            lang: lang.to_string(),
+            name: translation.name.trim().to_string(),
+            description: translation.description.trim().to_string(),
+            unit: translation
+                .unit
+                .map(|unit| unit.trim().to_string())
+                .filter(|unit| !unit.is_empty()),
+        });
+    }
+    if errors.is_empty() {
+        Ok(validated)
+    } else {
+        Err(errors)
+    }
+}
+
+/// The product with its texts in `lang`, falling back to English for a missing language.
+/// The translations themselves are left out of the result.
+pub fn localize_product(product: Product, lang: &str) -> Product {
+    let translation = normalize_language(lang).and_then(|lang| {
+        product
+            .translations
+            .iter()
+            .flatten()
+            .find(|translation| translation.lang == lang)
+            .cloned()
+    });
+    let product = Product {
+        translations: None,
+        ..product
+    };
+    match translation {
+        Some(translation) => Product {
+            name: translation.name,
+            description: translation.description,
+            unit: translation.unit.unwrap_or(product.unit.clone()),
+            ..product
+        },
+        None => product,
+    }
+}
+
+// (code, English, Hindi)
+const ERROR_MESSAGES: &[(&str, &str, &str)] = &[
+    (
+        "ANONYMOUS_CALLER",
+        "Please log in to continue.",
+        "आगे बढ़ने के लिए कृपया लॉग इन करें।",
+    ),
+    (
+        "PROFILE_NOT_FOUND",
+        "No profile was found for this phone number.",
+        "इस फ़ोन नंबर के लिए कोई प्रोफ़ाइल नहीं मिली।",
+    ),
+    (
+        "FAILED_TO_ADD_TO_LIST",
+        "Could not be added to the list.",
+        "सूची में नहीं जोड़ा जा सका।",
+    ),
+    (
+        "INVALID_INPUT",
+        "Some of the details entered are invalid.",
+        "दर्ज की गई कुछ जानकारी सही नहीं है।",
+    ),
+    (
+        "INVALID_PRODUCT_IN_ORDER",
+        "The order contains a product that is not available.",
+        "ऑर्डर में ऐसा उत्पाद है जो उपलब्ध नहीं है।",
+    ),
+    (
+        "ORDER_NOT_FOUND",
+        "The order was not found.",
+        "ऑर्डर नहीं मिला।",
+    ),
+    (
+        "ACCESS_DENIED",
+        "You are not allowed to do this.",
+        "आपको यह करने की अनुमति नहीं है।",
+    ),
+    (
+        "STORAGE_ERROR",
+        "Something went wrong while saving. Please try again.",
+        "सहेजते समय कुछ गड़बड़ हो गई। कृपया फिर से कोशिश करें।",
+    ),
+    (
+        "CANNOT_CANCEL_ORDER",
+        "This order can no longer be cancelled.",
+        "यह ऑर्डर अब रद्द नहीं किया जा सकता।",
+    ),
+    (
+        "CANNOT_EDIT_ORDER",
+        "This order can no longer be changed.",
+        "यह ऑर्डर अब बदला नहीं जा सकता।",
+    ),
+    (
+        "PRODUCT_NOT_FOUND",
+        "The product was not found.",
+        "उत्पाद नहीं मिला।",
+    ),
+    (
+        "CART_PRICES_CHANGED",
+        "Some prices in your cart have changed. Please review your cart.",
+        "आपकी कार्ट में कुछ कीमतें बदल गई हैं। कृपया अपनी कार्ट देखें।",
+    ),
+    (
+        "CART_ITEM_NOT_FOUND",
+        "This product is not in your cart.",
+        "यह उत्पाद आपकी कार्ट में नहीं है।",
+    ),
+    (
+        "CART_TOO_MANY_ITEMS",
+        "Your cart is full.",
+        "आपकी कार्ट भर गई है।",
+    ),
+    (
+        "VARIANT_NOT_FOUND",
+        "This pack size is not available.",
+        "यह पैक आकार उपलब्ध नहीं है।",
+    ),
+    (
+        "OUT_OF_STOCK",
+        "Not enough packs are in stock.",
+        "स्टॉक में पर्याप्त पैक नहीं हैं।",
+    ),
+    (
+        "COUPON_NOT_FOUND",
+        "This coupon code is not valid.",
+        "यह कूपन कोड मान्य नहीं है।",
+    ),
+    (
+        "COUPON_DISABLED",
+        "This coupon is no longer available.",
+        "यह कूपन अब उपलब्ध नहीं है।",
+    ),
+    (
+        "COUPON_NOT_YET_VALID",
+        "This coupon cannot be used yet.",
+        "यह कूपन अभी उपयोग नहीं किया जा सकता।",
+    ),
+    (
+        "COUPON_EXPIRED",
+        "This coupon has expired.",
+        "इस कूपन की समय सीमा समाप्त हो गई है।",
+    ),
+    (
+        "COUPON_USAGE_LIMIT_REACHED",
+        "This coupon has been used up.",
+        "यह कूपन पूरी तरह उपयोग हो चुका है।",
+    ),
+    (
+        "COUPON_CUSTOMER_LIMIT_REACHED",
+        "You have already used this coupon.",
+        "आप यह कूपन पहले ही उपयोग कर चुके हैं।",
+    ),
+    (
+        "COUPON_NOT_APPLICABLE",
+        "This coupon does not apply to the products in your order.",
+        "यह कूपन आपके ऑर्डर के उत्पादों पर लागू नहीं होता।",
+    ),
+    (
+        "COUPON_MINIMUM_NOT_REACHED",
+        "Your order total is below the minimum for this coupon.",
+        "आपके ऑर्डर की राशि इस कूपन की न्यूनतम राशि से कम है।",
+    ),
+    (
+        "CATEGORY_NOT_FOUND",
+        "The category was not found.",
+        "श्रेणी नहीं मिली।",
+    ),
+    (
+        "CATEGORY_HAS_SUBCATEGORIES",
+        "Remove or move the subcategories first.",
+        "पहले उप-श्रेणियाँ हटाएँ या स्थानांतरित करें।",
+    ),
+    (
+        "IMAGE_UPLOAD_NOT_FOUND",
+        "The upload was not found. Please upload the image again.",
+        "अपलोड नहीं मिला। कृपया चित्र फिर से अपलोड करें।",
+    ),
+    (
+        "IMAGE_TOO_LARGE",
+        "The image is too large.",
+        "चित्र बहुत बड़ा है।",
+    ),
+    (
+        "IMAGE_CHECKSUM_MISMATCH",
+        "The image was not uploaded completely. Please try again.",
+        "चित्र पूरा अपलोड नहीं हुआ। कृपया फिर से प्रयास करें।",
+    ),
+    (
+        "IMAGE_NOT_FOUND",
+        "The image was not found.",
+        "चित्र नहीं मिला।",
+    ),
+    (
+        "IMAGE_IN_USE",
+        "The image is still shown for a product.",
+        "यह चित्र अभी भी किसी उत्पाद के लिए दिखाया जा रहा है।",
+    ),
+    (
+        "PRICE_CHANGE_NOT_FOUND",
+        "No price change is scheduled for that time.",
+        "उस समय के लिए कोई मूल्य परिवर्तन निर्धारित नहीं है।",
+    ),
+    (
+        "PRICE_LIST_NOT_FOUND",
+        "The price list was not found.",
+        "मूल्य सूची नहीं मिली।",
+    ),
+    (
+        "PRICE_LIST_ALREADY_ASSIGNED",
+        "This custom price list already belongs to another customer.",
+        "यह विशेष मूल्य सूची पहले से किसी अन्य ग्राहक की है।",
+    ),
+    (
+        "PRICE_LIST_IN_USE",
+        "The price list is still assigned to customers.",
+        "यह मूल्य सूची अभी भी ग्राहकों को दी गई है।",
+    ),
+    (
+        "FIELD_REQUIRED",
+        "This field is required.",
+        "यह जानकारी आवश्यक है।",
+    ),
+    (
+        "FIELD_OUT_OF_RANGE",
+        "This value is out of range.",
+        "यह मान सीमा से बाहर है।",
+    ),
+    (
+        "FIELD_TOO_LONG",
+        "This value is too long.",
+        "यह मान बहुत लंबा है।",
+    ),
+    (
+        "FIELD_DUPLICATE",
+        "This value was entered more than once.",
+        "यह मान एक से अधिक बार दर्ज किया गया है।",
+    ),
+    (
+        "FIELD_UNSUPPORTED",
+        "This value is not supported.",
+        "यह मान समर्थित नहीं है।",
+    ),
+    (
+        "ADDRESS_NOT_FOUND",
+        "The address was not found.",
+        "पता नहीं मिला।",
+    ),
+    (
+        "PHONE_NOT_VERIFIED",
+        "Please verify your phone number before ordering.",
+        "ऑर्डर करने से पहले कृपया अपना फ़ोन नंबर सत्यापित करें।",
+    ),
+    (
+        "ALREADY_ANONYMISED",
+        "This profile has already been deleted.",
+        "यह प्रोफ़ाइल पहले ही हटाई जा चुकी है।",
+    ),
+    (
+        "DELETION_ALREADY_REQUESTED",
+        "Deletion of this profile has already been requested.",
+        "इस प्रोफ़ाइल को हटाने का अनुरोध पहले ही किया जा चुका है।",
+    ),
+    (
+        "HAS_ACTIVE_ORDERS",
+        "The profile still has open orders.",
+        "इस प्रोफ़ाइल के ऑर्डर अभी बाकी हैं।",
+    ),
+    (
+        "PHONE_EMPTY",
+        "Please enter a phone number.",
+        "कृपया फ़ोन नंबर दर्ज करें।",
+    ),
+    (
+        "PHONE_INVALID_CHARACTER",
+        "The phone number may only contain digits.",
+        "फ़ोन नंबर में केवल अंक हो सकते हैं।",
+    ),
+    (
+        "PHONE_INVALID_LENGTH",
+        "The phone number has the wrong number of digits.",
+        "फ़ोन नंबर में अंकों की संख्या गलत है।",
+    ),
+    (
+        "PHONE_INVALID_COUNTRY_CODE",
+        "The country code of the phone number is not supported.",
+        "फ़ोन नंबर का देश कोड समर्थित नहीं है।",
+    ),
+    (
+        "PROFILE_ALREADY_EXISTS",
+        "A profile with this phone number already exists.",
+        "इस फ़ोन नंबर से प्रोफ़ाइल पहले से मौजूद है।",
+    ),
+    (
+        "TOO_MANY_ADDRESSES",
+        "You have saved the maximum number of addresses.",
+        "आप अधिकतम संख्या में पते सहेज चुके हैं।",
+    ),
+    (
+        "PHONE_SAME_NUMBER",
+        "The new phone number is the same as the current one.",
+        "नया फ़ोन नंबर मौजूदा नंबर जैसा ही है।",
+    ),
+    (
+        "PHONE_ALREADY_IN_USE",
+        "This phone number is already in use.",
+        "यह फ़ोन नंबर पहले से उपयोग में है।",
+    ),
+    (
+        "PHONE_CHANGE_NOT_REQUESTED",
+        "No phone number change has been requested.",
+        "फ़ोन नंबर बदलने का कोई अनुरोध नहीं है।",
+    ),
+    (
+        "OTP_NOT_CONFIGURED",
+        "Phone verification is not available right now.",
+        "फ़ोन सत्यापन अभी उपलब्ध नहीं है।",
+    ),
+    (
+        "OTP_TOO_MANY_REQUESTS",
+        "Too many codes requested. Please wait and try again.",
+        "बहुत सारे कोड मांगे गए। कृपया थोड़ी देर बाद फिर से कोशिश करें।",
+    ),
+    (
+        "OTP_SEND_FAILED",
+        "The verification code could not be sent. Please try again.",
+        "सत्यापन कोड नहीं भेजा जा सका। कृपया फिर से कोशिश करें।",
+    ),
+    (
+        "OTP_NO_PENDING_CODE",
+        "Please request a verification code first.",
+        "कृपया पहले सत्यापन कोड मांगें।",
+    ),
+    (
+        "OTP_EXPIRED",
+        "The verification code has expired. Please request a new one.",
+        "सत्यापन कोड की समय सीमा समाप्त हो गई है। कृपया नया कोड मांगें।",
+    ),
+    (
+        "OTP_INVALID_CODE",
+        "The verification code is incorrect.",
+        "सत्यापन कोड गलत है।",
+    ),
+    (
+        "OTP_TOO_MANY_ATTEMPTS",
+        "Too many incorrect attempts. Please request a new code.",
+        "बहुत सारे गलत प्रयास। कृपया नया कोड मांगें।",
+    ),
+    (
+        "CERTIFICATE_UNAVAILABLE",
+        "The prices could not be verified. Please try again.",
+        "कीमतों का सत्यापन नहीं हो सका। कृपया फिर से कोशिश करें।",
+    ),
+    (
+        "ALREADY_INITIALIZED",
+        "The shop has already been set up.",
+        "दुकान पहले ही सेट की जा चुकी है।",
+    ),
+    (
+        "NOTIFICATION_NOT_FOUND",
+        "The notification was not found.",
+        "सूचना नहीं मिली।",
+    ),
+    (
+        "NOTIFICATION_NOT_FAILED",
+        "Only failed notifications can be sent again.",
+        "केवल विफल सूचनाएँ फिर से भेजी जा सकती हैं।",
+    ),
+    ("JOB_NOT_FOUND", "The job was not found.", "कार्य नहीं मिला।"),
+    (
+        "SNAPSHOT_ENCODING_FAILED",
+        "The backup could not be created.",
+        "बैकअप नहीं बनाया जा सका।",
+    ),
+    (
+        "SNAPSHOT_NOT_EXPORTED",
+        "Please create a backup first.",
+        "कृपया पहले बैकअप बनाएँ।",
+    ),
+    (
+        "SNAPSHOT_UNSUPPORTED_VERSION",
+        "This backup was made by an unsupported version.",
+        "यह बैकअप असमर्थित संस्करण से बनाया गया है।",
+    ),
+    (
+        "SNAPSHOT_INVALID_MANIFEST",
+        "The backup details are inconsistent.",
+        "बैकअप की जानकारी असंगत है।",
+    ),
+    (
+        "SNAPSHOT_CANISTER_NOT_FRESH",
+        "Backups can only be restored into a new installation.",
+        "बैकअप केवल नई स्थापना में ही बहाल किया जा सकता है।",
+    ),
+    (
+        "SNAPSHOT_NO_IMPORT",
+        "No backup restore has been started.",
+        "कोई बैकअप बहाली शुरू नहीं की गई है।",
+    ),
+    (
+        "SNAPSHOT_CHUNK_MISMATCH",
+        "This part belongs to a different backup.",
+        "यह भाग किसी अन्य बैकअप का है।",
+    ),
+    (
+        "SNAPSHOT_CHUNK_OUT_OF_RANGE",
+        "This part of the backup does not exist.",
+        "बैकअप का यह भाग मौजूद नहीं है।",
+    ),
+    (
+        "SNAPSHOT_CHECKSUM_MISMATCH",
+        "The backup was not transferred completely. Please try again.",
+        "बैकअप पूरा स्थानांतरित नहीं हुआ। कृपया फिर से कोशिश करें।",
+    ),
+    (
+        "SNAPSHOT_INCOMPLETE",
+        "Some parts of the backup are still missing.",
+        "बैकअप के कुछ भाग अभी भी बाकी हैं।",
+    ),
+    (
+        "SNAPSHOT_INVALID",
+        "The backup is damaged and cannot be restored.",
+        "बैकअप खराब है और बहाल नहीं किया जा सकता।",
+    ),
+    (
+        "CSV_INVALID",
+        "The CSV file could not be read.",
+        "CSV फ़ाइल पढ़ी नहीं जा सकी।",
+    ),
+    (
+        "CSV_UPLOAD_NOT_FOUND",
+        "The upload was not found. Please upload the file again.",
+        "अपलोड नहीं मिला। कृपया फ़ाइल फिर से अपलोड करें।",
+    ),
+    (
+        "CSV_UPLOAD_TOO_LARGE",
+        "The file is too large.",
+        "फ़ाइल बहुत बड़ी है।",
+    ),
+    (
+        "API_KEY_CREATION_FAILED",
+        "The API key could not be created. Please try again.",
+        "API कुंजी नहीं बनाई जा सकी। कृपया फिर से कोशिश करें।",
+    ),
+    (
+        "API_KEY_NOT_FOUND",
+        "The API key was not found.",
+        "API कुंजी नहीं मिली।",
+    ),
+    (
+        "API_KEY_ALREADY_REVOKED",
+        "The API key has already been revoked.",
+        "API कुंजी पहले ही रद्द की जा चुकी है।",
+    ),
+    (
+        "CANISTER_NOT_MONITORED",
+        "This canister is not monitored.",
+        "इस कैनिस्टर की निगरानी नहीं की जा रही है।",
+    ),
+    (
+        "TOO_MANY_MONITORED_CANISTERS",
+        "The maximum number of canisters is already monitored.",
+        "अधिकतम संख्या में कैनिस्टरों की निगरानी पहले से हो रही है।",
+    ),
+    (
+        "TOP_UP_NOT_FOUND",
+        "The top-up was not found.",
+        "टॉप-अप नहीं मिला।",
+    ),
+    (
+        "TOP_UP_NOT_RETRYABLE",
+        "This top-up cannot be retried.",
+        "इस टॉप-अप को दोबारा नहीं किया जा सकता।",
+    ),
+    (
+        "TOP_UP_NOT_MINTED",
+        "The ICP was transferred but the cycles are not credited yet. Please retry the top-up.",
+        "ICP स्थानांतरित हो गया है लेकिन साइकिल अभी जमा नहीं हुए हैं। कृपया टॉप-अप दोबारा करें।",
+    ),
+    ("TOP_UP_FAILED", "The top-up failed.", "टॉप-अप विफल हो गया।"),
+];
+
+/// The message for an error code in `lang`, falling back to English.
+pub fn error_message(code: &str, lang: &str) -> Option<&'static str> {
+    let hindi = normalize_language(lang) == Some("hi");
+    ERROR_MESSAGES
+        .iter()
+        .find(|(known, _, _)| *known == code)
+        .map(|(_, en, hi)| if hindi { *hi } else { *en })
+}
+
+/// The complete message table for `lang`.
+pub fn error_messages(lang: &str) -> Vec<ErrorMessage> {
+    ERROR_MESSAGES
+        .iter()
+        .filter_map(|(code, _, _)| {
+            error_message(code, lang).map(|message| ErrorMessage {
+                code: code.to_string(),
+                message: message.to_string(),
+            })
+        })
+        .collect()
+}
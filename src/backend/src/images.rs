@@ -0,0 +1,132 @@
+// Product images.
+//
+// Admins upload an image in chunks and finish the upload with its content type and SHA-256
+// hash, which must match what arrived. Images are kept in stable memory, identical uploads
+// share one image, and `http_request` serves them at `/api/images/<id>`. An image never
+// changes under its ID, so responses may be cached indefinitely.
+use crate::{
+    errors::{FieldError, FieldErrorKind, ImageError},
+    models::ProductImage,
+    store,
+};
+use candid::Principal;
+use sha2::{Digest, Sha256};
+use std::{cell::RefCell, collections::BTreeMap};
+
+/// Images are served in a single HTTP response, so they are kept well below its size limit.
+pub const MAX_IMAGE_BYTES: usize = 1024 * 1024;
+
+// Supported content types and the bytes their files start with. WebP files continue with
+// the file size and "WEBP", which is checked separately.
+const CONTENT_TYPES: &[(&str, &[u8])] = &[
+    ("image/jpeg", b"\xFF\xD8\xFF"),
+    ("image/png", b"\x89PNG\r\n\x1A\n"),
+    ("image/webp", b"RIFF"),
+];
+
+thread_local! {
+    // Chunked image uploads in progress: (uploader, upload ID) -> content so far.
+    // Kept on the heap only; an upgrade discards unfinished uploads.
+    static UPLOADS: RefCell<BTreeMap<(Principal, String), Vec<u8>>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Appends a chunk to an upload and returns its total size in bytes.
+pub fn append_upload_chunk(
+    caller: Principal,
+    upload_id: String,
+    chunk: &[u8],
+) -> Result<u64, ImageError> {
+    UPLOADS.with(|uploads| {
+        let mut uploads = uploads.borrow_mut();
+        let content = uploads.entry((caller, upload_id)).or_default();
+        let size = content.len() + chunk.len();
+        if size > MAX_IMAGE_BYTES {
+            return Err(ImageError::TooLarge {
+                size: size as u64,
+                max: MAX_IMAGE_BYTES as u64,
+            });
+        }
+        content.extend_from_slice(chunk);
+        Ok(content.len() as u64)
+    })
+}
+
+// Whether the content looks like a file of the content type
+fn has_signature(content_type: &str, content: &[u8]) -> bool {
+    let Some((_, signature)) = CONTENT_TYPES.iter().find(|(t, _)| *t == content_type) else {
+        return false;
+    };
+    content.starts_with(signature)
+        && (content_type != "image/webp" || content.get(8..12) == Some(b"WEBP".as_slice()))
+}
+
+/// Stores the finished upload as an image, or returns the stored image with the same
+/// content. The upload is discarded unless it was not found.
+pub fn finish_upload(
+    caller: Principal,
+    upload_id: &str,
+    content_type: &str,
+    sha256: &[u8],
+    now: u64,
+) -> Result<ProductImage, ImageError> {
+    let key = (caller, upload_id.to_string());
+    let content = UPLOADS
+        .with(|uploads| uploads.borrow_mut().remove(&key))
+        .ok_or(ImageError::UploadNotFound(upload_id.to_string()))?;
+    let content_type = content_type.trim().to_lowercase();
+
+    let mut errors = Vec::new();
+    if content.is_empty() {
+        errors.push(FieldError::new(
+            "upload_id",
+            FieldErrorKind::Required,
+            "Nothing was uploaded.",
+        ));
+    } else if !CONTENT_TYPES.iter().any(|(t, _)| *t == content_type) {
+        errors.push(FieldError::new(
+            "content_type",
+            FieldErrorKind::Unsupported,
+            "Images must be JPEG, PNG or WebP.",
+        ));
+    } else if !has_signature(&content_type, &content) {
+        errors.push(FieldError::new(
+            "content_type",
+            FieldErrorKind::Unsupported,
+            format!("The upload is not a valid {} file.", content_type),
+        ));
+    }
+    if !errors.is_empty() {
+        return Err(ImageError::InvalidInput(errors));
+    }
+    let hash = Sha256::digest(&content).to_vec();
+    if hash != sha256 {
+        return Err(ImageError::ChecksumMismatch);
+    }
+
+    if let Some(existing) = store::find_image_by_hash(&hash) {
+        return Ok(existing);
+    }
+    let image = ProductImage {
+        id: store::next_image_id(),
+        content_type,
+        size: content.len() as u64,
+        sha256: hash,
+        uploaded_at: now,
+    };
+    store::put_image(image.clone(), content);
+    Ok(image)
+}
+
+/// Removes an image that no product shows.
+pub fn remove_image(id: u64) -> Result<ProductImage, ImageError> {
+    if store::get_image(id).is_none() {
+        return Err(ImageError::ImageNotFound(id));
+    }
+    if let Some(product) = store::get_all_products()
+        .into_iter()
+        .find(|product| product.image_id == Some(id))
+    {
+        return Err(ImageError::InUse(product.id));
+    }
+    store::remove_image(id).ok_or(ImageError::ImageNotFound(id))
+}
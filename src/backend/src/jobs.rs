@@ -0,0 +1,255 @@
+// Scheduled background jobs, run from a timer (see timer.rs).
+//
+// Jobs live in stable memory so they survive upgrades; only the timer itself has to be
+// re-armed in post_upgrade. What a job does is determined by its `JobKind`.
+use crate::{
+    cycles, date,
+    errors::{FieldError, FieldErrorKind, JobError},
+    models::{DailySummary, Job, JobKind, JobSchedule, OrderStatus},
+    store,
+};
+use std::collections::BTreeMap;
+
+pub const AUTO_CANCEL_JOB_NAME: &str = "auto-cancel-pending-orders";
+pub const DAILY_SUMMARY_JOB_NAME: &str = "daily-summary";
+pub const CYCLES_MONITOR_JOB_NAME: &str = "cycles-monitor";
+
+const DEFAULT_PENDING_ORDER_TIMEOUT_SECONDS: u64 = 24 * 60 * 60;
+const AUTO_CANCEL_INTERVAL_SECONDS: u64 = 15 * 60;
+const CYCLES_MONITOR_INTERVAL_SECONDS: u64 = 60 * 60;
+// The summary of a day is taken a few minutes after local midnight
+const DAILY_SUMMARY_MINUTE_OF_DAY: u64 = 5;
+pub const MIN_INTERVAL_SECONDS: u64 = 60;
+
+/// Creates the built-in jobs that don't exist yet. Existing ones keep their configuration.
+/// The auto-cancel job is created disabled.
+pub fn ensure_builtin_jobs(now: u64) {
+    let existing: Vec<String> = store::get_all_jobs()
+        .into_iter()
+        .map(|job| job.name)
+        .collect();
+    if !existing.iter().any(|name| name == AUTO_CANCEL_JOB_NAME) {
+        store::put_job(Job {
+            id: store::get_next_job_id(),
+            name: AUTO_CANCEL_JOB_NAME.to_string(),
+            kind: JobKind::AutoCancelPendingOrders {
+                timeout_seconds: DEFAULT_PENDING_ORDER_TIMEOUT_SECONDS,
+            },
+            schedule: JobSchedule::Recurring {
+                interval_seconds: AUTO_CANCEL_INTERVAL_SECONDS,
+            },
+            // Off until an admin turns it on: created by an upgrade, it would otherwise cancel
+            // every order that was left pending for longer than the timeout right away
+            enabled: false,
+            next_run_at: now + AUTO_CANCEL_INTERVAL_SECONDS * date::NANOS_PER_SECOND,
+            last_run_at: None,
+            last_result: None,
+        });
+    }
+    if !existing.iter().any(|name| name == DAILY_SUMMARY_JOB_NAME) {
+        let utc_offset_minutes = date::DEFAULT_UTC_OFFSET_MINUTES;
+        store::put_job(Job {
+            id: store::get_next_job_id(),
+            name: DAILY_SUMMARY_JOB_NAME.to_string(),
+            kind: JobKind::DailySummary { utc_offset_minutes },
+            schedule: JobSchedule::Recurring {
+                interval_seconds: 24 * 60 * 60,
+            },
+            enabled: true,
+            next_run_at: next_local_time(now, DAILY_SUMMARY_MINUTE_OF_DAY, utc_offset_minutes),
+            last_run_at: None,
+            last_result: None,
+        });
+    }
+    if !existing.iter().any(|name| name == CYCLES_MONITOR_JOB_NAME) {
+        store::put_job(Job {
+            id: store::get_next_job_id(),
+            name: CYCLES_MONITOR_JOB_NAME.to_string(),
+            kind: JobKind::RecordCyclesBalances,
+            schedule: JobSchedule::Recurring {
+                interval_seconds: CYCLES_MONITOR_INTERVAL_SECONDS,
+            },
+            enabled: true,
+            // The first sample is taken right away
+            next_run_at: now,
+            last_run_at: None,
+            last_result: None,
+        });
+    }
+}
+
+/// Next time after `now` at which the local clock shows `minute_of_day`.
+fn next_local_time(now: u64, minute_of_day: u64, utc_offset_minutes: i32) -> u64 {
+    let local_days = local_day(now, utc_offset_minutes);
+    let (today, _) = date::day_range(local_days, utc_offset_minutes);
+    let at = today + minute_of_day * 60 * date::NANOS_PER_SECOND;
+    if at > now {
+        at
+    } else {
+        at + date::NANOS_PER_DAY
+    }
+}
+
+/// Local calendar day (days since 1970-01-01) that `timestamp` falls on.
+fn local_day(timestamp: u64, utc_offset_minutes: i32) -> i64 {
+    let seconds = (timestamp / date::NANOS_PER_SECOND) as i64 + utc_offset_minutes as i64 * 60;
+    seconds.div_euclid(86_400)
+}
+
+/// Checks a job's parameters before it is stored.
+pub fn validate(kind: &JobKind, schedule: &JobSchedule) -> Result<(), JobError> {
+    let mut errors = Vec::new();
+    if let JobSchedule::Recurring { interval_seconds } = schedule {
+        if *interval_seconds < MIN_INTERVAL_SECONDS {
+            errors.push(FieldError::new(
+                "schedule.interval_seconds",
+                FieldErrorKind::OutOfRange,
+                format!(
+                    "Recurring jobs must run at most every {} seconds.",
+                    MIN_INTERVAL_SECONDS
+                ),
+            ));
+        }
+    }
+    match kind {
+        JobKind::AutoCancelPendingOrders { timeout_seconds } if *timeout_seconds < 60 => {
+            errors.push(FieldError::new(
+                "kind.timeout_seconds",
+                FieldErrorKind::OutOfRange,
+                "The pending order timeout must be at least a minute.",
+            ));
+        }
+        JobKind::DailySummary { utc_offset_minutes }
+            if !(-720..=840).contains(utc_offset_minutes) =>
+        {
+            errors.push(FieldError::new(
+                "kind.utc_offset_minutes",
+                FieldErrorKind::OutOfRange,
+                "Invalid UTC offset.",
+            ));
+        }
+        _ => {}
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(JobError::InvalidInput(errors))
+    }
+}
+
+/// Earliest time an enabled job is due.
+pub fn next_due_at() -> Option<u64> {
+    store::get_all_jobs()
+        .into_iter()
+        .filter(|job| job.enabled)
+        .map(|job| job.next_run_at)
+        .min()
+}
+
+/// Runs every enabled job that is due at `now` and schedules its next run. One-shot jobs
+/// are disabled after running so their result stays visible.
+pub fn run_due_jobs(now: u64) {
+    for job in store::get_all_jobs() {
+        if job.enabled && job.next_run_at <= now {
+            run_job(job, now);
+        }
+    }
+}
+
+/// Runs a job immediately and records the result.
+pub fn run_job(mut job: Job, now: u64) -> Job {
+    let result = execute(&job.kind, now);
+    if let Err(e) = &result {
+        ic_cdk::println!("Job {} ({}) failed: {}", job.id, job.name, e);
+    }
+    job.last_run_at = Some(now);
+    job.last_result = Some(result);
+    match job.schedule {
+        JobSchedule::Once => job.enabled = false,
+        JobSchedule::Recurring { interval_seconds } => {
+            // Runs missed while the canister was stopped are skipped, not caught up
+            let interval = interval_seconds.max(MIN_INTERVAL_SECONDS) * date::NANOS_PER_SECOND;
+            while job.next_run_at <= now {
+                job.next_run_at += interval;
+            }
+        }
+    }
+    store::put_job(job.clone());
+    job
+}
+
+fn execute(kind: &JobKind, now: u64) -> Result<String, String> {
+    match kind {
+        JobKind::AutoCancelPendingOrders { timeout_seconds } => {
+            cancel_stale_pending_orders(*timeout_seconds, now)
+        }
+        JobKind::DailySummary { utc_offset_minutes } => {
+            // The job runs shortly after midnight and summarises the day that just ended
+            let yesterday = local_day(now, *utc_offset_minutes) - 1;
+            let summary = summarize_day(yesterday, *utc_offset_minutes, now);
+            let message = format!(
+                "{}: {} orders, revenue {:.2}",
+                summary.date, summary.orders_placed, summary.revenue
+            );
+            store::put_daily_summary(summary);
+            Ok(message)
+        }
+        JobKind::RecordCyclesBalances => {
+            // Querying the management canister is async; the samples are stored when it replies
+            ic_cdk::spawn(cycles::record_samples(now));
+            Ok("Sampling cycle balances.".to_string())
+        }
+    }
+}
+
+/// Cancels orders that are still Pending `timeout_seconds` after they were placed.
+fn cancel_stale_pending_orders(timeout_seconds: u64, now: u64) -> Result<String, String> {
+    let cutoff = now.saturating_sub(timeout_seconds * date::NANOS_PER_SECOND);
+    let stale: Vec<u64> = store::get_all_orders()
+        .into_iter()
+        .filter(|order| order.status == OrderStatus::Pending && order.timestamp <= cutoff)
+        .map(|order| order.id)
+        .collect();
+    for order_id in &stale {
+        crate::set_order_status(*order_id, OrderStatus::Cancelled, now)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(format!("Cancelled {} pending orders.", stale.len()))
+}
+
+/// Aggregates the orders placed on a local calendar day.
+pub fn summarize_day(days: i64, utc_offset_minutes: i32, now: u64) -> DailySummary {
+    let (start, end) = date::day_range(days, utc_offset_minutes);
+    let (year, month, day) = date::civil_from_days(days);
+    let mut summary = DailySummary {
+        date: format!("{:04}-{:02}-{:02}", year, month, day),
+        created_at: now,
+        ..Default::default()
+    };
+
+    let mut first_order_at: BTreeMap<String, u64> = BTreeMap::new();
+    for order in store::get_all_orders() {
+        let first = first_order_at
+            .entry(order.user_phone_number.clone())
+            .or_insert(order.timestamp);
+        *first = (*first).min(order.timestamp);
+
+        if !(start..end).contains(&order.timestamp) {
+            continue;
+        }
+        summary.orders_placed += 1;
+        match order.status {
+            OrderStatus::Delivered => summary.orders_delivered += 1,
+            OrderStatus::Cancelled => summary.orders_cancelled += 1,
+            _ => summary.orders_open += 1,
+        }
+        if order.status != OrderStatus::Cancelled {
+            summary.revenue += order.total_amount;
+        }
+    }
+    summary.first_time_customers = first_order_at
+        .values()
+        .filter(|first| (start..end).contains(*first))
+        .count() as u64;
+    summary
+}
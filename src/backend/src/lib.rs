@@ -1,29 +1,78 @@
 #![allow(dead_code)]
 #![warn(unused_variables)]
 
+mod address;
+mod audit;
+mod bulk;
+mod cart;
+mod catalog;
+mod certified;
+mod csv;
+mod cycles;
+mod date;
 mod errors;
 mod guard;
+mod hex;
+mod http;
+mod i18n;
+mod images;
+mod jobs;
+mod json;
 mod models;
+mod notifications;
+mod outcall;
+mod phone;
+mod pricing;
+mod promotions;
+mod snapshot;
 mod store;
 #[cfg(test)]
 mod tests;
+mod timer;
+mod topup;
+mod verification;
 
 use crate::{guard::*, models::*};
 use candid::{CandidType, Deserialize, Principal};
-use errors::{GetUserDataError, OrderError};
-use ic_cdk::api::management_canister::main::{
-    canister_status, CanisterIdRecord, CanisterStatusResponse,
+use errors::{
+    AddressError, ApiKeyError, CartError, CategoryError, CertificationError, CouponError, CsvError,
+    CyclesError, DataDeletionError, ErrorCode, FieldError, FieldErrorKind, GetUserDataError,
+    ImageError, JobError, NotificationError, OrderError, OtpError, PhoneChangeError,
+    PhoneNumberError, PriceListError, ProductError, ProfileError, QuantityError, SettingsError,
+    SetupError, SnapshotError, StoreError, TopUpError,
 };
+use http::{HttpRequest, HttpResponse};
+use ic_cdk::api::management_canister::http_request::{
+    HttpResponse as OutcallResponse, TransformArgs,
+};
+use ic_cdk::api::management_canister::main::{raw_rand, CanisterStatusResponse};
 use ic_cdk::{api::time, call, id, query, update};
 
 ///////////////////////////////////////////////////////////
 // USER FUNCTIONS
 ///////////////////////////////////////////////////////////
 
+/// Normalises a phone number using the configured default country.
+fn normalize_phone(raw: &str) -> Result<String, PhoneNumberError> {
+    phone::normalize_phone_number(raw, &store::get_phone_settings())
+}
+
+/// Normalises a phone number and follows the redirect left by a phone number change,
+/// unless a profile is registered under the number itself.
+fn resolve_customer_phone(raw: &str) -> Result<String, PhoneNumberError> {
+    let phone_number = normalize_phone(raw)?;
+    if store::get_user_profile(&phone_number).is_some() {
+        return Ok(phone_number);
+    }
+    Ok(store::get_phone_redirect(&phone_number).unwrap_or(phone_number))
+}
+
 // User Profile Management
 
 #[query]
 fn get_profile_by_phone(phone_number: String) -> Result<UserProfile, GetUserDataError> {
+    let phone_number =
+        resolve_customer_phone(&phone_number).map_err(GetUserDataError::InvalidPhoneNumber)?;
     match store::get_user_profile(&phone_number) {
         Some(profile) => Ok(profile),
         None => Err(GetUserDataError::DidntFindUserData),
@@ -31,45 +80,437 @@ fn get_profile_by_phone(phone_number: String) -> Result<UserProfile, GetUserData
 }
 
 #[update]
-fn update_profile(profile: UserProfile) -> Result<(), GetUserDataError> {
-    if profile.phone_number.trim().is_empty() {
-        return Err(GetUserDataError::DidntFindUserData);
-    }
+fn update_profile(mut profile: UserProfile) -> Result<(), ProfileError> {
+    validate_profile_fields(&profile)?;
+    profile.phone_number =
+        resolve_customer_phone(&profile.phone_number).map_err(ProfileError::InvalidPhoneNumber)?;
+    // Anonymisation and saved addresses are managed through their own endpoints
+    let existing = store::get_user_profile(&profile.phone_number);
+    profile.deleted_at = existing.as_ref().and_then(|p| p.deleted_at);
+    profile.addresses = existing.as_ref().and_then(|p| p.addresses.clone());
+    profile.default_address_id = existing.as_ref().and_then(|p| p.default_address_id);
+    profile.verified_at = existing.as_ref().and_then(|p| p.verified_at);
+    profile.notifications_opt_out = existing.as_ref().and_then(|p| p.notifications_opt_out);
+    profile.price_list_id = existing.and_then(|p| p.price_list_id);
     store::update_user_profile(profile);
     Ok(())
 }
 
-#[update]
-fn create_profile(profile: UserProfile) -> Result<(), String> {
-    if profile.phone_number.trim().is_empty()
-        || profile.name.trim().is_empty()
-        || profile.address.trim().is_empty()
-    {
-        return Err("Phone number, name, and address cannot be empty.".to_string());
+/// Checks the fields a customer fills in themselves.
+fn validate_profile_fields(profile: &UserProfile) -> Result<(), ProfileError> {
+    let mut errors = Vec::new();
+    if profile.name.trim().is_empty() {
+        errors.push(FieldError::new(
+            "name",
+            FieldErrorKind::Required,
+            "Name cannot be empty.",
+        ));
     }
-    if store::get_user_profile(&profile.phone_number).is_some() {
-        Err(format!(
-            "Profile with phone number {} already exists.",
-            profile.phone_number
-        ))
+    if profile.address.trim().is_empty() {
+        errors.push(FieldError::new(
+            "address",
+            FieldErrorKind::Required,
+            "Address cannot be empty.",
+        ));
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ProfileError::InvalidInput(errors))
+    }
+}
+
+#[update]
+fn create_profile(profile: UserProfile) -> Result<(), ProfileError> {
+    validate_profile_fields(&profile)?;
+    let phone_number =
+        normalize_phone(&profile.phone_number).map_err(ProfileError::InvalidPhoneNumber)?;
+    if store::get_user_profile(&phone_number).is_some() {
+        Err(ProfileError::AlreadyExists(phone_number))
     } else {
         let complete_profile = UserProfile {
-            phone_number: profile.phone_number,
+            phone_number,
             name: profile.name,
             address: profile.address,
             order_ids: Vec::new(),
+            deleted_at: None,
+            addresses: None,
+            default_address_id: None,
+            verified_at: None,
+            notifications_opt_out: None,
+            price_list_id: None,
         };
+        // A recycled number no longer redirects to its previous owner
+        store::remove_phone_redirect(&complete_profile.phone_number);
         store::update_user_profile(complete_profile);
         Ok(())
     }
 }
 
+/// Asks for the profile to be moved to a new phone number. The change is applied once the
+/// new number is confirmed with an OTP (`request_otp` + `verify_otp` with the
+/// `ChangePhoneNumber` purpose) or approved through `change_phone_number_admin`.
+#[update]
+fn request_phone_number_change(
+    current_phone_number: String,
+    new_phone_number: String,
+) -> Result<(), PhoneChangeError> {
+    let current_phone_number = resolve_customer_phone(&current_phone_number)
+        .map_err(PhoneChangeError::InvalidPhoneNumber)?;
+    let new_phone_number =
+        normalize_phone(&new_phone_number).map_err(PhoneChangeError::InvalidPhoneNumber)?;
+    validate_phone_change(&current_phone_number, &new_phone_number)?;
+    store::add_phone_change_request(PhoneChangeRequest {
+        current_phone_number,
+        new_phone_number,
+        requested_at: time(),
+    });
+    Ok(())
+}
+
+/// Lets a customer ask for their personal data to be deleted.
+/// The request is queued for an admin, who anonymises the data once no order is in progress.
+#[update]
+fn request_data_deletion(phone_number: String) -> Result<(), DataDeletionError> {
+    let phone_number =
+        resolve_customer_phone(&phone_number).map_err(DataDeletionError::InvalidPhoneNumber)?;
+    match store::get_user_profile(&phone_number) {
+        None => return Err(DataDeletionError::ProfileNotFound),
+        Some(profile) if profile.deleted_at.is_some() => {
+            return Err(DataDeletionError::AlreadyAnonymised)
+        }
+        Some(_) => {}
+    }
+    let request = DeletionRequest {
+        phone_number,
+        requested_at: time(),
+    };
+    if store::add_deletion_request(request) {
+        Ok(())
+    } else {
+        Err(DataDeletionError::AlreadyRequested)
+    }
+}
+
+/// Lets a customer opt out of (or back into) order status notifications.
+#[update]
+fn set_notification_preference(
+    phone_number: String,
+    opt_out: bool,
+) -> Result<(), GetUserDataError> {
+    let phone_number =
+        resolve_customer_phone(&phone_number).map_err(GetUserDataError::InvalidPhoneNumber)?;
+    match store::get_user_profile(&phone_number) {
+        Some(mut profile) if profile.deleted_at.is_none() => {
+            profile.notifications_opt_out = Some(opt_out);
+            store::update_user_profile(profile);
+            Ok(())
+        }
+        _ => Err(GetUserDataError::DidntFindUserData),
+    }
+}
+
+// Phone Verification
+
+/// Sends a one-time code by SMS. For `VerifyProfile` it goes to the profile's number, for
+/// `ChangePhoneNumber` to the new number of the customer's pending change request.
+#[update]
+async fn request_otp(phone_number: String, purpose: OtpPurpose) -> Result<(), OtpError> {
+    let (phone_number, purpose) = match purpose {
+        OtpPurpose::VerifyProfile => {
+            let phone_number =
+                resolve_customer_phone(&phone_number).map_err(OtpError::InvalidPhoneNumber)?;
+            match store::get_user_profile(&phone_number) {
+                Some(profile) if profile.deleted_at.is_none() => {}
+                _ => return Err(OtpError::ProfileNotFound),
+            }
+            (phone_number, OtpPurpose::VerifyProfile)
+        }
+        OtpPurpose::ChangePhoneNumber {
+            current_phone_number,
+        } => {
+            let current_phone_number = resolve_customer_phone(&current_phone_number)
+                .map_err(OtpError::InvalidPhoneNumber)?;
+            let phone_number =
+                normalize_phone(&phone_number).map_err(OtpError::InvalidPhoneNumber)?;
+            match store::get_phone_change_request(&current_phone_number) {
+                Some(request) if request.new_phone_number == phone_number => {}
+                _ => return Err(OtpError::PhoneChangeNotRequested),
+            }
+            (
+                phone_number,
+                OtpPurpose::ChangePhoneNumber {
+                    current_phone_number,
+                },
+            )
+        }
+    };
+    verification::issue_code(phone_number, purpose, time()).await
+}
+
+/// Checks a code sent by `request_otp` and applies what it was issued for.
+/// Returns the verified profile.
+#[update]
+fn verify_otp(phone_number: String, code: String) -> Result<UserProfile, OtpError> {
+    let normalized = normalize_phone(&phone_number).map_err(OtpError::InvalidPhoneNumber)?;
+    let phone_number = if store::get_otp_challenge(&normalized).is_some() {
+        normalized
+    } else {
+        resolve_customer_phone(&phone_number).map_err(OtpError::InvalidPhoneNumber)?
+    };
+    let now = time();
+
+    let mut profile = match verification::check_code(&phone_number, &code, now)? {
+        OtpPurpose::VerifyProfile => match store::get_user_profile(&phone_number) {
+            Some(profile) if profile.deleted_at.is_none() => profile,
+            _ => return Err(OtpError::ProfileNotFound),
+        },
+        OtpPurpose::ChangePhoneNumber {
+            current_phone_number,
+        } => {
+            match store::get_phone_change_request(&current_phone_number) {
+                Some(request) if request.new_phone_number == phone_number => {}
+                _ => return Err(OtpError::PhoneChangeNotRequested),
+            }
+            change_customer_phone_number(&current_phone_number, &phone_number)
+                .map_err(OtpError::PhoneChangeFailed)?
+        }
+    };
+    profile.verified_at = Some(now);
+    store::update_user_profile(profile.clone());
+    Ok(profile)
+}
+
+/// Transform function for HTTPS outcalls (see `outcall::post_json`).
+#[query]
+fn transform_outcall_response(args: TransformArgs) -> OutcallResponse {
+    outcall::strip_response(args)
+}
+
+// Saved Addresses
+
+/// Loads a customer's profile, applies `update` to it and saves it back.
+fn update_customer_profile<T>(
+    phone_number: &str,
+    update: impl FnOnce(&mut UserProfile) -> Result<T, AddressError>,
+) -> Result<T, AddressError> {
+    let phone_number =
+        resolve_customer_phone(phone_number).map_err(AddressError::InvalidPhoneNumber)?;
+    let mut profile = match store::get_user_profile(&phone_number) {
+        Some(profile) if profile.deleted_at.is_none() => profile,
+        _ => return Err(AddressError::ProfileNotFound),
+    };
+    let result = update(&mut profile)?;
+    store::update_user_profile(profile);
+    Ok(result)
+}
+
+#[update]
+fn add_address(phone_number: String, input: AddressInput) -> Result<SavedAddress, AddressError> {
+    let input = address::validate_address_input(input).map_err(AddressError::InvalidInput)?;
+    update_customer_profile(&phone_number, |profile| {
+        let addresses = profile.addresses.get_or_insert_with(Vec::new);
+        if addresses.len() as u64 >= address::MAX_SAVED_ADDRESSES {
+            return Err(AddressError::TooManyAddresses(address::MAX_SAVED_ADDRESSES));
+        }
+        let saved = SavedAddress {
+            id: address::next_address_id(addresses),
+            label: input.label,
+            address_lines: input.address_lines,
+            landmark: input.landmark,
+            zone: input.zone,
+            geo: input.geo,
+        };
+        addresses.push(saved.clone());
+        // The first saved address becomes the default
+        if profile.default_address_id.is_none() {
+            profile.default_address_id = Some(saved.id);
+        }
+        Ok(saved)
+    })
+}
+
+#[update]
+fn update_address(
+    phone_number: String,
+    address_id: u64,
+    input: AddressInput,
+) -> Result<SavedAddress, AddressError> {
+    let input = address::validate_address_input(input).map_err(AddressError::InvalidInput)?;
+    update_customer_profile(&phone_number, |profile| {
+        let saved = profile
+            .addresses
+            .iter_mut()
+            .flatten()
+            .find(|a| a.id == address_id)
+            .ok_or(AddressError::AddressNotFound(address_id))?;
+        saved.label = input.label;
+        saved.address_lines = input.address_lines;
+        saved.landmark = input.landmark;
+        saved.zone = input.zone;
+        saved.geo = input.geo;
+        Ok(saved.clone())
+    })
+}
+
+#[update]
+fn remove_address(phone_number: String, address_id: u64) -> Result<(), AddressError> {
+    update_customer_profile(&phone_number, |profile| {
+        let addresses = profile.addresses.get_or_insert_with(Vec::new);
+        let len_before = addresses.len();
+        addresses.retain(|a| a.id != address_id);
+        if addresses.len() == len_before {
+            return Err(AddressError::AddressNotFound(address_id));
+        }
+        if profile.default_address_id == Some(address_id) {
+            profile.default_address_id = addresses.first().map(|a| a.id);
+        }
+        Ok(())
+    })
+}
+
+#[update]
+fn set_default_address(phone_number: String, address_id: u64) -> Result<(), AddressError> {
+    update_customer_profile(&phone_number, |profile| {
+        if !profile
+            .addresses
+            .iter()
+            .flatten()
+            .any(|a| a.id == address_id)
+        {
+            return Err(AddressError::AddressNotFound(address_id));
+        }
+        profile.default_address_id = Some(address_id);
+        Ok(())
+    })
+}
+
+// Cart
+
+/// Applies `update` to the customer's cart (an empty one if there is none) and stores it.
+fn update_customer_cart<F>(phone_number: &str, update: F) -> Result<CartView, CartError>
+where
+    F: FnOnce(&mut Cart) -> Result<(), CartError>,
+{
+    let phone_number =
+        resolve_customer_phone(phone_number).map_err(CartError::InvalidPhoneNumber)?;
+    match store::get_user_profile(&phone_number) {
+        Some(profile) if profile.deleted_at.is_none() => {}
+        _ => return Err(CartError::ProfileNotFound),
+    }
+    let mut cart = store::get_cart(&phone_number).unwrap_or(Cart {
+        phone_number,
+        ..Default::default()
+    });
+    update(&mut cart)?;
+    cart.updated_at = time();
+    store::put_cart(cart.clone());
+    Ok(cart::view(&cart, cart.updated_at))
+}
+
+/// The customer's cart with current prices; lines whose price changed since they were added
+/// carry the previous price.
+#[query]
+fn get_cart(phone_number: String) -> Result<CartView, CartError> {
+    let phone_number =
+        resolve_customer_phone(&phone_number).map_err(CartError::InvalidPhoneNumber)?;
+    Ok(store::get_cart(&phone_number)
+        .map(|cart| cart::view(&cart, time()))
+        .unwrap_or_default())
+}
+
+/// Adds a quantity of a product to the cart, or with `variant_id` a number of its packs.
+#[update]
+fn add_to_cart(
+    phone_number: String,
+    product_id: u64,
+    quantity: f64,
+    variant_id: Option<u64>,
+) -> Result<CartView, CartError> {
+    update_customer_cart(&phone_number, |cart| {
+        cart::add_item(cart, product_id, variant_id, quantity, time())
+    })
+}
+
+#[update]
+fn update_cart_item(
+    phone_number: String,
+    product_id: u64,
+    quantity: f64,
+    variant_id: Option<u64>,
+) -> Result<CartView, CartError> {
+    update_customer_cart(&phone_number, |cart| {
+        cart::update_item(cart, product_id, variant_id, quantity, time())
+    })
+}
+
+#[update]
+fn remove_from_cart(
+    phone_number: String,
+    product_id: u64,
+    variant_id: Option<u64>,
+) -> Result<CartView, CartError> {
+    update_customer_cart(&phone_number, |cart| {
+        cart::remove_item(cart, product_id, variant_id)
+    })
+}
+
+#[update]
+fn clear_cart(phone_number: String) -> Result<CartView, CartError> {
+    update_customer_cart(&phone_number, |cart| {
+        cart.items.clear();
+        Ok(())
+    })
+}
+
+/// Places an order for the items in the cart and empties it. If a price changed since the
+/// customer last saw it, nothing is ordered: the cart takes over the new prices and the
+/// customer has to check out again.
+#[update]
+fn checkout_cart(
+    phone_number: String,
+    delivery_address: String,
+    address_id: Option<u64>,
+    coupon_code: Option<String>,
+) -> Result<u64, OrderError> {
+    let phone_number =
+        resolve_customer_phone(&phone_number).map_err(OrderError::InvalidPhoneNumber)?;
+    let mut cart = store::get_cart(&phone_number).unwrap_or_default();
+    let changed = cart::refresh_prices(&mut cart, time());
+    if !changed.is_empty() {
+        cart.updated_at = time();
+        store::put_cart(cart);
+        return Err(OrderError::PricesChanged(changed));
+    }
+    let items_input = cart
+        .items
+        .iter()
+        .map(|item| OrderItemInput {
+            product_id: item.product_id,
+            quantity: item.quantity,
+            variant_id: item.variant_id,
+        })
+        .collect();
+    // No awaits between placing the order and removing the cart, so both happen or neither
+    let order_id = place_order(
+        phone_number.clone(),
+        items_input,
+        delivery_address,
+        address_id,
+        coupon_code,
+        time(),
+    )?;
+    store::remove_cart(&phone_number);
+    Ok(order_id)
+}
+
 // User Order Management
 
 #[derive(CandidType, Deserialize, Clone, Debug)]
 pub struct OrderItemInput {
     pub product_id: u64,
     pub quantity: f64,
+    pub variant_id: Option<u64>, // With a variant the quantity is a whole number of packs
 }
 
 #[update]
@@ -77,50 +518,64 @@ fn create_order(
     phone_number: String,
     items_input: Vec<OrderItemInput>,
     delivery_address: String,
+    address_id: Option<u64>, // Saved address to deliver to, instead of `delivery_address`
+    coupon_code: Option<String>,
 ) -> Result<u64, OrderError> {
-    if phone_number.trim().is_empty()
-        || items_input.is_empty()
-        || delivery_address.trim().is_empty()
-    {
-        return Err(OrderError::InvalidInput(
-            "Phone number, items, and delivery address cannot be empty".to_string(),
-        ));
-    }
+    place_order(
+        phone_number,
+        items_input,
+        delivery_address,
+        address_id,
+        coupon_code,
+        time(),
+    )
+}
+
+/// Validates and stores a new order, priced with the prices effective at `timestamp`.
+/// Shared by `create_order` and `checkout_cart`.
+fn place_order(
+    phone_number: String,
+    items_input: Vec<OrderItemInput>,
+    delivery_address: String,
+    address_id: Option<u64>,
+    coupon_code: Option<String>,
+    timestamp: u64,
+) -> Result<u64, OrderError> {
+    check_items_input(&items_input)?;
+    let phone_number =
+        resolve_customer_phone(&phone_number).map_err(OrderError::InvalidPhoneNumber)?;
 
     let user_profile = match store::get_user_profile(&phone_number) {
         Some(profile) => profile,
         None => return Err(OrderError::UserProfileNotFound),
     };
-
-    let mut order_items: Vec<OrderItem> = Vec::new();
-    let mut total_amount: f64 = 0.0;
-
-    for item_input in items_input {
-        if item_input.quantity <= 0.0 {
-            return Err(OrderError::InvalidInput(format!(
-                "Invalid quantity {} for product ID {}",
-                item_input.quantity, item_input.product_id
-            )));
-        }
-        match store::get_product_by_id(item_input.product_id) {
-            Some(product) => {
-                let item_total = product.price * item_input.quantity;
-                order_items.push(OrderItem {
-                    product_id: item_input.product_id,
-                    quantity: item_input.quantity,
-                    price_per_unit_at_order: product.price,
-                });
-                total_amount += item_total;
-            }
-            None => {
-                return Err(OrderError::InvalidProductInOrder(item_input.product_id));
-            }
-        }
+    if user_profile.verified_at.is_none()
+        && store::get_verification_settings().require_verified_phone_for_orders
+    {
+        return Err(OrderError::PhoneNotVerified);
     }
 
-    let order_id = store::get_next_order_id();
-    let timestamp = time();
+    let delivery_address = resolve_delivery_address(&user_profile, delivery_address, address_id)?;
+
+    let order_items = price_items(user_profile.price_list_id, items_input, timestamp)?;
+    let mut total_amount: f64 = order_items
+        .iter()
+        .map(|item| item.price_per_unit_at_order * item.quantity)
+        .sum();
+
+    let discounts = match coupon_code {
+        Some(code) => vec![
+            promotions::discount_for(&code, &phone_number, &order_items, timestamp)
+                .map_err(OrderError::InvalidCoupon)?,
+        ],
+        None => Vec::new(),
+    };
+    total_amount -= discounts
+        .iter()
+        .map(|discount| discount.amount)
+        .sum::<f64>();
 
+    let order_id = store::get_next_order_id();
     let order = Order {
         id: order_id,
         user_phone_number: phone_number.clone(),
@@ -130,9 +585,13 @@ fn create_order(
         status: OrderStatus::Pending,
         timestamp,
         delivery_address,
+        delivery_address_id: address_id,
         last_updated: timestamp,
+        discounts: (!discounts.is_empty()).then_some(discounts),
     };
 
+    catalog::take_stock(&order.items)?;
+    let items = order.items.clone();
     match store::add_order(order) {
         Ok(_) => {
             let mut updated_profile = user_profile;
@@ -140,25 +599,254 @@ fn create_order(
             store::update_user_profile(updated_profile);
             Ok(order_id)
         }
-        Err(e) => Err(OrderError::StorageError(format!(
-            "Failed to save order: {}",
-            e
-        ))),
+        Err(e) => {
+            catalog::restock(&items);
+            Err(OrderError::StorageError(format!(
+                "Failed to save order: {}",
+                e
+            )))
+        }
     }
 }
 
+/// Checks that there are items and that their quantities are positive.
+fn check_items_input(items_input: &[OrderItemInput]) -> Result<(), OrderError> {
+    if items_input.is_empty() {
+        return Err(OrderError::InvalidInput(vec![FieldError::new(
+            "items",
+            FieldErrorKind::Required,
+            "Items cannot be empty",
+        )]));
+    }
+    let invalid_quantities: Vec<FieldError> = items_input
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.quantity.is_nan() || item.quantity <= 0.0)
+        .map(|(index, item)| {
+            FieldError::new(
+                format!("items[{}].quantity", index),
+                FieldErrorKind::OutOfRange,
+                format!(
+                    "Invalid quantity {} for product ID {}",
+                    item.quantity, item.product_id
+                ),
+            )
+        })
+        .collect();
+    if !invalid_quantities.is_empty() {
+        return Err(OrderError::InvalidInput(invalid_quantities));
+    }
+    Ok(())
+}
+
+/// The address to deliver to: one of the customer's saved addresses when `address_id` is
+/// given, otherwise `delivery_address`.
+fn resolve_delivery_address(
+    user_profile: &UserProfile,
+    delivery_address: String,
+    address_id: Option<u64>,
+) -> Result<String, OrderError> {
+    match address_id {
+        Some(address_id) => match user_profile
+            .addresses
+            .iter()
+            .flatten()
+            .find(|a| a.id == address_id)
+        {
+            Some(saved) => Ok(address::format_address(saved)),
+            None => Err(OrderError::AddressNotFound(address_id)),
+        },
+        None if delivery_address.trim().is_empty() => {
+            Err(OrderError::InvalidInput(vec![FieldError::new(
+                "delivery_address",
+                FieldErrorKind::Required,
+                "Delivery address cannot be empty",
+            )]))
+        }
+        None => Ok(delivery_address),
+    }
+}
+
+/// Replaces the items of an order, re-priced at `now`, and optionally its address
+/// (`delivery_address` or `address_id`; neither keeps the current one). The stock taken for
+/// the old items is returned and taken again for the new ones, the coupon discounts are
+/// recomputed with the terms recorded on the order (the edit is refused where a coupon no
+/// longer applies), and the edit is added to the order's history (see `get_order_edits`).
+fn edit_order(
+    mut order: Order,
+    items_input: Vec<OrderItemInput>,
+    delivery_address: Option<String>,
+    address_id: Option<u64>,
+    edited_by: OrderEditor,
+    reason: Option<String>,
+    now: u64,
+) -> Result<Order, OrderError> {
+    check_items_input(&items_input)?;
+    let user_profile =
+        store::get_user_profile(&order.user_phone_number).ok_or(OrderError::UserProfileNotFound)?;
+    let new_address = match (delivery_address, address_id) {
+        (None, None) => None,
+        (delivery_address, address_id) => Some(resolve_delivery_address(
+            &user_profile,
+            delivery_address.unwrap_or_default(),
+            address_id,
+        )?),
+    };
+
+    let items = price_items(user_profile.price_list_id, items_input, now)?;
+    let discounts = order
+        .discounts
+        .iter()
+        .flatten()
+        .map(|discount| promotions::rediscount(discount, &items))
+        .collect::<Result<Vec<OrderDiscount>, CouponError>>()
+        .map_err(OrderError::InvalidCoupon)?;
+    let total_amount = items
+        .iter()
+        .map(|item| item.price_per_unit_at_order * item.quantity)
+        .sum::<f64>()
+        - discounts
+            .iter()
+            .map(|discount| discount.amount)
+            .sum::<f64>();
+
+    catalog::restock(&order.items);
+    if let Err(e) = catalog::take_stock(&items) {
+        // The old items' stock was just returned, so taking it again cannot fail
+        let _ = catalog::take_stock(&order.items);
+        return Err(e);
+    }
+
+    let edit = OrderEdit {
+        order_id: order.id,
+        edited_at: now,
+        edited_by,
+        reason,
+        previous_items: std::mem::replace(&mut order.items, items),
+        previous_total_amount: order.total_amount,
+        previous_delivery_address: order.delivery_address.clone(),
+    };
+    order.total_amount = total_amount;
+    if let Some(new_address) = new_address {
+        order.delivery_address = new_address;
+        order.delivery_address_id = address_id;
+    }
+    order.discounts = (!discounts.is_empty()).then_some(discounts);
+    order.last_updated = now;
+
+    let items = order.items.clone();
+    let order = store::update_order(order).map_err(|e| {
+        catalog::restock(&items);
+        let _ = catalog::take_stock(&edit.previous_items);
+        OrderError::from(e)
+    })?;
+    store::add_order_edit(edit);
+    Ok(order)
+}
+
+/// Checks the quantities and prices the items at `at` for a customer with `price_list_id`
+/// (see `pricing::item_price`).
+fn price_items(
+    price_list_id: Option<u64>,
+    items_input: Vec<OrderItemInput>,
+    at: u64,
+) -> Result<Vec<OrderItem>, OrderError> {
+    items_input
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let product = store::get_product_by_id(item.product_id)
+                .ok_or(OrderError::InvalidProductInOrder(item.product_id))?;
+            let variant = catalog::check_quantity(&product, item.variant_id, item.quantity)
+                .map_err(|error| match error {
+                    QuantityError::VariantNotFound(variant_id) => OrderError::VariantNotFound {
+                        product_id: item.product_id,
+                        variant_id,
+                    },
+                    other => OrderError::InvalidInput(vec![FieldError::new(
+                        format!("items[{}].quantity", index),
+                        FieldErrorKind::OutOfRange,
+                        other.to_string(),
+                    )]),
+                })?;
+            let price =
+                pricing::item_price(price_list_id, &product, variant.as_ref(), item.quantity, at);
+            Ok(OrderItem {
+                product_id: item.product_id,
+                quantity: item.quantity,
+                price_per_unit_at_order: price.price_per_unit,
+                price_list_id: price.price_list_id,
+                variant_id: item.variant_id,
+            })
+        })
+        .collect()
+}
+
+/// The discount a coupon would give on the items at the current prices, so customers can
+/// check a code before ordering.
 #[query]
-fn get_my_orders(phone_number: String) -> Result<Vec<Order>, OrderError> {
-    if phone_number.trim().is_empty() {
-        return Err(OrderError::InvalidInput(
-            "Phone number cannot be empty".to_string(),
-        ));
+fn preview_coupon(
+    phone_number: String,
+    items_input: Vec<OrderItemInput>,
+    coupon_code: String,
+) -> Result<OrderDiscount, OrderError> {
+    let phone_number =
+        resolve_customer_phone(&phone_number).map_err(OrderError::InvalidPhoneNumber)?;
+    let now = time();
+    let items = price_items(
+        pricing::customer_price_list_id(&phone_number),
+        items_input,
+        now,
+    )?;
+    promotions::discount_for(&coupon_code, &phone_number, &items, now)
+        .map_err(OrderError::InvalidCoupon)
+}
+
+/// Updates an order's status and queues a notification for the customer. Cancelling an
+/// order puts its packs back in stock.
+fn set_order_status(
+    order_id: u64,
+    status: OrderStatus,
+    timestamp: u64,
+) -> Result<Order, StoreError> {
+    let was_cancelled =
+        store::get_order(order_id).is_some_and(|order| order.status == OrderStatus::Cancelled);
+    let updated_order = store::update_order_status(order_id, status, timestamp)?;
+    if updated_order.status == OrderStatus::Cancelled && !was_cancelled {
+        catalog::restock(&updated_order.items);
     }
+    notifications::enqueue_status_change(&updated_order, timestamp);
+    Ok(updated_order)
+}
+
+#[query]
+fn get_my_orders(phone_number: String) -> Result<Vec<Order>, OrderError> {
+    let phone_number =
+        resolve_customer_phone(&phone_number).map_err(OrderError::InvalidPhoneNumber)?;
     Ok(store::get_orders_by_phone(&phone_number))
 }
 
+/// The edits of one of the customer's orders, oldest first.
+#[query]
+fn get_order_edits(
+    order_id: u64,
+    requestor_phone_number: String,
+) -> Result<Vec<OrderEdit>, OrderError> {
+    let requestor_phone_number =
+        resolve_customer_phone(&requestor_phone_number).map_err(OrderError::InvalidPhoneNumber)?;
+    match store::get_order(order_id) {
+        Some(order) if order.user_phone_number == requestor_phone_number => {
+            Ok(store::get_order_edits(order_id))
+        }
+        Some(_) => Err(OrderError::AccessDenied),
+        None => Err(OrderError::OrderNotFound),
+    }
+}
+
 #[query]
 fn get_order_details(order_id: u64, requestor_phone_number: String) -> Result<Order, OrderError> {
+    let requestor_phone_number =
+        resolve_customer_phone(&requestor_phone_number).map_err(OrderError::InvalidPhoneNumber)?;
     match store::get_order(order_id) {
         Some(order) => {
             if order.user_phone_number == requestor_phone_number {
@@ -173,144 +861,995 @@ fn get_order_details(order_id: u64, requestor_phone_number: String) -> Result<Or
 
 #[update]
 fn cancel_my_order(order_id: u64, requestor_phone_number: String) -> Result<Order, OrderError> {
-    if requestor_phone_number.trim().is_empty() {
-        return Err(OrderError::InvalidInput(
-            "Requestor phone number cannot be empty".to_string(),
+    let requestor_phone_number =
+        resolve_customer_phone(&requestor_phone_number).map_err(OrderError::InvalidPhoneNumber)?;
+
+    match store::get_order(order_id) {
+        Some(order) => {
+            if order.user_phone_number != requestor_phone_number {
+                return Err(OrderError::AccessDenied);
+            }
+
+            // Check if the order is in a cancellable state (e.g., Pending)
+            if order.status != OrderStatus::Pending {
+                return Err(OrderError::CannotCancelOrder(
+                    "Order can only be cancelled if it is in Pending status.".to_string(),
+                ));
+            }
+
+            Ok(set_order_status(order_id, OrderStatus::Cancelled, time())?)
+        }
+        None => Err(OrderError::OrderNotFound),
+    }
+}
+
+/// Replaces the items of a pending order and optionally its address, see `edit_order`.
+/// Orders have no delivery slots, so there is no slot cut-off: editing ends when the order
+/// leaves `Pending`, i.e. once it is confirmed or cancelled (also by the auto-cancel job).
+#[update]
+fn update_my_order(
+    order_id: u64,
+    requestor_phone_number: String,
+    items_input: Vec<OrderItemInput>,
+    delivery_address: Option<String>,
+    address_id: Option<u64>,
+) -> Result<Order, OrderError> {
+    let requestor_phone_number =
+        resolve_customer_phone(&requestor_phone_number).map_err(OrderError::InvalidPhoneNumber)?;
+    let order = store::get_order(order_id).ok_or(OrderError::OrderNotFound)?;
+    if order.user_phone_number != requestor_phone_number {
+        return Err(OrderError::AccessDenied);
+    }
+    if order.status != OrderStatus::Pending {
+        return Err(OrderError::CannotEditOrder(
+            "Order can only be changed if it is in Pending status.".to_string(),
+        ));
+    }
+    edit_order(
+        order,
+        items_input,
+        delivery_address,
+        address_id,
+        OrderEditor::Customer,
+        None,
+        time(),
+    )
+}
+
+///////////////////////////////////////////////////////////
+// ADMIN FUNCTIONS
+///////////////////////////////////////////////////////////
+
+const MAX_AUDIT_PAGE_SIZE: u64 = 100;
+
+// Admin Product Management
+
+/// All products. With `lang` ("en", "hi") the texts are returned in that language, falling
+/// back to English, and the translations are left out; without it products come as stored.
+#[query]
+fn get_products(lang: Option<String>) -> Vec<Product> {
+    let products = store::get_all_products();
+    match lang {
+        Some(lang) => products
+            .into_iter()
+            .map(|product| i18n::localize_product(product, &lang))
+            .collect(),
+        None => products,
+    }
+}
+
+/// Products matching a search by name, category and tags. See `ProductSearch`.
+#[query]
+fn search_products(search: ProductSearch) -> Vec<Product> {
+    catalog::search_products(search)
+}
+
+/// All categories; `parent_id` links them into a tree.
+#[query]
+fn get_categories() -> Vec<Category> {
+    store::get_all_categories()
+}
+
+/// The base price changes of a product, oldest first, including scheduled ones.
+#[query]
+fn get_price_history(product_id: u64) -> Vec<PriceChange> {
+    store::get_price_changes(product_id)
+}
+
+/// Messages for the codes of `errors::ErrorCode` in the given language (English fallback).
+#[query]
+fn get_error_messages(lang: String) -> Vec<ErrorMessage> {
+    i18n::error_messages(&lang)
+}
+
+/// Products together with a certificate and witness, so the frontend can check the prices
+/// against the certified root hash instead of trusting the replica that answered the query.
+#[query]
+fn get_products_certified() -> Result<CertifiedProducts, CertificationError> {
+    let certificate = ic_cdk::api::data_certificate().ok_or(CertificationError::NotInQuery)?;
+    Ok(CertifiedProducts {
+        products: store::get_all_products(),
+        certificate,
+        witness: certified::products_witness()?,
+    })
+}
+
+#[derive(candid::CandidType, serde::Deserialize)]
+struct AddProductPayload {
+    name: String,
+    description: String,
+    price: f64,
+    unit: String,
+    // On updates, None keeps the current translations, category, tags, image, variants and step
+    translations: Option<Vec<ProductTranslation>>,
+    category_id: Option<u64>,
+    tags: Option<Vec<String>>,
+    image_id: Option<u64>, // Uploaded with `upload_image_chunk` and `finish_image_upload`
+    variants: Option<Vec<ProductVariant>>,
+    quantity_step: Option<f64>,
+}
+
+/// Checks the fields of a new product, or of the product with ID `id`, reporting every
+/// invalid field at once. New products have ID 0.
+fn validate_product_payload(
+    payload: AddProductPayload,
+    id: Option<u64>,
+) -> Result<Product, ProductError> {
+    let mut errors = Vec::new();
+    if payload.name.trim().is_empty() {
+        errors.push(FieldError::new(
+            "name",
+            FieldErrorKind::Required,
+            "Name cannot be empty.",
+        ));
+    }
+    if payload.unit.trim().is_empty() {
+        errors.push(FieldError::new(
+            "unit",
+            FieldErrorKind::Required,
+            "Unit cannot be empty.",
+        ));
+    }
+    if !payload.price.is_finite() || payload.price < 0.0 {
+        errors.push(FieldError::new(
+            "price",
+            FieldErrorKind::OutOfRange,
+            format!("Invalid price {}.", payload.price),
+        ));
+    }
+    let translations = match payload.translations.map(i18n::validate_translations) {
+        Some(Err(translation_errors)) => {
+            errors.extend(translation_errors);
+            None
+        }
+        Some(Ok(translations)) => Some(translations),
+        None => None,
+    };
+    if let Some(category_id) = payload.category_id {
+        if store::get_category(category_id).is_none() {
+            errors.push(FieldError::new(
+                "category_id",
+                FieldErrorKind::Unsupported,
+                format!("Category {} not found.", category_id),
+            ));
+        }
+    }
+    if let Some(image_id) = payload.image_id {
+        if store::get_image(image_id).is_none() {
+            errors.push(FieldError::new(
+                "image_id",
+                FieldErrorKind::Unsupported,
+                format!("Image {} not found.", image_id),
+            ));
+        }
+    }
+    let tags = match payload.tags.map(catalog::validate_tags) {
+        Some(Err(tag_errors)) => {
+            errors.extend(tag_errors);
+            None
+        }
+        Some(Ok(tags)) => Some(tags),
+        None => None,
+    };
+    let variants = match payload
+        .variants
+        .map(|variants| catalog::validate_variants(id, variants))
+    {
+        Some(Err(variant_errors)) => {
+            errors.extend(variant_errors);
+            None
+        }
+        Some(Ok(variants)) => Some(variants),
+        None => None,
+    };
+    if payload
+        .quantity_step
+        .is_some_and(|step| !step.is_finite() || step <= 0.0)
+    {
+        errors.push(FieldError::new(
+            "quantity_step",
+            FieldErrorKind::OutOfRange,
+            "The quantity step must be above 0.",
+        ));
+    }
+    if errors.is_empty() {
+        Ok(Product {
+            id: id.unwrap_or(0),
+            name: payload.name,
+            description: payload.description,
+            price: payload.price,
+            unit: payload.unit,
+            translations,
+            category_id: payload.category_id,
+            tags,
+            image_id: payload.image_id,
+            variants,
+            quantity_step: payload.quantity_step,
+        })
+    } else {
+        Err(ProductError::InvalidInput(errors))
+    }
+}
+
+#[update(guard = "is_dev")]
+fn add_product_admin(payload: AddProductPayload) -> Result<u64, ProductError> {
+    let args_summary = format!("name={}, price={}", payload.name, payload.price);
+    let result = validate_product_payload(payload, None)
+        .and_then(|product| Ok(store::add_product(product)?));
+    audit::record("add_product_admin", args_summary, &result);
+    result
+}
+
+#[update(guard = "is_dev")]
+fn update_product_admin(id: u64, payload: AddProductPayload) -> Result<Product, ProductError> {
+    let args_summary = format!("id={}, name={}, price={}", id, payload.name, payload.price);
+    let result = validate_product_payload(payload, Some(id)).and_then(|product| {
+        let current = store::get_product_by_id(id).ok_or(ProductError::ProductNotFound(id))?;
+        if current.price != product.price {
+            let now = time();
+            pricing::record_price_change(&current, product.price, now, now);
+        }
+        let product = Product {
+            id, // Keep the original ID
+            translations: product.translations.or(current.translations),
+            category_id: product.category_id.or(current.category_id),
+            tags: product.tags.or(current.tags),
+            image_id: product.image_id.or(current.image_id),
+            variants: product.variants.or(current.variants),
+            quantity_step: product.quantity_step.or(current.quantity_step),
+            ..product
+        };
+        Ok(store::update_product(id, product)?)
+    });
+    audit::record("update_product_admin", args_summary, &result);
+    result
+}
+
+/// Sets the number of packs of a variant in stock; None stops tracking its stock.
+#[update(guard = "is_dev")]
+fn set_variant_stock_admin(
+    product_id: u64,
+    variant_id: u64,
+    stock: Option<u64>,
+) -> Result<Product, ProductError> {
+    let args_summary = format!(
+        "product_id={}, variant_id={}, stock={:?}",
+        product_id, variant_id, stock
+    );
+    let result = store::get_product_by_id(product_id)
+        .ok_or(ProductError::ProductNotFound(product_id))
+        .and_then(|mut product| {
+            let variant = product
+                .variants
+                .iter_mut()
+                .flatten()
+                .find(|variant| variant.id == variant_id)
+                .ok_or(ProductError::VariantNotFound {
+                    product_id,
+                    variant_id,
+                })?;
+            variant.stock = stock;
+            Ok(store::update_product(product_id, product)?)
+        });
+    audit::record("set_variant_stock_admin", args_summary, &result);
+    result
+}
+
+/// Schedules a change of a product's base price at `effective_from` (nanoseconds since
+/// epoch, in the future). Orders placed from then on use the new price.
+#[update(guard = "is_dev")]
+fn schedule_price_change_admin(
+    product_id: u64,
+    price: f64,
+    effective_from: u64,
+) -> Result<PriceChange, ProductError> {
+    let args_summary = format!(
+        "product_id={}, price={}, effective_from={}",
+        product_id, price, effective_from
+    );
+    let result = pricing::schedule_price_change(product_id, price, effective_from, time())
+        .inspect(|change| timer::schedule_at(change.effective_from));
+    audit::record("schedule_price_change_admin", args_summary, &result);
+    result
+}
+
+#[update(guard = "is_dev")]
+fn cancel_price_change_admin(
+    product_id: u64,
+    effective_from: u64,
+) -> Result<PriceChange, ProductError> {
+    let args_summary = format!(
+        "product_id={}, effective_from={}",
+        product_id, effective_from
+    );
+    let result = pricing::cancel_price_change(product_id, effective_from, time());
+    audit::record("cancel_price_change_admin", args_summary, &result);
+    result
+}
+
+// Admin Product Images
+
+/// Appends a chunk to an image upload. Returns the size of the upload so far.
+#[update(guard = "is_dev")]
+fn upload_image_chunk(upload_id: String, chunk: Vec<u8>) -> Result<u64, ImageError> {
+    let args_summary = format!("upload_id={}, bytes={}", upload_id, chunk.len());
+    let result = images::append_upload_chunk(ic_cdk::caller(), upload_id, &chunk);
+    audit::record("upload_image_chunk", args_summary, &result);
+    result
+}
+
+/// Stores an uploaded image once its content type and SHA-256 hash check out. Uploading
+/// an image that is already stored returns the existing one.
+#[update(guard = "is_dev")]
+fn finish_image_upload(
+    upload_id: String,
+    content_type: String,
+    sha256: Vec<u8>,
+) -> Result<ProductImage, ImageError> {
+    let args_summary = format!("upload_id={}, content_type={}", upload_id, content_type);
+    let result =
+        images::finish_upload(ic_cdk::caller(), &upload_id, &content_type, &sha256, time());
+    audit::record("finish_image_upload", args_summary, &result);
+    result
+}
+
+#[query(guard = "is_dev")]
+fn get_images_admin() -> Vec<ProductImage> {
+    store::get_all_images()
+}
+
+#[update(guard = "is_dev")]
+fn remove_image_admin(id: u64) -> Result<ProductImage, ImageError> {
+    let result = images::remove_image(id);
+    audit::record("remove_image_admin", format!("id={}", id), &result);
+    result
+}
+
+// Admin Categories
+
+#[derive(candid::CandidType, serde::Deserialize)]
+struct CategoryPayload {
+    name: String,
+    parent_id: Option<u64>,
+}
+
+#[update(guard = "is_dev")]
+fn create_category_admin(payload: CategoryPayload) -> Result<Category, CategoryError> {
+    let args_summary = format!("name={}, parent_id={:?}", payload.name, payload.parent_id);
+    let result = catalog::validate_category(Category {
+        id: store::next_category_id(),
+        name: payload.name,
+        parent_id: payload.parent_id,
+    })
+    .inspect(|category| store::put_category(category.clone()));
+    audit::record("create_category_admin", args_summary, &result);
+    result
+}
+
+/// Renames a category or moves it, with its subcategories, under another parent.
+#[update(guard = "is_dev")]
+fn update_category_admin(id: u64, payload: CategoryPayload) -> Result<Category, CategoryError> {
+    let args_summary = format!(
+        "id={}, name={}, parent_id={:?}",
+        id, payload.name, payload.parent_id
+    );
+    let result = store::get_category(id)
+        .ok_or(CategoryError::CategoryNotFound(id))
+        .and_then(|_| {
+            catalog::validate_category(Category {
+                id,
+                name: payload.name,
+                parent_id: payload.parent_id,
+            })
+        })
+        .inspect(|category| store::put_category(category.clone()));
+    audit::record("update_category_admin", args_summary, &result);
+    result
+}
+
+/// Removes a category without subcategories; its products move to the parent category.
+#[update(guard = "is_dev")]
+fn remove_category_admin(id: u64) -> Result<Category, CategoryError> {
+    let result = catalog::remove_category(id);
+    audit::record("remove_category_admin", format!("id={}", id), &result);
+    result
+}
+
+// Admin Promotions
+
+#[query(guard = "is_dev")]
+fn get_coupons_admin() -> Vec<CouponSummary> {
+    store::get_all_coupons()
+        .into_iter()
+        .map(|coupon| CouponSummary {
+            times_used: promotions::times_used(&coupon.code, None),
+            coupon,
+        })
+        .collect()
+}
+
+/// Creates a coupon, or replaces the one with the same code. Uses made so far still count
+/// towards the limits of the replaced coupon.
+#[update(guard = "is_dev")]
+fn set_coupon_admin(coupon: Coupon) -> Result<Coupon, CouponError> {
+    let args_summary = format!(
+        "code={}, discount={:?}, enabled={}",
+        coupon.code, coupon.discount, coupon.enabled
+    );
+    let result = promotions::validate_coupon(coupon).inspect(|coupon| {
+        store::put_coupon(coupon.clone());
+    });
+    audit::record("set_coupon_admin", args_summary, &result);
+    result
+}
+
+#[update(guard = "is_dev")]
+fn remove_coupon_admin(code: String) -> Result<Coupon, CouponError> {
+    let code = promotions::normalize_code(&code);
+    let result = store::remove_coupon(&code).ok_or(CouponError::CouponNotFound(code.clone()));
+    audit::record("remove_coupon_admin", format!("code={}", code), &result);
+    result
+}
+
+// Admin Price Lists
+
+#[derive(candid::CandidType, serde::Deserialize)]
+struct PriceListPayload {
+    name: String,
+    kind: PriceListKind,
+    prices: Vec<ProductPrice>,
+}
+
+#[query(guard = "is_dev")]
+fn get_price_lists_admin() -> Vec<PriceList> {
+    store::get_all_price_lists()
+}
+
+#[update(guard = "is_dev")]
+fn create_price_list_admin(payload: PriceListPayload) -> Result<PriceList, PriceListError> {
+    let args_summary = format!(
+        "name={}, kind={:?}, products={}",
+        payload.name,
+        payload.kind,
+        payload.prices.len()
+    );
+    let result = pricing::validate_price_list(PriceList {
+        id: store::next_price_list_id(),
+        name: payload.name,
+        kind: payload.kind,
+        prices: payload.prices,
+    })
+    .inspect(|list| store::put_price_list(list.clone()));
+    audit::record("create_price_list_admin", args_summary, &result);
+    result
+}
+
+#[update(guard = "is_dev")]
+fn update_price_list_admin(
+    id: u64,
+    payload: PriceListPayload,
+) -> Result<PriceList, PriceListError> {
+    let args_summary = format!(
+        "id={}, name={}, kind={:?}, products={}",
+        id,
+        payload.name,
+        payload.kind,
+        payload.prices.len()
+    );
+    let result = match store::get_price_list(id) {
+        Some(_) => pricing::validate_price_list(PriceList {
+            id,
+            name: payload.name,
+            kind: payload.kind,
+            prices: payload.prices,
+        })
+        .inspect(|list| store::put_price_list(list.clone())),
+        None => Err(PriceListError::PriceListNotFound(id)),
+    };
+    audit::record("update_price_list_admin", args_summary, &result);
+    result
+}
+
+/// Removes a price list that is no longer assigned to any customer.
+#[update(guard = "is_dev")]
+fn remove_price_list_admin(id: u64) -> Result<PriceList, PriceListError> {
+    let assigned = pricing::customers_with_price_list(id).len() as u64;
+    let result = if assigned > 0 {
+        Err(PriceListError::InUse(assigned))
+    } else {
+        store::remove_price_list(id).ok_or(PriceListError::PriceListNotFound(id))
+    };
+    audit::record("remove_price_list_admin", format!("id={}", id), &result);
+    result
+}
+
+/// Assigns a wholesale or custom price list to a customer, or removes the assignment with
+/// `None` so the customer pays retail prices again.
+#[update(guard = "is_dev")]
+fn assign_price_list_admin(
+    phone_number: String,
+    price_list_id: Option<u64>,
+) -> Result<UserProfile, PriceListError> {
+    let args_summary = format!(
+        "phone_ref={}, price_list_id={:?}",
+        audit::phone_ref(&phone_number),
+        price_list_id
+    );
+    let result = resolve_customer_phone(&phone_number)
+        .map_err(PriceListError::InvalidPhoneNumber)
+        .and_then(|phone_number| assign_price_list(&phone_number, price_list_id));
+    // AlreadyAssigned names another customer, so only the error code is recorded
+    let outcome = result.as_ref().map_err(ErrorCode::code);
+    audit::record("assign_price_list_admin", args_summary, &outcome);
+    result
+}
+
+fn assign_price_list(
+    phone_number: &String,
+    price_list_id: Option<u64>,
+) -> Result<UserProfile, PriceListError> {
+    let mut profile = match store::get_user_profile(phone_number) {
+        Some(profile) if profile.deleted_at.is_none() => profile,
+        _ => return Err(PriceListError::ProfileNotFound),
+    };
+    if let Some(id) = price_list_id {
+        let list = store::get_price_list(id).ok_or(PriceListError::PriceListNotFound(id))?;
+        if list.kind == PriceListKind::Custom {
+            if let Some(other) = pricing::customers_with_price_list(id)
+                .into_iter()
+                .find(|other| other != phone_number)
+            {
+                return Err(PriceListError::AlreadyAssigned(other));
+            }
+        }
+    }
+    profile.price_list_id = price_list_id;
+    store::update_user_profile(profile.clone());
+    Ok(profile)
+}
+
+// Admin User Management
+
+#[query(guard = "is_dev")]
+fn get_all_customers() -> Vec<UserProfile> {
+    store::get_all_user_profiles()
+        .into_iter()
+        .filter(|profile| profile.deleted_at.is_none())
+        .collect()
+}
+
+/// Anonymises a customer instead of removing them, so that their orders and the
+/// aggregated sales figures stay intact. Returns the anonymised profile.
+#[update(guard = "is_dev")]
+fn delete_profile_admin(phone_number: String) -> Result<UserProfile, DataDeletionError> {
+    let result = resolve_customer_phone(&phone_number)
+        .map_err(DataDeletionError::InvalidPhoneNumber)
+        .and_then(|normalized| anonymise_customer(&normalized, time()));
+    // The audit log can't be scrubbed later, so a deletion is recorded under the tombstone
+    let args_summary = match &result {
+        Ok(anonymised) => format!("tombstone={}", anonymised.phone_number),
+        Err(_) => format!("phone_ref={}", audit::phone_ref(&phone_number)),
+    };
+    audit::record("delete_profile_admin", args_summary, &result);
+    result
+}
+
+#[query(guard = "is_dev")]
+fn get_deletion_requests() -> Vec<DeletionRequest> {
+    store::get_deletion_requests()
+}
+
+/// Replaces the customer's phone number, name and address with a tombstone ID on the
+/// profile, on every order and in the notification outbox, re-keys the profile under that
+/// tombstone ID and drops the customer's requests, cart, OTP challenge and redirects.
+/// The edits of the orders lose their previous addresses, items and reasons.
+///
+/// Audit log entries are hash-chained and kept as written: entries recorded for admin
+/// actions on the customer before the deletion keep their phone number.
+fn anonymise_customer(phone_number: &String, now: u64) -> Result<UserProfile, DataDeletionError> {
+    let profile = match store::get_user_profile(phone_number) {
+        Some(profile) if profile.deleted_at.is_some() => {
+            return Err(DataDeletionError::AlreadyAnonymised)
+        }
+        Some(profile) => profile,
+        None => return Err(DataDeletionError::ProfileNotFound),
+    };
+
+    let active_orders: Vec<u64> = store::get_orders_by_phone(phone_number)
+        .into_iter()
+        .filter(|order| {
+            order.status != OrderStatus::Delivered && order.status != OrderStatus::Cancelled
+        })
+        .map(|order| order.id)
+        .collect();
+    if !active_orders.is_empty() {
+        return Err(DataDeletionError::HasActiveOrders(active_orders));
+    }
+
+    let tombstone = store::next_tombstone_id();
+    let order_ids = store::update_orders_by_phone(phone_number, |order| {
+        order.user_phone_number = tombstone.clone();
+        order.customer_name = tombstone.clone();
+        order.delivery_address = tombstone.clone();
+    });
+    // The edit history holds earlier addresses, and admins' reasons may name the customer
+    for order_id in order_ids {
+        store::update_order_edits(order_id, |edit| {
+            edit.previous_delivery_address = tombstone.clone();
+            edit.previous_items.clear();
+            edit.reason = None;
+        });
+    }
+    notifications::forget_customer(phone_number, &tombstone);
+
+    let anonymised = UserProfile {
+        phone_number: tombstone.clone(),
+        name: tombstone.clone(),
+        address: tombstone,
+        order_ids: profile.order_ids,
+        deleted_at: Some(now),
+        addresses: None,
+        default_address_id: None,
+        verified_at: None,
+        notifications_opt_out: None,
+        price_list_id: None,
+    };
+    store::delete_user_profile(phone_number);
+    store::update_user_profile(anonymised.clone());
+    store::remove_deletion_request(phone_number);
+    store::remove_phone_change_request(phone_number);
+    store::remove_cart(phone_number);
+    store::remove_otp_challenge(phone_number);
+    store::remove_otp_lockout(phone_number);
+    // Old numbers of the customer are personal data too
+    store::remove_phone_redirects_to(phone_number);
+    Ok(anonymised)
+}
+
+// Admin Phone Number Changes
+
+#[query(guard = "is_dev")]
+fn get_phone_change_requests() -> Vec<PhoneChangeRequest> {
+    store::get_phone_change_requests()
+}
+
+/// Moves a customer to a new phone number, either on the admin's own initiative or to
+/// approve a customer's pending request. Returns the re-keyed profile.
+#[update(guard = "is_dev")]
+fn change_phone_number_admin(
+    current_phone_number: String,
+    new_phone_number: String,
+) -> Result<UserProfile, PhoneChangeError> {
+    let args_summary = format!(
+        "current_ref={}, new_ref={}",
+        audit::phone_ref(&current_phone_number),
+        audit::phone_ref(&new_phone_number)
+    );
+    let result = resolve_customer_phone(&current_phone_number)
+        .and_then(|current| Ok((current, normalize_phone(&new_phone_number)?)))
+        .map_err(PhoneChangeError::InvalidPhoneNumber)
+        .and_then(|(current, new)| change_customer_phone_number(&current, &new));
+    // NumberAlreadyInUse carries the phone number, so only the error code is recorded
+    let outcome = result.as_ref().map_err(ErrorCode::code);
+    audit::record("change_phone_number_admin", args_summary, &outcome);
+    result
+}
+
+fn validate_phone_change(
+    current_phone_number: &String,
+    new_phone_number: &String,
+) -> Result<UserProfile, PhoneChangeError> {
+    if current_phone_number == new_phone_number {
+        return Err(PhoneChangeError::SameNumber);
+    }
+    let profile = match store::get_user_profile(current_phone_number) {
+        Some(profile) if profile.deleted_at.is_none() => profile,
+        _ => return Err(PhoneChangeError::ProfileNotFound),
+    };
+    if store::get_user_profile(new_phone_number).is_some() {
+        return Err(PhoneChangeError::NumberAlreadyInUse(
+            new_phone_number.clone(),
         ));
     }
+    Ok(profile)
+}
+
+/// Re-keys the profile, its orders and any pending requests under the new phone number and
+/// leaves a redirect from the old one. Runs without awaits, so it is applied atomically.
+fn change_customer_phone_number(
+    current_phone_number: &String,
+    new_phone_number: &String,
+) -> Result<UserProfile, PhoneChangeError> {
+    let mut profile = validate_phone_change(current_phone_number, new_phone_number)?;
+
+    store::update_orders_by_phone(current_phone_number, |order| {
+        order.user_phone_number = new_phone_number.clone();
+    });
+    notifications::move_customer(current_phone_number, new_phone_number);
+
+    store::delete_user_profile(current_phone_number);
+    profile.phone_number = new_phone_number.clone();
+    // The new number hasn't been proven yet (the OTP flow marks it verified afterwards)
+    profile.verified_at = None;
+    store::update_user_profile(profile.clone());
+
+    if let Some(request) = store::remove_deletion_request(current_phone_number) {
+        store::add_deletion_request(DeletionRequest {
+            phone_number: new_phone_number.clone(),
+            ..request
+        });
+    }
+    if let Some(cart) = store::remove_cart(current_phone_number) {
+        store::put_cart(Cart {
+            phone_number: new_phone_number.clone(),
+            ..cart
+        });
+    }
+    store::remove_phone_change_request(current_phone_number);
+    store::set_phone_redirect(current_phone_number, new_phone_number);
+
+    Ok(profile)
+}
+
+// Admin Order Management
+
+#[query(guard = "is_dev")]
+fn get_all_orders() -> Result<Vec<Order>, OrderError> {
+    Ok(store::get_all_orders())
+}
+
+#[query(guard = "is_dev")]
+fn get_order_details_admin(order_id: u64) -> Result<Order, OrderError> {
+    match store::get_order(order_id) {
+        Some(order) => Ok(order),
+        None => Err(OrderError::OrderNotFound),
+    }
+}
 
+/// The edits of an order with the editors and reasons, oldest first.
+#[query(guard = "is_dev")]
+fn get_order_edits_admin(order_id: u64) -> Result<Vec<OrderEdit>, OrderError> {
     match store::get_order(order_id) {
-        Some(mut order) => {
-            if order.user_phone_number != requestor_phone_number {
-                return Err(OrderError::AccessDenied);
-            }
+        Some(_) => Ok(store::get_order_edits(order_id)),
+        None => Err(OrderError::OrderNotFound),
+    }
+}
 
-            // Check if the order is in a cancellable state (e.g., Pending)
-            if order.status != OrderStatus::Pending {
-                return Err(OrderError::CannotCancelOrder(
-                    "Order can only be cancelled if it is in Pending status.".to_string(),
-                ));
-            }
+#[update(guard = "is_dev")]
+fn update_order_status_admin(order_id: u64, new_status: OrderStatus) -> Result<Order, OrderError> {
+    let args_summary = format!("order_id={}, new_status={:?}", order_id, new_status);
+    let result = set_order_status(order_id, new_status, time()).map_err(OrderError::from);
+    audit::record("update_order_status_admin", args_summary, &result);
+    result
+}
 
-            let timestamp = time();
-            match store::update_order_status(order_id, OrderStatus::Cancelled, timestamp) {
-                Ok(updated_order) => Ok(updated_order),
-                Err(e) => {
-                    // Check if the error message indicates the order was not found
-                    // This could happen in a race condition if the order was deleted
-                    // or cancelled by another process between the get_order and update_order_status calls.
-                    if e.contains("not found") {
-                        Err(OrderError::OrderNotFound)
-                    } else {
-                        Err(OrderError::StorageError(e))
-                    }
-                }
+/// Replaces the items of a pending or confirmed order and optionally its address, see
+/// `edit_order`. The reason is kept with the edit.
+#[update(guard = "is_dev")]
+fn update_order_admin(
+    order_id: u64,
+    items_input: Vec<OrderItemInput>,
+    delivery_address: Option<String>,
+    address_id: Option<u64>,
+    reason: String,
+) -> Result<Order, OrderError> {
+    let args_summary = format!(
+        "order_id={}, items={}, reason={}",
+        order_id,
+        items_input.len(),
+        reason
+    );
+    let result = if reason.trim().is_empty() {
+        Err(OrderError::InvalidInput(vec![FieldError::new(
+            "reason",
+            FieldErrorKind::Required,
+            "A reason is required",
+        )]))
+    } else {
+        match store::get_order(order_id) {
+            Some(order)
+                if matches!(order.status, OrderStatus::Pending | OrderStatus::Confirmed) =>
+            {
+                edit_order(
+                    order,
+                    items_input,
+                    delivery_address,
+                    address_id,
+                    OrderEditor::Admin(ic_cdk::caller()),
+                    Some(reason.trim().to_string()),
+                    time(),
+                )
             }
+            Some(_) => Err(OrderError::CannotEditOrder(
+                "Order can only be changed if it is Pending or Confirmed.".to_string(),
+            )),
+            None => Err(OrderError::OrderNotFound),
         }
-        None => Err(OrderError::OrderNotFound),
-    }
+    };
+    audit::record("update_order_admin", args_summary, &result);
+    result
 }
 
-///////////////////////////////////////////////////////////
-// ADMIN FUNCTIONS
-///////////////////////////////////////////////////////////
+// Admin Scheduled Jobs
 
-// Admin Product Management
+#[derive(candid::CandidType, serde::Deserialize)]
+struct JobPayload {
+    name: String,
+    kind: JobKind,
+    schedule: JobSchedule,
+    enabled: bool,
+    next_run_at: Option<u64>, // Defaults to now
+}
 
-#[query]
-fn get_products() -> Vec<Product> {
-    store::get_all_products()
+#[query(guard = "is_dev")]
+fn get_jobs() -> Vec<Job> {
+    store::get_all_jobs()
 }
 
-#[derive(candid::CandidType, serde::Deserialize)]
-struct AddProductPayload {
-    name: String,
-    description: String,
-    price: f64,
-    unit: String,
+#[update(guard = "is_dev")]
+fn schedule_job_admin(payload: JobPayload) -> Result<Job, JobError> {
+    let args_summary = format!(
+        "name={}, kind={:?}, schedule={:?}",
+        payload.name, payload.kind, payload.schedule
+    );
+    let result = jobs::validate(&payload.kind, &payload.schedule).map(|_| {
+        let job = Job {
+            id: store::get_next_job_id(),
+            name: payload.name,
+            kind: payload.kind,
+            schedule: payload.schedule,
+            enabled: payload.enabled,
+            next_run_at: payload.next_run_at.unwrap_or_else(time),
+            last_run_at: None,
+            last_result: None,
+        };
+        store::put_job(job.clone());
+        job
+    });
+    audit::record("schedule_job_admin", args_summary, &result);
+    timer::rearm();
+    result
 }
 
 #[update(guard = "is_dev")]
-fn add_product_admin(payload: AddProductPayload) -> Result<u64, String> {
-    let product_to_add = Product {
-        id: 0,
-        name: payload.name,
-        description: payload.description,
-        price: payload.price,
-        unit: payload.unit,
+fn update_job_admin(id: u64, payload: JobPayload) -> Result<Job, JobError> {
+    let args_summary = format!(
+        "id={}, kind={:?}, schedule={:?}, enabled={}",
+        id, payload.kind, payload.schedule, payload.enabled
+    );
+    let result = match store::get_job(id) {
+        Some(job) => jobs::validate(&payload.kind, &payload.schedule).map(|_| {
+            let updated = Job {
+                name: payload.name,
+                kind: payload.kind,
+                schedule: payload.schedule,
+                enabled: payload.enabled,
+                next_run_at: payload.next_run_at.unwrap_or(job.next_run_at),
+                ..job
+            };
+            store::put_job(updated.clone());
+            updated
+        }),
+        None => Err(JobError::JobNotFound(id)),
     };
-    store::add_product(product_to_add)
+    audit::record("update_job_admin", args_summary, &result);
+    timer::rearm();
+    result
 }
 
 #[update(guard = "is_dev")]
-fn update_product_admin(id: u64, payload: AddProductPayload) -> Result<Product, String> {
-    let product_update = Product {
-        id, // Keep the original ID
-        name: payload.name,
-        description: payload.description,
-        price: payload.price,
-        unit: payload.unit,
-    };
-    store::update_product(id, product_update)
+fn remove_job_admin(id: u64) -> Result<Job, JobError> {
+    let result = store::remove_job(id).ok_or(JobError::JobNotFound(id));
+    audit::record("remove_job_admin", format!("id={}", id), &result);
+    result
 }
 
-// Admin User Management
+/// Runs a job right away, independent of its schedule.
+#[update(guard = "is_dev")]
+fn run_job_now_admin(id: u64) -> Result<Job, JobError> {
+    let result = match store::get_job(id) {
+        Some(job) => Ok(jobs::run_job(job, time())),
+        None => Err(JobError::JobNotFound(id)),
+    };
+    audit::record("run_job_now_admin", format!("id={}", id), &result);
+    timer::rearm();
+    result
+}
 
+/// Daily summaries from `from_date` to `to_date` (both `YYYY-MM-DD`, inclusive).
 #[query(guard = "is_dev")]
-fn get_all_customers() -> Vec<UserProfile> {
-    store::get_all_user_profiles()
+fn get_daily_summaries(from_date: String, to_date: String) -> Vec<DailySummary> {
+    store::get_daily_summaries(&from_date, &to_date)
 }
 
+// Admin Backup/Restore
+
+/// Encodes the whole canister state for download with `export_snapshot_chunk`.
 #[update(guard = "is_dev")]
-fn delete_profile_admin(phone_number: String) -> Result<UserProfile, String> {
-    if phone_number.trim().is_empty() {
-        return Err("Phone number cannot be empty.".to_string());
-    }
-    match store::delete_user_profile(&phone_number) {
-        Some(deleted_profile) => Ok(deleted_profile),
-        None => Err(format!(
-            "Profile with phone number {} not found.",
-            phone_number
-        )),
-    }
+fn export_snapshot() -> Result<SnapshotManifest, SnapshotError> {
+    let result = snapshot::export(time());
+    audit::record("export_snapshot", String::new(), &result);
+    result
 }
 
-// Admin Order Management
-
 #[query(guard = "is_dev")]
-fn get_all_orders() -> Result<Vec<Order>, OrderError> {
-    Ok(store::get_all_orders())
+fn export_snapshot_chunk(index: u64) -> Result<SnapshotChunk, SnapshotError> {
+    snapshot::export_chunk(index)
 }
 
-#[query(guard = "is_dev")]
-fn get_order_details_admin(order_id: u64) -> Result<Order, OrderError> {
-    match store::get_order(order_id) {
-        Some(order) => Ok(order),
-        None => Err(OrderError::OrderNotFound),
-    }
+// Starting an import and uploading chunks are not audited: the audit log of a fresh canister
+// must stay empty until the snapshot's own log has been restored.
+#[update(guard = "is_dev")]
+fn begin_import_snapshot(manifest: SnapshotManifest) -> Result<(), SnapshotError> {
+    snapshot::begin_import(manifest)
 }
 
 #[update(guard = "is_dev")]
-fn update_order_status_admin(order_id: u64, new_status: OrderStatus) -> Result<Order, OrderError> {
-    let timestamp = time();
-    match store::update_order_status(order_id, new_status, timestamp) {
-        Ok(updated_order) => Ok(updated_order),
-        Err(e) => {
-            if e.contains("not found") {
-                Err(OrderError::OrderNotFound)
-            } else {
-                Err(OrderError::StorageError(e))
-            }
-        }
+fn import_snapshot_chunk(chunk: SnapshotChunk) -> Result<u64, SnapshotError> {
+    snapshot::import_chunk(chunk)
+}
+
+/// Restores the uploaded snapshot once every chunk has arrived and all checksums match.
+#[update(guard = "is_dev")]
+fn import_snapshot() -> Result<String, SnapshotError> {
+    let result = finish_snapshot_import(ic_cdk::caller(), time());
+    if result.is_ok() {
+        timer::rearm();
+    }
+    result
+}
+
+/// Only a successful import is audited, on top of the restored log: a failed one must leave
+/// the audit log empty, or the canister would no longer count as fresh for the next attempt.
+fn finish_snapshot_import(caller: Principal, now: u64) -> Result<String, SnapshotError> {
+    let result = snapshot::finish_import();
+    if result.is_ok() {
+        audit::record_at(caller, "import_snapshot", String::new(), &result, now);
     }
+    result
 }
 
 // Admin System Functions
 
 #[update(guard = "is_dev")]
-fn initialize_products() -> Result<String, String> {
+fn initialize_products() -> Result<String, SetupError> {
+    let result = seed_initial_products();
+    audit::record("initialize_products", String::new(), &result);
+    result
+}
+
+fn hindi(name: &str, description: &str, unit: &str) -> ProductTranslation {
+    ProductTranslation {
+        lang: "hi".to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        unit: Some(unit.to_string()),
+    }
+}
+
+fn seed_initial_products() -> Result<String, SetupError> {
     if store::is_initialized() {
-        return Err("Already initialized".to_string());
+        return Err(SetupError::AlreadyInitialized);
     }
 
     let initial_products = vec![
@@ -320,6 +1859,8 @@ fn initialize_products() -> Result<String, String> {
             description: "Fresh Cow Milk".to_string(),
             price: 70.0,
             unit: "litre".to_string(),
+            translations: Some(vec![hindi("दूध", "ताज़ा गाय का दूध", "लीटर")]),
+            ..Default::default()
         },
         Product {
             id: 1,
@@ -327,6 +1868,8 @@ fn initialize_products() -> Result<String, String> {
             description: "Fresh Homemade Paneer".to_string(),
             price: 300.0,
             unit: "kg".to_string(),
+            translations: Some(vec![hindi("पनीर", "ताज़ा घर का बना पनीर", "किलो")]),
+            ..Default::default()
         },
         Product {
             id: 2,
@@ -334,6 +1877,8 @@ fn initialize_products() -> Result<String, String> {
             description: "Curd with Fenugreek".to_string(),
             price: 100.0,
             unit: "kg".to_string(),
+            translations: Some(vec![hindi("मेथी दही", "मेथी वाला दही", "किलो")]),
+            ..Default::default()
         },
         Product {
             id: 3,
@@ -341,6 +1886,8 @@ fn initialize_products() -> Result<String, String> {
             description: "Sour Curd".to_string(),
             price: 50.0,
             unit: "kg".to_string(),
+            translations: Some(vec![hindi("खट्टी दही", "खट्टा दही", "किलो")]),
+            ..Default::default()
         },
         Product {
             id: 4,
@@ -348,6 +1895,8 @@ fn initialize_products() -> Result<String, String> {
             description: "Buttermilk".to_string(),
             price: 20.0,
             unit: "litre".to_string(),
+            translations: Some(vec![hindi("मट्ठा", "छाछ", "लीटर")]),
+            ..Default::default()
         },
         Product {
             id: 5,
@@ -355,6 +1904,8 @@ fn initialize_products() -> Result<String, String> {
             description: "Pure Desi Ghee".to_string(),
             price: 600.0,
             unit: "litre".to_string(),
+            translations: Some(vec![hindi("घी", "शुद्ध देसी घी", "लीटर")]),
+            ..Default::default()
         },
         Product {
             id: 6,
@@ -362,6 +1913,8 @@ fn initialize_products() -> Result<String, String> {
             description: "Fresh Milk Cream".to_string(),
             price: 300.0,
             unit: "kg".to_string(),
+            translations: Some(vec![hindi("मलाई", "ताज़ी दूध की मलाई", "किलो")]),
+            ..Default::default()
         },
     ];
 
@@ -373,20 +1926,361 @@ fn initialize_products() -> Result<String, String> {
         };
         match store::add_product(product_with_correct_id) {
             Ok(_) => count += 1,
-            Err(e) => return Err(format!("Failed to add product {}: {}", index, e)),
+            Err(e) => {
+                return Err(SetupError::StorageError(format!(
+                    "Failed to add product {}: {}",
+                    index, e
+                )))
+            }
         }
     }
 
-    store::mark_initialized()?;
+    store::mark_initialized().map_err(SetupError::StorageError)?;
 
     Ok(format!("Initialized {} products.", count))
 }
 
+// Admin Phone Number Settings
+
+#[query(guard = "is_dev")]
+fn get_phone_settings() -> PhoneSettings {
+    store::get_phone_settings()
+}
+
+#[update(guard = "is_dev")]
+fn set_phone_settings(settings: PhoneSettings) -> Result<(), SettingsError> {
+    let args_summary = format!("{:?}", settings);
+    let result = store::set_phone_settings(settings).map_err(SettingsError::StorageError);
+    audit::record("set_phone_settings", args_summary, &result);
+    result
+}
+
+/// Normalises all stored phone numbers, merging profiles that turn out to be duplicates.
+#[update(guard = "is_dev")]
+fn migrate_phone_numbers() -> Result<PhoneMigrationReport, SettingsError> {
+    let result: Result<PhoneMigrationReport, SettingsError> = Ok(
+        phone::migrate_stored_phone_numbers(&store::get_phone_settings()),
+    );
+    audit::record("migrate_phone_numbers", String::new(), &result);
+    result
+}
+
+// Admin Phone Verification Settings
+
+/// Returns the verification settings with the SMS gateway credentials redacted.
+#[query(guard = "is_dev")]
+fn get_verification_settings() -> VerificationSettings {
+    let mut settings = store::get_verification_settings();
+    if settings.sms_auth_header_value.is_some() {
+        settings.sms_auth_header_value = Some("********".to_string());
+    }
+    settings
+}
+
+#[update(guard = "is_dev")]
+fn set_verification_settings(settings: VerificationSettings) -> Result<(), SettingsError> {
+    let args_summary = format!(
+        "endpoint={:?}, ttl={}s, max_attempts={}, require_verified={}",
+        settings.sms_endpoint_url,
+        settings.code_ttl_seconds,
+        settings.max_attempts,
+        settings.require_verified_phone_for_orders
+    );
+    let mut errors = Vec::new();
+    if settings.code_ttl_seconds == 0 {
+        errors.push(FieldError::new(
+            "code_ttl_seconds",
+            FieldErrorKind::OutOfRange,
+            "The code TTL must be greater than zero.",
+        ));
+    }
+    if settings.max_attempts == 0 {
+        errors.push(FieldError::new(
+            "max_attempts",
+            FieldErrorKind::OutOfRange,
+            "Max attempts must be greater than zero.",
+        ));
+    }
+    let result = if errors.is_empty() {
+        store::set_verification_settings(settings).map_err(SettingsError::StorageError)
+    } else {
+        Err(SettingsError::InvalidInput(errors))
+    };
+    audit::record("set_verification_settings", args_summary, &result);
+    result
+}
+
+// Admin Notifications
+
+/// Returns the notification settings with the webhook credentials redacted.
+#[query(guard = "is_dev")]
+fn get_notification_settings() -> NotificationSettings {
+    let mut settings = store::get_notification_settings();
+    if settings.auth_header_value.is_some() {
+        settings.auth_header_value = Some("********".to_string());
+    }
+    settings
+}
+
+#[update(guard = "is_dev")]
+fn set_notification_settings(settings: NotificationSettings) -> Result<(), SettingsError> {
+    let args_summary = format!(
+        "enabled={}, channel={:?}, webhook={:?}, max_attempts={}",
+        settings.enabled, settings.channel, settings.webhook_url, settings.max_attempts
+    );
+    let result = if settings.max_attempts == 0 {
+        Err(SettingsError::InvalidInput(vec![FieldError::new(
+            "max_attempts",
+            FieldErrorKind::OutOfRange,
+            "Max attempts must be greater than zero.",
+        )]))
+    } else {
+        store::set_notification_settings(settings).map_err(SettingsError::StorageError)
+    };
+    audit::record("set_notification_settings", args_summary, &result);
+    // Deliveries may have been waiting for a webhook to be configured
+    timer::rearm();
+    result
+}
+
+/// Notifications that were given up on or are being retried after a failed delivery.
+#[query(guard = "is_dev")]
+fn get_failed_notifications() -> Vec<Notification> {
+    store::get_failed_notifications()
+}
+
+/// Puts a failed notification back into the outbox with a fresh set of attempts.
+#[update(guard = "is_dev")]
+fn retry_notification_admin(notification_id: u64) -> Result<Notification, NotificationError> {
+    let result = match store::get_notification(notification_id) {
+        Some(notification) if notification.state == NotificationState::Failed => {
+            let now = time();
+            let retried = Notification {
+                state: NotificationState::Pending,
+                attempts: 0,
+                next_attempt_at: now,
+                ..notification
+            };
+            store::put_notification(retried.clone());
+            timer::schedule_at(now);
+            Ok(retried)
+        }
+        Some(_) => Err(NotificationError::NotFailed(notification_id)),
+        None => Err(NotificationError::NotificationNotFound(notification_id)),
+    };
+    audit::record(
+        "retry_notification_admin",
+        format!("notification_id={}", notification_id),
+        &result,
+    );
+    result
+}
+
+// Admin Bulk Import/Export
+
+/// Appends a chunk to a CSV upload that can then be imported with `CsvSource::Upload`.
+/// Returns the size of the upload so far.
+#[update(guard = "is_dev")]
+fn upload_csv_chunk(upload_id: String, chunk: String) -> Result<u64, CsvError> {
+    let args_summary = format!("upload_id={}, bytes={}", upload_id, chunk.len());
+    let result = bulk::append_upload_chunk(ic_cdk::caller(), upload_id, &chunk);
+    audit::record("upload_csv_chunk", args_summary, &result);
+    result
+}
+
+/// Resolves the CSV of an import. An upload is consumed by a successful non-dry-run import.
+fn with_csv_source<F>(source: CsvSource, dry_run: bool, import: F) -> Result<ImportReport, CsvError>
+where
+    F: FnOnce(&str) -> Result<ImportReport, CsvError>,
+{
+    match source {
+        CsvSource::Inline(content) => import(&content),
+        CsvSource::Upload(upload_id) => {
+            let caller = ic_cdk::caller();
+            let content = bulk::get_upload(caller, &upload_id)
+                .ok_or(CsvError::UploadNotFound(upload_id.clone()))?;
+            let report = import(&content)?;
+            if !dry_run {
+                bulk::remove_upload(caller, &upload_id);
+            }
+            Ok(report)
+        }
+    }
+}
+
+fn import_args_summary(source: &CsvSource, dry_run: bool) -> String {
+    match source {
+        CsvSource::Inline(content) => {
+            format!("inline bytes={}, dry_run={}", content.len(), dry_run)
+        }
+        CsvSource::Upload(upload_id) => format!("upload_id={}, dry_run={}", upload_id, dry_run),
+    }
+}
+
+/// Imports products from CSV with the columns `id,name,description,price,unit`.
+#[update(guard = "is_dev")]
+fn import_products_csv(source: CsvSource, dry_run: bool) -> Result<ImportReport, CsvError> {
+    let args_summary = import_args_summary(&source, dry_run);
+    let result = with_csv_source(source, dry_run, |content| {
+        bulk::import_products(content, dry_run, time())
+    });
+    audit::record("import_products_csv", args_summary, &result);
+    result
+}
+
+/// Imports customer profiles from CSV with the columns `phone_number,name,address`.
+#[update(guard = "is_dev")]
+fn import_customers_csv(source: CsvSource, dry_run: bool) -> Result<ImportReport, CsvError> {
+    let args_summary = import_args_summary(&source, dry_run);
+    let settings = store::get_phone_settings();
+    let result = with_csv_source(source, dry_run, |content| {
+        bulk::import_customers(content, dry_run, &settings)
+    });
+    audit::record("import_customers_csv", args_summary, &result);
+    result
+}
+
+#[query(guard = "is_dev")]
+fn export_products_csv() -> String {
+    bulk::export_products()
+}
+
+#[query(guard = "is_dev")]
+fn export_customers_csv(tz_offset_minutes: Option<i32>) -> String {
+    bulk::export_customers(tz_offset_minutes.unwrap_or(date::DEFAULT_UTC_OFFSET_MINUTES))
+}
+
+/// Orders placed from `from_date` to `to_date` (both `YYYY-MM-DD`, inclusive) in local time.
+#[query(guard = "is_dev")]
+fn export_orders_csv(
+    from_date: String,
+    to_date: String,
+    tz_offset_minutes: Option<i32>,
+) -> Result<String, CsvError> {
+    let offset = tz_offset_minutes.unwrap_or(date::DEFAULT_UTC_OFFSET_MINUTES);
+    let parse = |field: &str, value: &str| {
+        date::parse_date(value).map_err(|e| {
+            CsvError::InvalidInput(vec![FieldError::new(field, FieldErrorKind::Unsupported, e)])
+        })
+    };
+    let (start, _) = date::day_range(parse("from_date", &from_date)?, offset);
+    let (_, end) = date::day_range(parse("to_date", &to_date)?, offset);
+    if end <= start {
+        return Err(CsvError::InvalidInput(vec![FieldError::new(
+            "to_date",
+            FieldErrorKind::OutOfRange,
+            "The end date must not be before the start date.",
+        )]));
+    }
+    Ok(bulk::export_orders(
+        bulk::orders_between(start, end),
+        offset,
+    ))
+}
+
+// Admin API Keys
+
+/// Creates an API key for the HTTP endpoints. The key is returned only once; the canister
+/// keeps just its hash.
+#[update(guard = "is_dev")]
+async fn create_api_key_admin(name: String) -> Result<CreatedApiKey, ApiKeyError> {
+    let caller = ic_cdk::caller();
+    let args_summary = format!("name={}", name);
+    let result = issue_api_key(caller, name).await;
+    audit::record_for(caller, "create_api_key_admin", args_summary, &result);
+    result
+}
+
+async fn issue_api_key(caller: Principal, name: String) -> Result<CreatedApiKey, ApiKeyError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(ApiKeyError::InvalidInput(vec![FieldError::new(
+            "name",
+            FieldErrorKind::Required,
+            "API key name cannot be empty.",
+        )]));
+    }
+    let (random_bytes,) = raw_rand().await.map_err(|(code, msg)| {
+        ApiKeyError::RandomnessUnavailable(format!("{:?}: {}", code, msg))
+    })?;
+    let secret: String = random_bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let key = format!("dk_{}", secret);
+
+    let id = store::get_next_api_key_id();
+    store::put_api_key(ApiKey {
+        id,
+        name,
+        prefix: key[..10].to_string(),
+        key_hash: http::hash_api_key(&key),
+        created_by: caller,
+        created_at: time(),
+        last_used_at: None,
+        revoked: false,
+    });
+    Ok(CreatedApiKey { id, key })
+}
+
+#[query(guard = "is_dev")]
+fn list_api_keys_admin() -> Vec<ApiKey> {
+    store::get_all_api_keys()
+}
+
+#[update(guard = "is_dev")]
+fn revoke_api_key_admin(id: u64) -> Result<ApiKey, ApiKeyError> {
+    let result = match store::get_api_key(id) {
+        Some(api_key) if api_key.revoked => Err(ApiKeyError::AlreadyRevoked(id)),
+        Some(api_key) => {
+            let revoked = ApiKey {
+                revoked: true,
+                ..api_key
+            };
+            store::put_api_key(revoked.clone());
+            Ok(revoked)
+        }
+        None => Err(ApiKeyError::ApiKeyNotFound(id)),
+    };
+    audit::record("revoke_api_key_admin", format!("id={}", id), &result);
+    result
+}
+
+// Admin Audit Log
+
+#[query(guard = "is_dev")]
+fn get_audit_log(start: u64, limit: u64) -> AuditLogPage {
+    AuditLogPage {
+        entries: store::get_audit_entries(start, limit.min(MAX_AUDIT_PAGE_SIZE)),
+        total: store::audit_log_len(),
+    }
+}
+
+/// Recomputes the hash chain. Returns the ID of the first tampered entry on failure.
+#[query(guard = "is_dev")]
+fn verify_audit_log() -> Result<(), u64> {
+    store::verify_audit_chain()
+}
+
 #[ic_cdk::query]
 fn is_dev_check() -> bool {
     is_dev().is_ok()
 }
 
+///////////////////////////////////////////////////////////
+// HTTP GATEWAY
+///////////////////////////////////////////////////////////
+
+// GET /api/products            -> product catalogue as JSON
+// GET /api/orders.csv?date=... -> a day's orders as CSV, requires an API key
+// GET /api/images/<id>         -> an uploaded product image
+#[query]
+fn http_request(request: HttpRequest) -> HttpResponse {
+    http::handle_query(&request)
+}
+
+#[update]
+fn http_request_update(request: HttpRequest) -> HttpResponse {
+    http::handle_update(&request, time())
+}
+
 // Added pre_upgrade and post_upgrade hooks for stable storage
 #[ic_cdk::pre_upgrade]
 fn pre_upgrade() {
@@ -400,6 +2294,19 @@ fn pre_upgrade() {
     // across upgrades without explicit saving/loading here, as long as MEMORY_MANAGER is re-init.
 }
 
+#[ic_cdk::init]
+fn init(args: Option<InitArgs>) {
+    let args = args.unwrap_or_default();
+    if let Err(e) = cycles::init_monitored_canisters(args.monitored_canisters, id()) {
+        ic_cdk::trap(&format!("Invalid init args: {:?}", e));
+    }
+    // Publishes the root hash of the (still empty) product tree, so that witnesses match
+    // the certified data from the start
+    certified::recertify_all_products();
+    jobs::ensure_builtin_jobs(time());
+    timer::rearm();
+}
+
 #[ic_cdk::post_upgrade]
 fn post_upgrade() {
     // Re-initialize the MEMORY_MANAGER after an upgrade.
@@ -416,6 +2323,31 @@ fn post_upgrade() {
     // after an upgrade (e.g., to repopulate runtime caches from stable storage), call it here.
     // For example, if you had a function like store::reinit_state_after_upgrade():
     // store::reinit_state_after_upgrade();
+
+    // Products moved out of their size-bounded StableVec when translations were added
+    let migrated = store::migrate_legacy_products();
+    if migrated > 0 {
+        ic_cdk::println!("Migrated {} products", migrated);
+    }
+
+    // Scheduled price changes that fell due while the timer was off
+    pricing::apply_due_price_changes(time());
+
+    // Products stored before search was added
+    let indexed = store::ensure_search_index();
+    if indexed > 0 {
+        ic_cdk::println!("Indexed {} products for search", indexed);
+    }
+
+    // The product tree lives on the heap and is lost on upgrade
+    certified::recertify_all_products();
+
+    // Jobs added in this release are created on the first upgrade that ships them
+    jobs::ensure_builtin_jobs(time());
+    cycles::ensure_monitored_canisters(id());
+
+    // Timers are cleared by the upgrade; pending notifications and jobs need one re-armed.
+    timer::rearm();
 }
 
 // CYCLE MANAGEMENT & FINANCIAL STATUS FUNCTIONS
@@ -427,62 +2359,203 @@ pub struct CanisterCycles {
     cycles: u128,
 }
 
+#[derive(CandidType, Deserialize, Debug)]
+pub struct CanisterCyclesError {
+    id: Principal,
+    name: String,
+    message: String,
+}
+
+// A rejected status call is reported in `errors` and doesn't hide the other canisters
 #[derive(CandidType, Deserialize, Debug)]
 pub struct AllCanisterCyclesResponse {
-    backend: CanisterCycles,
-    frontend: CanisterCycles,
+    canisters: Vec<CanisterCycles>,
+    errors: Vec<CanisterCyclesError>,
 }
 
 #[update(guard = "is_dev")]
-async fn get_all_canister_cycles() -> Result<AllCanisterCyclesResponse, String> {
-    let backend_id = id();
-    let frontend_id_str = "mklha-yyaaa-aaaak-apcxq-cai"; // Mainnet Frontend Canister ID
-    let frontend_id =
-        Principal::from_text(frontend_id_str).expect("Failed to parse frontend principal");
+async fn get_all_canister_cycles() -> AllCanisterCyclesResponse {
+    let caller = ic_cdk::caller();
+    let response = fetch_all_canister_cycles().await;
+    let result = if response.errors.is_empty() {
+        Ok(())
+    } else {
+        let messages: Vec<&str> = response.errors.iter().map(|e| e.message.as_str()).collect();
+        Err(messages.join("; "))
+    };
+    audit::record_for(caller, "get_all_canister_cycles", String::new(), &result);
+    response
+}
+
+async fn fetch_all_canister_cycles() -> AllCanisterCyclesResponse {
+    let mut response = AllCanisterCyclesResponse {
+        canisters: Vec::new(),
+        errors: Vec::new(),
+    };
+    for canister in store::get_monitored_canisters() {
+        match cycles::fetch_sample(canister.canister_id, time()).await {
+            Ok(sample) => response.canisters.push(CanisterCycles {
+                id: canister.canister_id,
+                name: canister.label,
+                cycles: sample.cycles,
+            }),
+            Err(message) => response.errors.push(CanisterCyclesError {
+                id: canister.canister_id,
+                name: canister.label,
+                message,
+            }),
+        }
+    }
+    response
+}
 
-    let canisters_to_check = vec![(backend_id, "Backend"), (frontend_id, "Frontend")];
+#[query(guard = "is_dev")]
+fn get_monitored_canisters() -> Vec<MonitoredCanister> {
+    store::get_monitored_canisters()
+}
 
-    let mut results: Vec<CanisterCycles> = Vec::new();
+/// Adds a canister to the monitored list, or changes the label of one already on it.
+#[update(guard = "is_dev")]
+fn set_monitored_canister_admin(
+    canister: MonitoredCanister,
+) -> Result<MonitoredCanister, CyclesError> {
+    let args_summary = format!("{} ({})", canister.canister_id, canister.label);
+    let result = cycles::validate_monitored_canister(canister)
+        .inspect(|canister| store::set_monitored_canister(canister.clone()));
+    audit::record("set_monitored_canister_admin", args_summary, &result);
+    result
+}
 
-    for (canister_id, name) in canisters_to_check {
-        let status_args = CanisterIdRecord { canister_id };
+/// Stops monitoring a canister. Its recorded samples are kept until they expire.
+#[update(guard = "is_dev")]
+fn remove_monitored_canister_admin(canister_id: Principal) -> Result<(), CyclesError> {
+    let result = store::remove_monitored_canister(canister_id)
+        .map(|_| ())
+        .ok_or(CyclesError::NotMonitored(canister_id));
+    audit::record(
+        "remove_monitored_canister_admin",
+        canister_id.to_string(),
+        &result,
+    );
+    result
+}
 
-        match canister_status(status_args).await {
-            Ok((canister_status_response,)) => {
-                let cycles_balance: u128 = canister_status_response
-                    .cycles
-                    .0
-                    .try_into()
-                    .map_err(|_| format!("Cycles balance too large for u128 for {}", name))?;
-                results.push(CanisterCycles {
-                    id: canister_id,
-                    name: name.to_string(),
-                    cycles: cycles_balance,
-                });
-            }
-            Err((rejection_code, msg)) => {
-                let error_msg = format!(
-                    "Failed to get status for {} ({}): code={:?}, message={}",
-                    name, canister_id, rejection_code, msg
-                );
-                return Err(error_msg);
-            }
-        }
-    }
+/// Recorded cycle balances of the monitored canisters with burn rate and projected runway.
+/// Defaults to the last 30 days.
+#[query(guard = "is_dev")]
+fn get_cycles_history(since: Option<u64>) -> Vec<CyclesReport> {
+    let since = since.unwrap_or_else(|| time().saturating_sub(30 * date::NANOS_PER_DAY));
+    cycles::reports(since)
+}
 
-    if results.len() == 2 {
-        let backend_cycles = results.remove(0);
-        let frontend_cycles = results.remove(0);
+#[query(guard = "is_dev")]
+fn get_cycles_monitor_settings() -> CyclesMonitorSettings {
+    store::get_cycles_monitor_settings()
+}
 
-        Ok(AllCanisterCyclesResponse {
-            backend: backend_cycles,
-            frontend: frontend_cycles,
-        })
+#[update(guard = "is_dev")]
+fn set_cycles_monitor_settings(settings: CyclesMonitorSettings) -> Result<(), CyclesError> {
+    let args_summary = format!(
+        "threshold={}, alert_phone_number={:?}, alert_interval_seconds={}, retention_days={}",
+        settings.low_balance_threshold,
+        settings.alert_phone_number,
+        settings.alert_interval_seconds,
+        settings.retention_days
+    );
+    let result = validate_cycles_monitor_settings(settings).and_then(|settings| {
+        store::set_cycles_monitor_settings(settings).map_err(CyclesError::StorageError)
+    });
+    audit::record("set_cycles_monitor_settings", args_summary, &result);
+    result
+}
+
+fn validate_cycles_monitor_settings(
+    mut settings: CyclesMonitorSettings,
+) -> Result<CyclesMonitorSettings, CyclesError> {
+    let mut errors = Vec::new();
+    if settings.retention_days == 0 {
+        errors.push(FieldError::new(
+            "retention_days",
+            FieldErrorKind::OutOfRange,
+            "Retention must be at least one day.",
+        ));
+    }
+    if let Some(raw) = &settings.alert_phone_number {
+        match phone::normalize_phone_number(raw, &store::get_phone_settings()) {
+            Ok(phone_number) => settings.alert_phone_number = Some(phone_number),
+            Err(e) => errors.push(FieldError::new(
+                "alert_phone_number",
+                FieldErrorKind::Unsupported,
+                format!("Invalid phone number: {:?}", e),
+            )),
+        }
+    }
+    if errors.is_empty() {
+        Ok(settings)
     } else {
-        Err("Could not fetch status for all required canisters.".to_string())
+        Err(CyclesError::InvalidInput(errors))
     }
 }
 
+// ICP Top-Ups
+
+/// Ledger account (hex) of the backend. Top-ups are paid from it, so it must hold ICP.
+#[query(guard = "is_dev")]
+fn get_icp_account() -> String {
+    hex::encode(&topup::account_identifier(id(), &[0; 32]))
+}
+
+/// Converts `icp_e8s` of the backend's ICP into cycles for a monitored canister.
+#[update(guard = "is_dev")]
+async fn top_up_canister(canister_id: Principal, icp_e8s: u64) -> Result<TopUp, TopUpError> {
+    let caller = ic_cdk::caller();
+    let result = topup::top_up(canister_id, icp_e8s, false, caller, time()).await;
+    audit::record_for(
+        caller,
+        "top_up_canister",
+        format!("canister={}, icp_e8s={}", canister_id, icp_e8s),
+        &result,
+    );
+    result
+}
+
+/// Retries minting the cycles of a top-up whose ICP transfer went through.
+#[update(guard = "is_dev")]
+async fn retry_top_up_admin(top_up_id: u64) -> Result<TopUp, TopUpError> {
+    let caller = ic_cdk::caller();
+    let result = topup::retry_notify(top_up_id).await;
+    audit::record_for(caller, "retry_top_up_admin", top_up_id.to_string(), &result);
+    result
+}
+
+/// Manual and automatic top-ups, newest first.
+#[query(guard = "is_dev")]
+fn get_top_ups() -> Vec<TopUp> {
+    store::get_top_ups()
+}
+
+#[query(guard = "is_dev")]
+fn get_top_up_settings() -> TopUpSettings {
+    store::get_top_up_settings()
+}
+
+#[update(guard = "is_dev")]
+fn set_top_up_settings(settings: TopUpSettings) -> Result<(), TopUpError> {
+    let args_summary = format!(
+        "ledger={}, cmc={}, auto_top_up={}, min_cycles_balance={}, auto_top_up_e8s={}, max_top_up_e8s={}",
+        settings.ledger_canister_id,
+        settings.cmc_canister_id,
+        settings.auto_top_up,
+        settings.min_cycles_balance,
+        settings.auto_top_up_e8s,
+        settings.max_top_up_e8s
+    );
+    let result = topup::validate_settings(&settings)
+        .and_then(|_| store::set_top_up_settings(settings).map_err(TopUpError::StorageError));
+    audit::record("set_top_up_settings", args_summary, &result);
+    result
+}
+
 #[test]
 fn generate_candid() {
     candid::export_service!();
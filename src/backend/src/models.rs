@@ -1,13 +1,82 @@
-use candid::{CandidType, Deserialize};
+use candid::{CandidType, Deserialize, Principal};
 use serde::Serialize; // Added Serialize for potential future use, though not strictly needed for Candid only
 
 #[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default)]
 pub struct Product {
-    pub id: u64, // This ID must match the index in the StableVec in store.rs
-    pub name: String,
+    pub id: u64,
+    pub name: String, // Texts in the default language (English)
     pub description: String,
     pub price: f64,
     pub unit: String,
+    pub translations: Option<Vec<ProductTranslation>>, // At most one per language code
+    pub category_id: Option<u64>,
+    pub tags: Option<Vec<String>>, // Lowercase, without duplicates
+    pub image_id: Option<u64>,     // Served at /api/images/<id>
+    // Packs the product is sold in; products with variants can only be ordered in packs
+    pub variants: Option<Vec<ProductVariant>>,
+    pub quantity_step: Option<f64>, // Loose quantities must be multiples of this, e.g. 0.25 kg
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ProductVariant {
+    pub id: u64,            // Unique within the product
+    pub sku: String,        // Unique across all products
+    pub label: String,      // e.g. "500 ml tin"
+    pub pack_size: f64,     // In the product's unit
+    pub price: f64,         // Per pack
+    pub stock: Option<u64>, // Packs in stock, None if not tracked
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ProductTranslation {
+    pub lang: String, // Language code, e.g. "hi"
+    pub name: String,
+    pub description: String,
+    pub unit: Option<String>, // Falls back to the default unit
+}
+
+// An uploaded image. The content is stored separately and served by `http_request`.
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct ProductImage {
+    pub id: u64,
+    pub content_type: String, // image/jpeg, image/png or image/webp
+    pub size: u64,            // Bytes
+    pub sha256: Vec<u8>,
+    pub uploaded_at: u64, // Nanoseconds since epoch
+}
+
+// Categories form a tree through `parent_id`; products belong to at most one category.
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default)]
+pub struct Category {
+    pub id: u64,
+    pub name: String,
+    pub parent_id: Option<u64>, // None for top-level categories
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum ProductSort {
+    Relevance, // Names starting with the search text first, then by name
+    Name,
+    PriceAscending,
+    PriceDescending,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default)]
+pub struct ProductSearch {
+    pub text: Option<String>, // Every word must start or occur in a word of the name in any language
+    pub lang: Option<String>, // Language of the returned texts, as in `get_products`
+    pub category_id: Option<u64>, // Includes the subcategories
+    pub tags: Option<Vec<String>>, // Products with all of these tags
+    pub sort: Option<ProductSort>, // Defaults to relevance
+}
+
+// Response of `get_products_certified`. The witness is a CBOR hash tree whose
+// "products" subtree maps each product ID (8 bytes, big-endian) to the Candid-encoded product.
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct CertifiedProducts {
+    pub products: Vec<Product>,
+    pub certificate: Vec<u8>,
+    pub witness: Vec<u8>,
 }
 
 #[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default)]
@@ -19,6 +88,134 @@ pub struct UserProfile {
     pub address: String,
     // Store order IDs for user's order history
     pub order_ids: Vec<u64>,
+    // Set once the customer's personal data has been anonymised (nanoseconds since epoch)
+    pub deleted_at: Option<u64>,
+    // Named delivery addresses (home, shop, ...), managed through the address endpoints
+    pub addresses: Option<Vec<SavedAddress>>,
+    pub default_address_id: Option<u64>,
+    // Set when the customer proved ownership of the phone number with an OTP
+    pub verified_at: Option<u64>,
+    // Customer asked not to receive order status notifications
+    pub notifications_opt_out: Option<bool>,
+    // Wholesale or custom price list, assigned by an admin
+    pub price_list_id: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct GeoPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct SavedAddress {
+    pub id: u64,       // Unique within the customer's profile
+    pub label: String, // e.g. "Home", "Shop"
+    pub address_lines: Vec<String>,
+    pub landmark: Option<String>,
+    pub zone: Option<String>, // Delivery zone / area
+    pub geo: Option<GeoPoint>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct AddressInput {
+    pub label: String,
+    pub address_lines: Vec<String>,
+    pub landmark: Option<String>,
+    pub zone: Option<String>,
+    pub geo: Option<GeoPoint>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct DeletionRequest {
+    pub phone_number: String,
+    pub requested_at: u64, // Nanoseconds since epoch
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct PhoneChangeRequest {
+    pub current_phone_number: String,
+    pub new_phone_number: String,
+    pub requested_at: u64, // Nanoseconds since epoch
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct PhoneSettings {
+    pub default_country_code: u16, // Calling code used for national numbers, e.g. 91 for India
+    pub national_number_length: Option<u8>, // Expected national number length in the default country
+}
+
+impl Default for PhoneSettings {
+    fn default() -> Self {
+        PhoneSettings {
+            default_country_code: 91,
+            national_number_length: Some(10),
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default)]
+pub struct PhoneMigrationReport {
+    pub profiles_rewritten: u64,
+    pub profiles_merged: u64,
+    pub orders_rewritten: u64,
+    pub invalid_phone_numbers: Vec<String>, // Left untouched, need manual review
+}
+
+// --- Phone Verification Models ---
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum OtpPurpose {
+    VerifyProfile,
+    // The code is sent to the new number of a pending phone change request
+    ChangePhoneNumber { current_phone_number: String },
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct OtpChallenge {
+    pub phone_number: String,
+    pub purpose: OtpPurpose,
+    pub code_hash: Vec<u8>, // SHA-256 over salt, phone number and code; the code itself is never stored
+    pub salt: Vec<u8>,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub attempts_left: u32,
+}
+
+// Kept per phone number across challenges, so that requesting a new code doesn't reset the
+// number of guesses
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct OtpLockout {
+    pub phone_number: String,
+    pub lockouts: u32, // Challenges whose attempts were used up since the last successful check
+    pub locked_until: u64,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct VerificationSettings {
+    pub sms_endpoint_url: Option<String>, // SMS gateway receiving a JSON POST; OTPs are disabled while unset
+    pub sms_auth_header_name: Option<String>, // e.g. "Authorization"
+    pub sms_auth_header_value: Option<String>,
+    pub sms_sender_id: String,
+    pub code_ttl_seconds: u64,
+    pub max_attempts: u32,
+    pub resend_interval_seconds: u64,
+    pub require_verified_phone_for_orders: bool,
+}
+
+impl Default for VerificationSettings {
+    fn default() -> Self {
+        VerificationSettings {
+            sms_endpoint_url: None,
+            sms_auth_header_name: None,
+            sms_auth_header_value: None,
+            sms_sender_id: "DAIRY".to_string(),
+            code_ttl_seconds: 300,
+            max_attempts: 5,
+            resend_interval_seconds: 60,
+            require_verified_phone_for_orders: false,
+        }
+    }
 }
 
 // --- Order Related Models ---
@@ -28,6 +225,8 @@ pub struct OrderItem {
     pub product_id: u64,
     pub quantity: f64, // Use f64 for quantity (e.g., 1.5 kg paneer, 0.5 litre milk)
     pub price_per_unit_at_order: f64, // Store the price at the time of order
+    pub price_list_id: Option<u64>, // Price list the price came from, None for the base price
+    pub variant_id: Option<u64>, // Pack ordered; the quantity then counts packs and the price is per pack
 }
 
 #[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)] // Added PartialEq for status checks
@@ -57,5 +256,476 @@ pub struct Order {
     pub status: OrderStatus,
     pub timestamp: u64, // Timestamp of when the order was created (nanoseconds since epoch)
     pub delivery_address: String, // Delivery address for this specific order
+    pub delivery_address_id: Option<u64>, // Saved address the order was placed for, if any
     pub last_updated: u64, // Optional: Timestamp of last status update
+    pub discounts: Option<Vec<OrderDiscount>>, // Already subtracted from `total_amount`
+}
+
+// Who changed an order after it was placed
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum OrderEditor {
+    Customer,
+    Admin(Principal),
+}
+
+// An edit of an order, with the items, total and address from before it.
+// Stored apart from the order, see `store::get_order_edits`.
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct OrderEdit {
+    pub order_id: u64,
+    pub edited_at: u64,
+    pub edited_by: OrderEditor,
+    pub reason: Option<String>, // Given by admins
+    pub previous_items: Vec<OrderItem>,
+    pub previous_total_amount: f64,
+    pub previous_delivery_address: String,
+}
+
+// A discount line of an order
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct OrderDiscount {
+    pub coupon_code: String,
+    pub description: String,
+    pub amount: f64,                // Amount taken off the order total
+    pub terms: Option<CouponTerms>, // None on orders placed before the terms were recorded
+}
+
+// The terms of a coupon as redeemed on an order. Edits re-price the discount with them, so
+// changing or removing the coupon later doesn't affect orders already placed.
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct CouponTerms {
+    pub discount: Discount,
+    pub scope: CouponScope,
+    pub min_order_amount: Option<f64>,
+}
+
+// A product's base price from `effective_from` on (nanoseconds since epoch). Entries in the
+// future are scheduled changes; an entry at 0 holds the price from before the first change.
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct PriceChange {
+    pub product_id: u64,
+    pub price: f64,
+    pub effective_from: u64,
+    pub recorded_at: u64,
+}
+
+// --- Price List Models ---
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum PriceListKind {
+    Retail,    // Applies to every customer without a list of their own (at most one)
+    Wholesale, // Assigned to any number of customers
+    Custom,    // Assigned to a single customer
+}
+
+// Quantity break: the price applies from `min_quantity` up to the next tier
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct PriceTier {
+    pub min_quantity: f64,
+    pub price_per_unit: f64,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ProductPrice {
+    pub product_id: u64,
+    pub tiers: Vec<PriceTier>, // Sorted by `min_quantity`
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct PriceList {
+    pub id: u64,
+    pub name: String,
+    pub kind: PriceListKind,
+    pub prices: Vec<ProductPrice>, // Products not listed keep their base price
+}
+
+// --- Promotion Models ---
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum Discount {
+    Percentage(f64), // Percent off the items the coupon applies to, up to 100
+    Flat(f64),       // Amount off those items, at most their total
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum CouponScope {
+    AllProducts,
+    Products(Vec<u64>),
+    Categories(Vec<u64>), // Includes the subcategories
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct Coupon {
+    pub code: String, // Stored upper case, matched case-insensitively
+    pub description: String,
+    pub discount: Discount,
+    pub scope: CouponScope,
+    pub valid_from: Option<u64>,  // Nanoseconds since epoch, inclusive
+    pub valid_until: Option<u64>, // Exclusive
+    pub min_order_amount: Option<f64>,
+    pub max_uses: Option<u64>, // Across all customers
+    pub max_uses_per_customer: Option<u64>,
+    pub enabled: bool,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct CouponSummary {
+    pub coupon: Coupon,
+    pub times_used: u64, // Orders using the coupon that were not cancelled
+}
+
+// --- Cart Models ---
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct CartItem {
+    pub product_id: u64,
+    pub quantity: f64,
+    pub price_per_unit: f64, // Price the customer last saw for the product
+    pub variant_id: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default)]
+pub struct Cart {
+    pub phone_number: String, // Normalised phone number of the customer
+    pub items: Vec<CartItem>, // At most one per product and variant
+    pub updated_at: u64,
+}
+
+// A cart item priced with the current product data
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct CartLine {
+    pub product_id: u64,
+    pub variant_id: Option<u64>,
+    pub name: String,
+    pub variant_label: Option<String>,
+    pub unit: String,
+    pub quantity: f64,
+    pub price_per_unit: f64,                  // Current price
+    pub previous_price_per_unit: Option<f64>, // Set when the price changed since it was added
+    pub line_total: f64,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default)]
+pub struct CartView {
+    pub lines: Vec<CartLine>,
+    pub total_amount: f64,
+    pub prices_changed: bool,
+    pub updated_at: u64,
+}
+
+// --- Audit Log Models ---
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum AuditOutcome {
+    Success,
+    Failure(String), // Debug representation of the returned error
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct AuditEntry {
+    pub id: u64,           // Position of the entry in the log
+    pub timestamp: u64,    // Nanoseconds since epoch
+    pub caller: Principal, // Principal that invoked the guarded endpoint
+    pub method: String,
+    pub args_summary: String, // Human readable summary of the call arguments
+    pub outcome: AuditOutcome,
+    pub prev_hash: Vec<u8>, // Hash of the previous entry (32 zero bytes for the first one)
+    pub hash: Vec<u8>,      // SHA-256 over prev_hash and the fields above
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct AuditLogPage {
+    pub entries: Vec<AuditEntry>,
+    pub total: u64,
+}
+
+// --- Notification Models ---
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum NotificationChannel {
+    WhatsApp,    // WhatsApp Business Cloud API message endpoint
+    Sms,         // SMS gateway taking {"to", "from", "message"}
+    GenericJson, // Any webhook; receives the full event
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    pub channel: NotificationChannel,
+    pub webhook_url: Option<String>,
+    pub auth_header_name: Option<String>, // e.g. "Authorization"
+    pub auth_header_value: Option<String>,
+    pub sender_id: String, // Used by the Sms channel
+    pub max_attempts: u32,
+    pub base_backoff_seconds: u64, // Doubled after every failed attempt
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        NotificationSettings {
+            enabled: false,
+            channel: NotificationChannel::GenericJson,
+            webhook_url: None,
+            auth_header_name: None,
+            auth_header_value: None,
+            sender_id: "DAIRY".to_string(),
+            max_attempts: 5,
+            base_backoff_seconds: 60,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum NotificationState {
+    Pending,
+    Delivered { at: u64 },
+    Failed, // Gave up after max_attempts
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct Notification {
+    pub id: u64,
+    pub order_id: u64,
+    pub phone_number: String,
+    pub status: OrderStatus, // Order status that triggered the notification
+    pub message: String,
+    pub created_at: u64,
+    pub state: NotificationState,
+    pub attempts: u32,
+    pub next_attempt_at: u64,
+    pub last_error: Option<String>,
+    // What the notification is about; `None` for notifications queued before alerts existed,
+    // which are all order status changes. `order_id` and `status` only apply to those.
+    pub event: Option<NotificationEvent>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum NotificationEvent {
+    OrderStatusChanged,
+    LowCyclesBalance {
+        canister_id: Principal,
+        cycles: u128,
+    },
+}
+
+// --- Integration API Models ---
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct ApiKey {
+    pub id: u64,
+    pub name: String,      // What the key is used for, e.g. "Accountant spreadsheet"
+    pub prefix: String,    // First characters of the key, to recognise it in listings
+    pub key_hash: Vec<u8>, // SHA-256 of the key; the key itself is only shown once
+    pub created_by: Principal,
+    pub created_at: u64,
+    pub last_used_at: Option<u64>,
+    pub revoked: bool,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct CreatedApiKey {
+    pub id: u64,
+    pub key: String,
+}
+
+// --- Bulk Import/Export Models ---
+
+// Where an import reads its CSV from: the call argument itself, or an upload previously
+// assembled with `upload_csv_chunk` for files that don't fit into a single message
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub enum CsvSource {
+    Inline(String),
+    Upload(String), // Upload ID
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum ImportRowStatus {
+    Created,
+    Updated,
+    Invalid(String),
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct ImportRowResult {
+    pub row: u64,    // Record number in the file, the header being row 1
+    pub key: String, // Product ID or normalised phone number, if it could be determined
+    pub status: ImportRowStatus,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default)]
+pub struct ImportReport {
+    pub dry_run: bool, // Nothing was written; the statuses show what an import would do
+    pub created: u64,
+    pub updated: u64,
+    pub invalid: u64,
+    pub rows: Vec<ImportRowResult>,
+}
+
+// --- Backup/Restore Models ---
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct SnapshotManifest {
+    pub version: u32, // Format version of the encoded snapshot
+    pub created_at: u64,
+    pub total_size: u64,
+    pub chunk_size: u64,
+    pub chunk_count: u64,
+    pub checksum: Vec<u8>, // SHA-256 of the whole encoded snapshot
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct SnapshotChunk {
+    pub version: u32,
+    pub snapshot_checksum: Vec<u8>, // Ties the chunk to its manifest
+    pub index: u64,
+    pub data: Vec<u8>,
+    pub checksum: Vec<u8>, // SHA-256 of `data`
+}
+
+// --- Scheduled Job Models ---
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum JobKind {
+    // Cancels orders still Pending this long after they were placed
+    AutoCancelPendingOrders { timeout_seconds: u64 },
+    // Stores a DailySummary of the previous local day
+    DailySummary { utc_offset_minutes: i32 },
+    // Samples the cycle balances of the monitored canisters, see CyclesMonitorSettings
+    RecordCyclesBalances,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum JobSchedule {
+    Once,
+    Recurring { interval_seconds: u64 },
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct Job {
+    pub id: u64,
+    pub name: String,
+    pub kind: JobKind,
+    pub schedule: JobSchedule,
+    pub enabled: bool,
+    pub next_run_at: u64,
+    pub last_run_at: Option<u64>,
+    pub last_result: Option<Result<String, String>>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct DailySummary {
+    pub date: String, // Local date, YYYY-MM-DD
+    pub orders_placed: u64,
+    pub orders_delivered: u64,
+    pub orders_cancelled: u64,
+    pub orders_open: u64, // Placed that day and neither delivered nor cancelled yet
+    pub revenue: f64,     // Total of the day's orders that weren't cancelled
+    pub first_time_customers: u64, // Customers whose first order was placed that day
+    pub created_at: u64,
+}
+
+// --- Cycles Monitoring Models ---
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct CyclesMonitorSettings {
+    pub low_balance_threshold: u128, // Alert when a canister holds fewer cycles than this
+    pub alert_phone_number: Option<String>, // Recipient of WhatsApp/SMS alerts
+    pub alert_interval_seconds: u64, // Minimum time between two alerts for the same canister
+    pub retention_days: u64,
+}
+
+impl Default for CyclesMonitorSettings {
+    fn default() -> Self {
+        CyclesMonitorSettings {
+            low_balance_threshold: 2_000_000_000_000,
+            alert_phone_number: None,
+            alert_interval_seconds: 24 * 60 * 60,
+            retention_days: 90,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct CyclesSample {
+    pub canister_id: Principal,
+    pub timestamp: u64,
+    pub cycles: u128,
+    pub memory_size: u64, // Bytes
+    pub idle_cycles_burned_per_day: u128,
+    pub freezing_threshold_seconds: u64,
+    pub alerted: bool, // A low-balance alert was queued for this sample
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct CyclesReport {
+    pub canister_id: Principal,
+    pub name: String,
+    pub samples: Vec<CyclesSample>,               // Oldest first
+    pub burn_per_day: u128, // Observed over the returned samples, at least the idle burn
+    pub projected_days_until_freeze: Option<f64>, // None without samples or burn
+    pub below_threshold: bool,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct MonitoredCanister {
+    pub canister_id: Principal,
+    pub label: String, // Shown on the hosting balance page and in alerts
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct InitArgs {
+    // Canisters whose cycle balances are monitored; defaults to this canister alone
+    pub monitored_canisters: Option<Vec<MonitoredCanister>>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct TopUpSettings {
+    pub ledger_canister_id: Principal, // ICP ledger; point at local stand-ins for testing
+    pub cmc_canister_id: Principal,    // Cycles minting canister
+    pub auto_top_up: bool,
+    pub min_cycles_balance: u128, // Automatic top-ups start below this balance
+    pub auto_top_up_e8s: u64,     // ICP converted per automatic top-up
+    pub max_top_up_e8s: u64,      // Upper bound for a single top-up, manual or automatic
+}
+
+impl Default for TopUpSettings {
+    fn default() -> Self {
+        TopUpSettings {
+            ledger_canister_id: Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai")
+                .expect("Invalid ledger canister ID"),
+            cmc_canister_id: Principal::from_text("rkp4c-7iaaa-aaaaa-aaaca-cai")
+                .expect("Invalid CMC canister ID"),
+            auto_top_up: false,
+            min_cycles_balance: 1_000_000_000_000,
+            auto_top_up_e8s: 50_000_000,
+            max_top_up_e8s: 1_000_000_000,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum TopUpState {
+    Pending, // ICP transfer in flight
+    // ICP is at the CMC but the cycles haven't been minted yet; notify can be retried
+    Transferred { block_index: u64 },
+    Completed { block_index: u64, cycles: u128 },
+    Failed(String),
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct TopUp {
+    pub id: u64,
+    pub canister_id: Principal,
+    pub icp_e8s: u64,
+    pub automatic: bool,
+    pub requested_by: Principal,
+    pub created_at: u64,
+    pub state: TopUpState,
+    pub last_error: Option<String>, // Latest failed notify attempt
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct ErrorMessage {
+    pub code: String,
+    pub message: String,
 }
@@ -0,0 +1,261 @@
+use crate::{
+    json,
+    models::{
+        Notification, NotificationChannel, NotificationEvent, NotificationSettings,
+        NotificationState, Order, OrderStatus,
+    },
+    outcall, store, timer,
+};
+use ic_cdk::api::{management_canister::http_request::HttpHeader, time};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+// Deliveries attempted per timer tick; each one is an HTTPS outcall
+const BATCH_SIZE: usize = 10;
+// A delivery that hasn't reported back after this long is retried
+const IN_FLIGHT_TIMEOUT_SECONDS: u64 = 300;
+const MAX_BACKOFF_SECONDS: u64 = 6 * 60 * 60;
+
+fn status_message(order: &Order) -> String {
+    let status = match order.status {
+        OrderStatus::Pending => "received",
+        OrderStatus::Confirmed => "confirmed",
+        OrderStatus::Processing => "being prepared",
+        OrderStatus::OutForDelivery => "out for delivery",
+        OrderStatus::Delivered => "delivered",
+        OrderStatus::Cancelled => "cancelled",
+    };
+    format!(
+        "Hello {}, your order #{} (Rs. {:.2}) is {}.",
+        order.customer_name, order.id, order.total_amount, status
+    )
+}
+
+/// Queues a status notification for an order, unless notifications are disabled or the
+/// customer opted out. Delivery happens asynchronously from a timer.
+pub fn enqueue_status_change(order: &Order, now: u64) {
+    if queue_status_change(order, now).is_some() {
+        timer::schedule_at(now);
+    }
+}
+
+/// Stores the status notification for an order, due at `now`, and returns it. See
+/// `enqueue_status_change`.
+pub fn queue_status_change(order: &Order, now: u64) -> Option<Notification> {
+    if !store::get_notification_settings().enabled {
+        return None;
+    }
+    let opted_out = store::get_user_profile(&order.user_phone_number)
+        .and_then(|profile| profile.notifications_opt_out)
+        .unwrap_or(false);
+    if opted_out {
+        return None;
+    }
+
+    let notification = Notification {
+        id: store::get_next_notification_id(),
+        order_id: order.id,
+        phone_number: order.user_phone_number.clone(),
+        status: order.status.clone(),
+        message: status_message(order),
+        created_at: now,
+        state: NotificationState::Pending,
+        attempts: 0,
+        next_attempt_at: now,
+        last_error: None,
+        event: Some(NotificationEvent::OrderStatusChanged),
+    };
+    store::put_notification(notification.clone());
+    Some(notification)
+}
+
+/// Drops the notifications still queued for a deleted customer and replaces the phone
+/// number and message (which holds the customer's name) of the others with `tombstone`.
+pub fn forget_customer(phone_number: &String, tombstone: &str) {
+    let mut pending = Vec::new();
+    store::update_notifications_by_phone(phone_number, |notification| {
+        if notification.state == NotificationState::Pending {
+            pending.push(notification.id);
+        }
+        notification.phone_number = tombstone.to_string();
+        notification.message = tombstone.to_string();
+    });
+    for id in pending {
+        store::remove_notification(id);
+    }
+}
+
+/// Sends the customer's queued and future retries of notifications to their new number.
+pub fn move_customer(current_phone_number: &String, new_phone_number: &str) {
+    store::update_notifications_by_phone(current_phone_number, |notification| {
+        notification.phone_number = new_phone_number.to_string();
+    });
+}
+
+/// Queues an operational alert for the shop owner. WhatsApp and SMS alerts go to
+/// `phone_number`; without one only the GenericJson channel can deliver them.
+pub fn enqueue_alert(phone_number: Option<String>, event: NotificationEvent, message: String) {
+    let settings = store::get_notification_settings();
+    if !settings.enabled
+        || (phone_number.is_none() && settings.channel != NotificationChannel::GenericJson)
+    {
+        return;
+    }
+
+    let now = time();
+    store::put_notification(Notification {
+        id: store::get_next_notification_id(),
+        order_id: 0,
+        phone_number: phone_number.unwrap_or_default(),
+        status: OrderStatus::default(),
+        message,
+        created_at: now,
+        state: NotificationState::Pending,
+        attempts: 0,
+        next_attempt_at: now,
+        last_error: None,
+        event: Some(event),
+    });
+    timer::schedule_at(now);
+}
+
+/// Returns when the next pending notification is due, if delivery is configured.
+pub fn next_due_at() -> Option<u64> {
+    let settings = store::get_notification_settings();
+    if !settings.enabled || settings.webhook_url.is_none() {
+        return None;
+    }
+    store::next_notification_due_at()
+}
+
+/// Delivers the notifications that are due, rescheduling failed ones with exponential backoff.
+pub async fn process_outbox() {
+    let settings = store::get_notification_settings();
+    let now = time();
+    let due = store::get_due_notifications(now, BATCH_SIZE);
+
+    let webhook_url = match (&settings.webhook_url, settings.enabled) {
+        (Some(url), true) => url.clone(),
+        // Keep the queue until delivery is configured again
+        _ => return,
+    };
+
+    for mut notification in due {
+        // Claim the notification so that an overlapping tick doesn't send it twice
+        notification.next_attempt_at = now + IN_FLIGHT_TIMEOUT_SECONDS * NANOS_PER_SECOND;
+        store::put_notification(notification.clone());
+
+        let result = deliver(&settings, &webhook_url, &notification).await;
+
+        // Re-read: the notification may have been retried by an admin in the meantime
+        let mut notification = match store::get_notification(notification.id) {
+            Some(current) => current,
+            None => continue,
+        };
+        record_attempt(&mut notification, &settings, result, time());
+        store::put_notification(notification);
+    }
+}
+
+/// Records the outcome of a delivery attempt that finished at `finished_at`. Failed
+/// deliveries are retried with exponential backoff until `max_attempts` is reached.
+pub fn record_attempt(
+    notification: &mut Notification,
+    settings: &NotificationSettings,
+    result: Result<(), String>,
+    finished_at: u64,
+) {
+    notification.attempts += 1;
+    match result {
+        Ok(()) => {
+            notification.state = NotificationState::Delivered { at: finished_at };
+            notification.last_error = None;
+        }
+        Err(e) => {
+            notification.last_error = Some(e);
+            if notification.attempts >= settings.max_attempts {
+                notification.state = NotificationState::Failed;
+            } else {
+                let backoff = settings
+                    .base_backoff_seconds
+                    .saturating_mul(1u64 << (notification.attempts - 1).min(16))
+                    .min(MAX_BACKOFF_SECONDS);
+                notification.next_attempt_at = finished_at + backoff * NANOS_PER_SECOND;
+            }
+        }
+    }
+}
+
+/// The key the webhook de-duplicates on: every replica delivers each notification, and a
+/// retry must not send it twice either. Sent as `Idempotency-Key` header on every channel.
+pub fn idempotency_key(notification: &Notification) -> String {
+    format!("notification:{}", notification.id)
+}
+
+/// The request body for the configured channel. Each carries the idempotency key as well.
+pub fn payload(settings: &NotificationSettings, notification: &Notification) -> String {
+    let phone_number = json::escape(&notification.phone_number);
+    let message = json::escape(&notification.message);
+    match settings.channel {
+        NotificationChannel::WhatsApp => format!(
+            r#"{{"messaging_product":"whatsapp","to":"{}","type":"text","text":{{"body":"{}"}},"biz_opaque_callback_data":"{}"}}"#,
+            phone_number.trim_start_matches('+'),
+            message,
+            idempotency_key(notification)
+        ),
+        NotificationChannel::Sms => format!(
+            r#"{{"to":"{}","from":"{}","message":"{}","notification_id":{},"idempotency_key":"{}"}}"#,
+            phone_number,
+            json::escape(&settings.sender_id),
+            message,
+            notification.id,
+            idempotency_key(notification)
+        ),
+        NotificationChannel::GenericJson => match &notification.event {
+            Some(NotificationEvent::LowCyclesBalance {
+                canister_id,
+                cycles,
+            }) => format!(
+                r#"{{"event":"low_cycles_balance","notification_id":{},"idempotency_key":"{}","canister_id":"{}","cycles":{},"message":"{}","created_at":{}}}"#,
+                notification.id,
+                idempotency_key(notification),
+                canister_id,
+                cycles,
+                message,
+                notification.created_at
+            ),
+            Some(NotificationEvent::OrderStatusChanged) | None => format!(
+                r#"{{"event":"order_status_changed","notification_id":{},"idempotency_key":"{}","order_id":{},"phone_number":"{}","status":"{:?}","message":"{}","created_at":{}}}"#,
+                notification.id,
+                idempotency_key(notification),
+                notification.order_id,
+                phone_number,
+                notification.status,
+                message,
+                notification.created_at
+            ),
+        },
+    }
+}
+
+async fn deliver(
+    settings: &NotificationSettings,
+    webhook_url: &str,
+    notification: &Notification,
+) -> Result<(), String> {
+    let mut headers = vec![HttpHeader {
+        name: "Idempotency-Key".to_string(),
+        value: idempotency_key(notification),
+    }];
+    if let (Some(name), Some(value)) = (&settings.auth_header_name, &settings.auth_header_value) {
+        headers.push(HttpHeader {
+            name: name.clone(),
+            value: value.clone(),
+        });
+    }
+    let status = outcall::post_json(webhook_url, headers, payload(settings, notification)).await?;
+    if (200..300).contains(&status) {
+        Ok(())
+    } else {
+        Err(format!("Webhook responded with status {}", status))
+    }
+}
@@ -0,0 +1,54 @@
+use ic_cdk::api::management_canister::http_request::{
+    http_request, CanisterHttpRequestArgument, HttpHeader, HttpMethod, HttpResponse, TransformArgs,
+    TransformContext,
+};
+
+// Responses are only inspected for their status code
+const MAX_RESPONSE_BYTES: u64 = 2048;
+// Name of the query method registered in lib.rs that calls `strip_response`
+const TRANSFORM_FUNCTION: &str = "transform_outcall_response";
+// Upper bound for a small POST on a 13-node subnet; unused cycles are refunded
+const HTTP_OUTCALL_CYCLES: u128 = 1_000_000_000;
+
+/// Sends a JSON POST via an HTTPS outcall and returns the response status code.
+///
+/// Every replica of the subnet performs the request, so callers must send a stable
+/// idempotency key (by convention in an `Idempotency-Key` header and in the body) and the
+/// receiving endpoint must act on each key only once.
+pub async fn post_json(url: &str, headers: Vec<HttpHeader>, body: String) -> Result<u16, String> {
+    let mut request_headers = vec![HttpHeader {
+        name: "Content-Type".to_string(),
+        value: "application/json".to_string(),
+    }];
+    request_headers.extend(headers);
+
+    let request = CanisterHttpRequestArgument {
+        url: url.to_string(),
+        max_response_bytes: Some(MAX_RESPONSE_BYTES),
+        method: HttpMethod::POST,
+        headers: request_headers,
+        body: Some(body.into_bytes()),
+        transform: Some(TransformContext::from_name(
+            TRANSFORM_FUNCTION.to_string(),
+            vec![],
+        )),
+    };
+
+    match http_request(request, HTTP_OUTCALL_CYCLES).await {
+        Ok((response,)) => u16::try_from(&response.status.0)
+            .map_err(|_| format!("Invalid HTTP status {}", response.status)),
+        Err((rejection_code, msg)) => Err(format!(
+            "HTTP outcall to {} failed: code={:?}, message={}",
+            url, rejection_code, msg
+        )),
+    }
+}
+
+/// Strips headers and body from outcall responses so that all replicas agree on the result.
+pub fn strip_response(args: TransformArgs) -> HttpResponse {
+    HttpResponse {
+        status: args.response.status,
+        headers: Vec::new(),
+        body: Vec::new(),
+    }
+}
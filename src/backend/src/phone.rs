@@ -0,0 +1,305 @@
+use crate::{
+    address,
+    errors::PhoneNumberError,
+    models::{
+        Cart, DeletionRequest, OtpChallenge, OtpLockout, OtpPurpose, PhoneChangeRequest,
+        PhoneMigrationReport, PhoneSettings, SavedAddress, UserProfile,
+    },
+    store,
+};
+
+// E.164 allows at most 15 digits including the country code
+const MAX_E164_DIGITS: usize = 15;
+const MIN_E164_DIGITS: usize = 8;
+
+/// Normalises a phone number to E.164 (`+<country code><national number>`).
+///
+/// Spaces, dashes, dots and parentheses are ignored. Numbers starting with `+` or `00` are
+/// treated as international; anything else is a national number in the default country,
+/// with an optional leading trunk `0` (e.g. "098765-43210").
+pub fn normalize_phone_number(
+    raw: &str,
+    settings: &PhoneSettings,
+) -> Result<String, PhoneNumberError> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Err(PhoneNumberError::Empty);
+    }
+
+    let (international, rest) = match trimmed.strip_prefix('+') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed),
+    };
+
+    let mut digits = String::with_capacity(rest.len());
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            ' ' | '-' | '.' | '(' | ')' => {}
+            _ => return Err(PhoneNumberError::InvalidCharacter(c.to_string())),
+        }
+    }
+
+    let country_code = settings.default_country_code.to_string();
+    let e164_digits = if international {
+        digits
+    } else if let Some(stripped) = digits.strip_prefix("00") {
+        stripped.to_string()
+    } else {
+        let national = digits.strip_prefix('0').unwrap_or(&digits);
+        match settings.national_number_length {
+            // Country code typed without the `+`, e.g. "919876543210"
+            Some(len)
+                if national.len() == country_code.len() + len as usize
+                    && national.starts_with(&country_code) =>
+            {
+                national.to_string()
+            }
+            Some(len) if national.len() != len as usize => return Err(invalid_length(national)),
+            _ => format!("{}{}", country_code, national),
+        }
+    };
+
+    if e164_digits.starts_with('0') {
+        return Err(PhoneNumberError::InvalidCountryCode);
+    }
+    if e164_digits.len() < MIN_E164_DIGITS || e164_digits.len() > MAX_E164_DIGITS {
+        return Err(invalid_length(&e164_digits));
+    }
+    // Numbers in the default country must also have the expected national length
+    if let (Some(len), Some(national)) = (
+        settings.national_number_length,
+        e164_digits.strip_prefix(&country_code),
+    ) {
+        if national.len() != len as usize {
+            return Err(invalid_length(national));
+        }
+    }
+
+    Ok(format!("+{}", e164_digits))
+}
+
+/// The length error for `digits`, capped at 255 digits.
+fn invalid_length(digits: &str) -> PhoneNumberError {
+    PhoneNumberError::InvalidLength(u8::try_from(digits.len()).unwrap_or(u8::MAX))
+}
+
+/// Rewrites every stored phone number to its normalised form.
+///
+/// Profiles whose numbers normalise to the same key are merged: the profile already stored
+/// under the normalised key (or the first one migrated) keeps its name and address, and the
+/// order histories are combined. Orders, carts, OTP lockouts, redirects, phone change and
+/// deletion requests and queued notifications are re-keyed as well; where a record already
+/// exists under the normalised number, that one is kept. Outstanding OTP codes sent to a
+/// number that changes are dropped. Numbers that can't be parsed are reported and left
+/// untouched. Running it twice is a no-op.
+pub fn migrate_stored_phone_numbers(settings: &PhoneSettings) -> PhoneMigrationReport {
+    let mut report = PhoneMigrationReport::default();
+
+    for profile in store::get_all_user_profiles() {
+        if profile
+            .phone_number
+            .starts_with(store::deletion::TOMBSTONE_PREFIX)
+        {
+            continue;
+        }
+        let normalized = match normalize_phone_number(&profile.phone_number, settings) {
+            Ok(normalized) => normalized,
+            Err(_) => {
+                report.invalid_phone_numbers.push(profile.phone_number);
+                continue;
+            }
+        };
+        if normalized == profile.phone_number {
+            continue;
+        }
+
+        store::delete_user_profile(&profile.phone_number);
+        let migrated = match store::get_user_profile(&normalized) {
+            Some(existing) => {
+                report.profiles_merged += 1;
+                merge_profiles(existing, profile)
+            }
+            None => {
+                report.profiles_rewritten += 1;
+                UserProfile {
+                    phone_number: normalized,
+                    ..profile
+                }
+            }
+        };
+        store::update_user_profile(migrated);
+    }
+
+    for phone_number in store::get_order_phone_numbers() {
+        if phone_number.starts_with(store::deletion::TOMBSTONE_PREFIX) {
+            continue;
+        }
+        match normalize_phone_number(&phone_number, settings) {
+            Ok(normalized) if normalized != phone_number => {
+                let updated = store::update_orders_by_phone(&phone_number, |order| {
+                    order.user_phone_number = normalized.clone();
+                });
+                report.orders_rewritten += updated.len() as u64;
+            }
+            Ok(_) => {}
+            Err(_) => {
+                if !report.invalid_phone_numbers.contains(&phone_number) {
+                    report.invalid_phone_numbers.push(phone_number);
+                }
+            }
+        }
+    }
+
+    for request in store::get_deletion_requests() {
+        if let Some(normalized) = renormalized(&request.phone_number, settings) {
+            store::remove_deletion_request(&request.phone_number);
+            store::add_deletion_request(DeletionRequest {
+                phone_number: normalized,
+                ..request
+            });
+        }
+    }
+
+    for cart in store::get_all_carts() {
+        if let Some(normalized) = renormalized(&cart.phone_number, settings) {
+            store::remove_cart(&cart.phone_number);
+            if store::get_cart(&normalized).is_none() {
+                store::put_cart(Cart {
+                    phone_number: normalized,
+                    ..cart
+                });
+            }
+        }
+    }
+
+    for challenge in store::get_all_otp_challenges() {
+        // Codes are hashed together with the number they were sent to, so a code sent to a
+        // number that changes here could never be entered; the customer requests a new one
+        if renormalized(&challenge.phone_number, settings).is_some() {
+            store::remove_otp_challenge(&challenge.phone_number);
+            continue;
+        }
+        if let OtpPurpose::ChangePhoneNumber {
+            current_phone_number,
+        } = &challenge.purpose
+        {
+            if let Some(normalized) = renormalized(current_phone_number, settings) {
+                store::put_otp_challenge(OtpChallenge {
+                    purpose: OtpPurpose::ChangePhoneNumber {
+                        current_phone_number: normalized,
+                    },
+                    ..challenge
+                });
+            }
+        }
+    }
+
+    for lockout in store::get_all_otp_lockouts() {
+        if let Some(normalized) = renormalized(&lockout.phone_number, settings) {
+            store::remove_otp_lockout(&lockout.phone_number);
+            if store::get_otp_lockout(&normalized).is_none() {
+                store::put_otp_lockout(OtpLockout {
+                    phone_number: normalized,
+                    ..lockout
+                });
+            }
+        }
+    }
+
+    for (old_phone_number, new_phone_number) in store::get_all_phone_redirects() {
+        let old_normalized = renormalized(&old_phone_number, settings);
+        let new_normalized = renormalized(&new_phone_number, settings);
+        if old_normalized.is_none() && new_normalized.is_none() {
+            continue;
+        }
+        store::remove_phone_redirect(&old_phone_number);
+        let old_normalized = old_normalized.unwrap_or(old_phone_number);
+        let new_normalized = new_normalized.unwrap_or(new_phone_number);
+        if old_normalized != new_normalized
+            && store::get_user_profile(&old_normalized).is_none()
+            && store::get_phone_redirect(&old_normalized).is_none()
+        {
+            store::set_phone_redirect(&old_normalized, &new_normalized);
+        }
+    }
+
+    for request in store::get_phone_change_requests() {
+        let current = renormalized(&request.current_phone_number, settings);
+        let new = renormalized(&request.new_phone_number, settings);
+        if current.is_none() && new.is_none() {
+            continue;
+        }
+        store::remove_phone_change_request(&request.current_phone_number);
+        let current = current.unwrap_or_else(|| request.current_phone_number.clone());
+        if store::get_phone_change_request(&current).is_none() {
+            store::add_phone_change_request(PhoneChangeRequest {
+                current_phone_number: current,
+                new_phone_number: new.unwrap_or(request.new_phone_number),
+                ..request
+            });
+        }
+    }
+
+    for mut notification in store::get_all_notifications() {
+        if let Some(normalized) = renormalized(&notification.phone_number, settings) {
+            notification.phone_number = normalized;
+            store::put_notification(notification);
+        }
+    }
+
+    report
+}
+
+/// The normalised form of a stored phone number, if it differs from the stored one.
+/// Tombstones and numbers that can't be parsed are left as they are.
+fn renormalized(phone_number: &str, settings: &PhoneSettings) -> Option<String> {
+    if phone_number.starts_with(store::deletion::TOMBSTONE_PREFIX) {
+        return None;
+    }
+    normalize_phone_number(phone_number, settings)
+        .ok()
+        .filter(|normalized| normalized != phone_number)
+}
+
+/// Merges a duplicate profile into the one kept under the normalised key.
+fn merge_profiles(kept: UserProfile, duplicate: UserProfile) -> UserProfile {
+    let mut order_ids = kept.order_ids;
+    order_ids.extend(duplicate.order_ids);
+    order_ids.sort_unstable();
+    order_ids.dedup();
+
+    // Saved addresses of the duplicate are appended with fresh IDs
+    let mut addresses = kept.addresses.unwrap_or_default();
+    for duplicate_address in duplicate.addresses.unwrap_or_default() {
+        let id = address::next_address_id(&addresses);
+        addresses.push(SavedAddress {
+            id,
+            ..duplicate_address
+        });
+    }
+    let default_address_id = kept
+        .default_address_id
+        .or_else(|| addresses.first().map(|a| a.id));
+
+    UserProfile {
+        addresses: if addresses.is_empty() {
+            None
+        } else {
+            Some(addresses)
+        },
+        default_address_id,
+        name: if kept.name.trim().is_empty() {
+            duplicate.name
+        } else {
+            kept.name
+        },
+        address: if kept.address.trim().is_empty() {
+            duplicate.address
+        } else {
+            kept.address
+        },
+        order_ids,
+        ..kept
+    }
+}
@@ -0,0 +1,312 @@
+// Price resolution.
+//
+// A customer's unit price for a product comes from the price list assigned to them
+// (wholesale or custom), then from the retail price list, and otherwise is the product's
+// base price. Within a list the tier with the highest `min_quantity` not above the ordered
+// quantity applies; below the first tier the next source is used.
+//
+// Variants are sold at their pack price; price lists and the price history apply to the
+// price of loose quantities.
+//
+// Base prices have a history: every change is kept with the time it takes effect, so
+// changes can be scheduled ahead and past prices looked up. `Product::price` follows the
+// history; the timer applies scheduled changes once they are due.
+use crate::{
+    errors::{FieldError, FieldErrorKind, PriceListError, ProductError},
+    models::{
+        PriceChange, PriceList, PriceListKind, PriceTier, Product, ProductPrice, ProductVariant,
+    },
+    store,
+};
+
+const MAX_NAME_LENGTH: usize = 64;
+pub const MAX_PRICE_LIST_PRODUCTS: usize = 1000;
+
+/// A resolved unit price and the price list it came from (None for the base price).
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnitPrice {
+    pub price_per_unit: f64,
+    pub price_list_id: Option<u64>,
+}
+
+fn tier_price(list: &PriceList, product_id: u64, quantity: f64) -> Option<f64> {
+    list.prices
+        .iter()
+        .find(|price| price.product_id == product_id)?
+        .tiers
+        .iter()
+        .rev()
+        .find(|tier| tier.min_quantity <= quantity)
+        .map(|tier| tier.price_per_unit)
+}
+
+fn retail_price_list() -> Option<PriceList> {
+    store::get_all_price_lists()
+        .into_iter()
+        .find(|list| list.kind == PriceListKind::Retail)
+}
+
+/// The price list assigned to a customer, if any.
+pub fn customer_price_list_id(phone_number: &String) -> Option<u64> {
+    store::get_user_profile(phone_number).and_then(|profile| profile.price_list_id)
+}
+
+/// The base price of a product effective at `at`.
+pub fn base_price(product: &Product, at: u64) -> f64 {
+    store::get_price_change_at(product.id, at)
+        .map(|change| change.price)
+        .unwrap_or(product.price)
+}
+
+/// Records a change of a product's base price. The first change of a product also records
+/// the price it had until then, so the history covers all times.
+pub fn record_price_change(product: &Product, price: f64, effective_from: u64, now: u64) {
+    if store::get_price_changes(product.id).is_empty() {
+        store::put_price_change(PriceChange {
+            product_id: product.id,
+            price: product.price,
+            effective_from: 0,
+            recorded_at: now,
+        });
+    }
+    store::put_price_change(PriceChange {
+        product_id: product.id,
+        price,
+        effective_from,
+        recorded_at: now,
+    });
+}
+
+/// Schedules a base price change for a time in the future.
+pub fn schedule_price_change(
+    product_id: u64,
+    price: f64,
+    effective_from: u64,
+    now: u64,
+) -> Result<PriceChange, ProductError> {
+    let product =
+        store::get_product_by_id(product_id).ok_or(ProductError::ProductNotFound(product_id))?;
+    let mut errors = Vec::new();
+    if !price.is_finite() || price < 0.0 {
+        errors.push(FieldError::new(
+            "price",
+            FieldErrorKind::OutOfRange,
+            format!("Invalid price {}.", price),
+        ));
+    }
+    if effective_from <= now {
+        errors.push(FieldError::new(
+            "effective_from",
+            FieldErrorKind::OutOfRange,
+            "Scheduled changes must take effect in the future.",
+        ));
+    }
+    if !errors.is_empty() {
+        return Err(ProductError::InvalidInput(errors));
+    }
+    record_price_change(&product, price, effective_from, now);
+    Ok(PriceChange {
+        product_id,
+        price,
+        effective_from,
+        recorded_at: now,
+    })
+}
+
+/// Cancels a scheduled price change that has not taken effect yet.
+pub fn cancel_price_change(
+    product_id: u64,
+    effective_from: u64,
+    now: u64,
+) -> Result<PriceChange, ProductError> {
+    if effective_from <= now {
+        return Err(ProductError::InvalidInput(vec![FieldError::new(
+            "effective_from",
+            FieldErrorKind::OutOfRange,
+            "The price change has already taken effect.",
+        )]));
+    }
+    store::remove_price_change(product_id, effective_from).ok_or(
+        ProductError::PriceChangeNotFound {
+            product_id,
+            effective_from,
+        },
+    )
+}
+
+/// Sets the price of every product whose scheduled change is due. Returns how many changed.
+pub fn apply_due_price_changes(now: u64) -> u64 {
+    let mut applied = 0;
+    for product in store::get_all_products() {
+        let price = base_price(&product, now);
+        if price != product.price {
+            let id = product.id;
+            if store::update_product(id, Product { price, ..product }).is_ok() {
+                applied += 1;
+            }
+        }
+    }
+    applied
+}
+
+/// The earliest scheduled price change after `now`.
+pub fn next_price_change_at(now: u64) -> Option<u64> {
+    store::get_all_price_changes()
+        .into_iter()
+        .map(|change| change.effective_from)
+        .filter(|effective_from| *effective_from > now)
+        .min()
+}
+
+/// The price of `quantity` of a product, or of packs of one of its variants, at `at` for a
+/// customer with `price_list_id`.
+pub fn item_price(
+    price_list_id: Option<u64>,
+    product: &Product,
+    variant: Option<&ProductVariant>,
+    quantity: f64,
+    at: u64,
+) -> UnitPrice {
+    match variant {
+        Some(variant) => UnitPrice {
+            price_per_unit: variant.price,
+            price_list_id: None,
+        },
+        None => unit_price(price_list_id, product, quantity, at),
+    }
+}
+
+/// The unit price of `quantity` of a product at `at` for a customer with `price_list_id`.
+pub fn unit_price(
+    price_list_id: Option<u64>,
+    product: &Product,
+    quantity: f64,
+    at: u64,
+) -> UnitPrice {
+    let customer_list = price_list_id.and_then(store::get_price_list);
+    customer_list
+        .into_iter()
+        .chain(retail_price_list())
+        .find_map(|list| {
+            tier_price(&list, product.id, quantity).map(|price_per_unit| UnitPrice {
+                price_per_unit,
+                price_list_id: Some(list.id),
+            })
+        })
+        .unwrap_or(UnitPrice {
+            price_per_unit: base_price(product, at),
+            price_list_id: None,
+        })
+}
+
+/// Phone numbers of the customers a price list is assigned to.
+pub fn customers_with_price_list(id: u64) -> Vec<String> {
+    store::get_all_user_profiles()
+        .into_iter()
+        .filter(|profile| profile.price_list_id == Some(id))
+        .map(|profile| profile.phone_number)
+        .collect()
+}
+
+/// Checks a price list before it is stored under `id`, sorting the tiers of each product.
+pub fn validate_price_list(list: PriceList) -> Result<PriceList, PriceListError> {
+    let mut errors = Vec::new();
+    let name = list.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        errors.push(FieldError::new(
+            "name",
+            FieldErrorKind::Required,
+            format!("Names have 1 to {} characters.", MAX_NAME_LENGTH),
+        ));
+    }
+    if list.kind == PriceListKind::Retail {
+        if let Some(retail) = retail_price_list().filter(|retail| retail.id != list.id) {
+            errors.push(FieldError::new(
+                "kind",
+                FieldErrorKind::Duplicate,
+                format!("'{}' is already the retail price list.", retail.name),
+            ));
+        }
+    }
+    if list.kind == PriceListKind::Custom && customers_with_price_list(list.id).len() > 1 {
+        errors.push(FieldError::new(
+            "kind",
+            FieldErrorKind::Unsupported,
+            "The price list is assigned to several customers.",
+        ));
+    }
+    if list.prices.len() > MAX_PRICE_LIST_PRODUCTS {
+        errors.push(FieldError::new(
+            "prices",
+            FieldErrorKind::TooLong,
+            format!("At most {} products.", MAX_PRICE_LIST_PRODUCTS),
+        ));
+    }
+
+    let mut prices: Vec<ProductPrice> = Vec::new();
+    for (index, price) in list.prices.into_iter().enumerate() {
+        let field = format!("prices[{}]", index);
+        if store::get_product_by_id(price.product_id).is_none() {
+            errors.push(FieldError::new(
+                format!("{}.product_id", field),
+                FieldErrorKind::Unsupported,
+                format!("Product {} not found.", price.product_id),
+            ));
+        }
+        if prices
+            .iter()
+            .any(|existing| existing.product_id == price.product_id)
+        {
+            errors.push(FieldError::new(
+                format!("{}.product_id", field),
+                FieldErrorKind::Duplicate,
+                format!("Product {} is listed more than once.", price.product_id),
+            ));
+        }
+        let mut tiers: Vec<PriceTier> = price.tiers;
+        if tiers.is_empty() {
+            errors.push(FieldError::new(
+                format!("{}.tiers", field),
+                FieldErrorKind::Required,
+                "At least one price tier is needed.",
+            ));
+        }
+        if tiers.iter().any(|tier| {
+            !tier.min_quantity.is_finite()
+                || tier.min_quantity < 0.0
+                || !tier.price_per_unit.is_finite()
+                || tier.price_per_unit < 0.0
+        }) {
+            errors.push(FieldError::new(
+                format!("{}.tiers", field),
+                FieldErrorKind::OutOfRange,
+                "Quantities and prices cannot be negative.",
+            ));
+        }
+        tiers.sort_by(|a, b| a.min_quantity.total_cmp(&b.min_quantity));
+        if tiers
+            .windows(2)
+            .any(|pair| pair[0].min_quantity == pair[1].min_quantity)
+        {
+            errors.push(FieldError::new(
+                format!("{}.tiers", field),
+                FieldErrorKind::Duplicate,
+                "Two tiers start at the same quantity.",
+            ));
+        }
+        prices.push(ProductPrice {
+            product_id: price.product_id,
+            tiers,
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(PriceList {
+            name,
+            prices,
+            ..list
+        })
+    } else {
+        Err(PriceListError::InvalidInput(errors))
+    }
+}
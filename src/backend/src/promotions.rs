@@ -0,0 +1,269 @@
+// Coupons and promotional discounts.
+//
+// Admins manage coupons with a percentage or flat discount on all products or a selection
+// of them. A customer passes the code when ordering; the discount is computed on the items
+// the coupon applies to and stored on the order as a separate line. Usage is counted from
+// the orders themselves, so cancelling an order gives the use back.
+use crate::{
+    catalog,
+    errors::{CouponError, FieldError, FieldErrorKind},
+    models::{
+        Coupon, CouponScope, CouponTerms, Discount, Order, OrderDiscount, OrderItem, OrderStatus,
+    },
+    store,
+};
+
+const MIN_CODE_LENGTH: usize = 3;
+const MAX_CODE_LENGTH: usize = 32;
+const MAX_DESCRIPTION_LENGTH: usize = 200;
+pub const MAX_SCOPE_PRODUCTS: usize = 100;
+const MAX_SCOPE_CATEGORIES: usize = 20;
+
+/// Coupon codes are matched without regard to case or surrounding spaces.
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+/// Checks a coupon before it is stored and normalises its code and description.
+pub fn validate_coupon(mut coupon: Coupon) -> Result<Coupon, CouponError> {
+    let mut errors = Vec::new();
+    coupon.code = normalize_code(&coupon.code);
+    if coupon.code.len() < MIN_CODE_LENGTH
+        || coupon.code.len() > MAX_CODE_LENGTH
+        || !coupon
+            .code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        errors.push(FieldError::new(
+            "code",
+            FieldErrorKind::Unsupported,
+            format!(
+                "Codes have {} to {} letters, digits, '-' or '_'.",
+                MIN_CODE_LENGTH, MAX_CODE_LENGTH
+            ),
+        ));
+    }
+    coupon.description = coupon.description.trim().to_string();
+    if coupon.description.chars().count() > MAX_DESCRIPTION_LENGTH {
+        errors.push(FieldError::new(
+            "description",
+            FieldErrorKind::TooLong,
+            format!("At most {} characters.", MAX_DESCRIPTION_LENGTH),
+        ));
+    }
+    let discount_valid = match coupon.discount {
+        Discount::Percentage(percent) => percent > 0.0 && percent <= 100.0,
+        Discount::Flat(amount) => amount.is_finite() && amount > 0.0,
+    };
+    if !discount_valid {
+        errors.push(FieldError::new(
+            "discount",
+            FieldErrorKind::OutOfRange,
+            "Percentages must be above 0 and at most 100, flat amounts above 0.",
+        ));
+    }
+    if let CouponScope::Products(product_ids) = &coupon.scope {
+        if product_ids.is_empty() || product_ids.len() > MAX_SCOPE_PRODUCTS {
+            errors.push(FieldError::new(
+                "scope",
+                FieldErrorKind::OutOfRange,
+                format!("Between 1 and {} products.", MAX_SCOPE_PRODUCTS),
+            ));
+        }
+        for product_id in product_ids {
+            if store::get_product_by_id(*product_id).is_none() {
+                errors.push(FieldError::new(
+                    "scope",
+                    FieldErrorKind::Unsupported,
+                    format!("Product {} not found.", product_id),
+                ));
+            }
+        }
+    }
+    if let CouponScope::Categories(category_ids) = &coupon.scope {
+        if category_ids.is_empty() || category_ids.len() > MAX_SCOPE_CATEGORIES {
+            errors.push(FieldError::new(
+                "scope",
+                FieldErrorKind::OutOfRange,
+                format!("Between 1 and {} categories.", MAX_SCOPE_CATEGORIES),
+            ));
+        }
+        for category_id in category_ids {
+            if store::get_category(*category_id).is_none() {
+                errors.push(FieldError::new(
+                    "scope",
+                    FieldErrorKind::Unsupported,
+                    format!("Category {} not found.", category_id),
+                ));
+            }
+        }
+    }
+    if let (Some(from), Some(until)) = (coupon.valid_from, coupon.valid_until) {
+        if until <= from {
+            errors.push(FieldError::new(
+                "valid_until",
+                FieldErrorKind::OutOfRange,
+                "The coupon must end after it starts.",
+            ));
+        }
+    }
+    if coupon
+        .min_order_amount
+        .is_some_and(|amount| !amount.is_finite() || amount < 0.0)
+    {
+        errors.push(FieldError::new(
+            "min_order_amount",
+            FieldErrorKind::OutOfRange,
+            "The minimum order amount cannot be negative.",
+        ));
+    }
+    for (field, limit) in [
+        ("max_uses", coupon.max_uses),
+        ("max_uses_per_customer", coupon.max_uses_per_customer),
+    ] {
+        if limit == Some(0) {
+            errors.push(FieldError::new(
+                field,
+                FieldErrorKind::OutOfRange,
+                "Limits must allow at least one use.",
+            ));
+        }
+    }
+    if errors.is_empty() {
+        Ok(coupon)
+    } else {
+        Err(CouponError::InvalidInput(errors))
+    }
+}
+
+fn coupon_uses(order: &Order, code: &str) -> bool {
+    order.status != OrderStatus::Cancelled
+        && order
+            .discounts
+            .iter()
+            .flatten()
+            .any(|discount| discount.coupon_code == code)
+}
+
+/// How often a coupon was used, in total or by one customer. Cancelled orders don't count.
+pub fn times_used(code: &str, phone_number: Option<&str>) -> u64 {
+    store::get_all_orders()
+        .iter()
+        .filter(|order| phone_number.is_none_or(|phone| order.user_phone_number == phone))
+        .filter(|order| coupon_uses(order, code))
+        .count() as u64
+}
+
+fn applies_to(scope: &CouponScope, product_id: u64) -> bool {
+    match scope {
+        CouponScope::AllProducts => true,
+        CouponScope::Products(product_ids) => product_ids.contains(&product_id),
+        CouponScope::Categories(category_ids) => store::get_product_by_id(product_id)
+            .is_some_and(|product| catalog::in_categories(product.category_id, category_ids)),
+    }
+}
+
+fn round_to_paise(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// The discount a coupon gives a customer on the priced order items at `now`.
+pub fn discount_for(
+    code: &str,
+    phone_number: &str,
+    items: &[OrderItem],
+    now: u64,
+) -> Result<OrderDiscount, CouponError> {
+    let code = normalize_code(code);
+    let coupon = store::get_coupon(&code).ok_or(CouponError::CouponNotFound(code.clone()))?;
+    if !coupon.enabled {
+        return Err(CouponError::Disabled);
+    }
+    if coupon.valid_from.is_some_and(|from| now < from) {
+        return Err(CouponError::NotYetValid);
+    }
+    if coupon.valid_until.is_some_and(|until| now >= until) {
+        return Err(CouponError::Expired);
+    }
+    if coupon
+        .max_uses
+        .is_some_and(|max| times_used(&code, None) >= max)
+    {
+        return Err(CouponError::UsageLimitReached);
+    }
+    if coupon
+        .max_uses_per_customer
+        .is_some_and(|max| times_used(&code, Some(phone_number)) >= max)
+    {
+        return Err(CouponError::CustomerLimitReached);
+    }
+    let terms = CouponTerms {
+        discount: coupon.discount,
+        scope: coupon.scope,
+        min_order_amount: coupon.min_order_amount,
+    };
+    discount_on(coupon.code, coupon.description, terms, items)
+}
+
+/// The discount of a coupon already redeemed on an order, recomputed for its edited items
+/// with the terms recorded on the order. Validity and usage limits were checked when the
+/// order was placed. Orders from before the terms were recorded fall back to the coupon.
+pub fn rediscount(
+    discount: &OrderDiscount,
+    items: &[OrderItem],
+) -> Result<OrderDiscount, CouponError> {
+    let terms = match &discount.terms {
+        Some(terms) => terms.clone(),
+        None => {
+            let code = normalize_code(&discount.coupon_code);
+            let coupon = store::get_coupon(&code).ok_or(CouponError::CouponNotFound(code))?;
+            CouponTerms {
+                discount: coupon.discount,
+                scope: coupon.scope,
+                min_order_amount: coupon.min_order_amount,
+            }
+        }
+    };
+    discount_on(
+        discount.coupon_code.clone(),
+        discount.description.clone(),
+        terms,
+        items,
+    )
+}
+
+fn discount_on(
+    coupon_code: String,
+    description: String,
+    terms: CouponTerms,
+    items: &[OrderItem],
+) -> Result<OrderDiscount, CouponError> {
+    let subtotal: f64 = items
+        .iter()
+        .map(|item| item.price_per_unit_at_order * item.quantity)
+        .sum();
+    if let Some(min_order_amount) = terms.min_order_amount {
+        if subtotal < min_order_amount {
+            return Err(CouponError::MinimumOrderAmount(min_order_amount));
+        }
+    }
+    let eligible: f64 = items
+        .iter()
+        .filter(|item| applies_to(&terms.scope, item.product_id))
+        .map(|item| item.price_per_unit_at_order * item.quantity)
+        .sum();
+    if eligible <= 0.0 {
+        return Err(CouponError::NotApplicable);
+    }
+    let amount = match terms.discount {
+        Discount::Percentage(percent) => eligible * percent / 100.0,
+        Discount::Flat(amount) => amount.min(eligible),
+    };
+    Ok(OrderDiscount {
+        coupon_code,
+        description,
+        amount: round_to_paise(amount),
+        terms: Some(terms),
+    })
+}
@@ -0,0 +1,396 @@
+// Backup and restore of the whole canister state.
+//
+// `export` encodes every store into one versioned Candid blob that is handed out in
+// checksummed chunks. `import` collects the chunks of such a blob and restores it in one go,
+// but only into a freshly installed canister and only if every chunk and checksum matches.
+// The restore either applies completely or traps, which rolls the whole call back.
+use crate::{
+    errors::SnapshotError,
+    guard,
+    models::{
+        ApiKey, AuditEntry, Cart, Category, Coupon, CyclesMonitorSettings, DailySummary,
+        DeletionRequest, Job, Notification, NotificationSettings, Order, OrderEdit,
+        PhoneChangeRequest, PhoneSettings, PriceChange, PriceList, Product, ProductImage,
+        SnapshotChunk, SnapshotManifest, UserProfile, VerificationSettings,
+    },
+    store::{self, memory::StorableString},
+};
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use sha2::{Digest, Sha256};
+use std::{cell::RefCell, collections::BTreeMap};
+
+/// Bump when the layout of [`Snapshot`] changes.
+pub const SNAPSHOT_VERSION: u32 = 2;
+/// Oldest version that still decodes: fields added since then are optional.
+const MIN_SNAPSHOT_VERSION: u32 = 1;
+// Leaves room for the Candid envelope within the 2 MB ingress limit
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+// OTP challenges are left out on purpose: they expire within minutes.
+#[derive(CandidType, Deserialize)]
+struct Snapshot {
+    version: u32,
+    created_at: u64,
+    guard: Vec<Principal>,
+    initialized: bool,
+    products: Vec<Product>,
+    user_profiles: Vec<UserProfile>,
+    orders: Vec<Order>,
+    next_order_id: u64,
+    audit_entries: Vec<AuditEntry>,
+    deletion_requests: Vec<DeletionRequest>,
+    next_tombstone_id: u64,
+    phone_settings: PhoneSettings,
+    phone_redirects: Vec<(String, String)>,
+    phone_change_requests: Vec<PhoneChangeRequest>,
+    verification_settings: VerificationSettings,
+    notification_settings: NotificationSettings,
+    notifications: Vec<Notification>,
+    next_notification_id: u64,
+    api_keys: Vec<ApiKey>,
+    next_api_key_id: u64,
+    // Optional so that snapshots taken before scheduled jobs existed still decode
+    jobs: Option<Vec<Job>>,
+    next_job_id: Option<u64>,
+    daily_summaries: Option<Vec<DailySummary>>,
+    // Cycle samples, monitored canisters and top-ups are left out: they describe the deployment
+    // the snapshot was taken on
+    cycles_monitor_settings: Option<CyclesMonitorSettings>,
+    coupons: Option<Vec<Coupon>>,
+    price_lists: Option<Vec<PriceList>>,
+    price_changes: Option<Vec<PriceChange>>,
+    categories: Option<Vec<Category>>,
+    images: Option<Vec<(ProductImage, Vec<u8>)>>,
+    carts: Option<Vec<Cart>>, // Since version 2
+    order_edits: Option<Vec<OrderEdit>>,
+}
+
+struct PendingImport {
+    manifest: SnapshotManifest,
+    chunks: BTreeMap<u64, Vec<u8>>,
+}
+
+thread_local! {
+    // Latest export, kept on the heap until the next export or upgrade
+    static EXPORT: RefCell<Option<(SnapshotManifest, Vec<u8>)>> = const { RefCell::new(None) };
+    static IMPORT: RefCell<Option<PendingImport>> = const { RefCell::new(None) };
+}
+
+fn sha256(bytes: &[u8]) -> Vec<u8> {
+    Sha256::digest(bytes).to_vec()
+}
+
+/// Encodes the current state and returns the manifest of its chunks.
+pub fn export(now: u64) -> Result<SnapshotManifest, SnapshotError> {
+    let snapshot = Snapshot {
+        version: SNAPSHOT_VERSION,
+        created_at: now,
+        guard: guard::GUARD.with(|guard| guard.borrow().iter().copied().collect()),
+        initialized: store::is_initialized(),
+        products: store::get_all_products(),
+        user_profiles: store::get_all_user_profiles(),
+        orders: {
+            // Stored orders as they are, without the current profile names filled in
+            let mut orders = Vec::new();
+            store::iter_orders(|order| orders.push(order));
+            orders
+        },
+        next_order_id: store::peek_next_order_id(),
+        audit_entries: store::get_audit_entries(0, store::audit_log_len()),
+        deletion_requests: store::get_deletion_requests(),
+        next_tombstone_id: store::peek_next_tombstone_id(),
+        phone_settings: store::get_phone_settings(),
+        phone_redirects: store::get_all_phone_redirects(),
+        phone_change_requests: store::get_phone_change_requests(),
+        verification_settings: store::get_verification_settings(),
+        notification_settings: store::get_notification_settings(),
+        notifications: store::get_all_notifications(),
+        next_notification_id: store::peek_next_notification_id(),
+        api_keys: store::get_all_api_keys(),
+        next_api_key_id: store::peek_next_api_key_id(),
+        jobs: Some(store::get_all_jobs()),
+        next_job_id: Some(store::peek_next_job_id()),
+        daily_summaries: Some(store::get_all_daily_summaries()),
+        cycles_monitor_settings: Some(store::get_cycles_monitor_settings()),
+        coupons: Some(store::get_all_coupons()),
+        price_lists: Some(store::get_all_price_lists()),
+        price_changes: Some(store::get_all_price_changes()),
+        categories: Some(store::get_all_categories()),
+        images: Some(
+            store::get_all_images()
+                .into_iter()
+                .map(|image| {
+                    let content = store::get_image_content(image.id).unwrap_or_default();
+                    (image, content)
+                })
+                .collect(),
+        ),
+        carts: Some(store::get_all_carts()),
+        order_edits: Some(store::get_all_order_edits()),
+    };
+    let bytes = Encode!(&snapshot).map_err(|e| SnapshotError::EncodingFailed(e.to_string()))?;
+    let manifest = SnapshotManifest {
+        version: SNAPSHOT_VERSION,
+        created_at: now,
+        total_size: bytes.len() as u64,
+        chunk_size: CHUNK_SIZE as u64,
+        chunk_count: bytes.len().div_ceil(CHUNK_SIZE) as u64,
+        checksum: sha256(&bytes),
+    };
+    EXPORT.with(|export| *export.borrow_mut() = Some((manifest.clone(), bytes)));
+    Ok(manifest)
+}
+
+/// Returns one chunk of the latest export.
+pub fn export_chunk(index: u64) -> Result<SnapshotChunk, SnapshotError> {
+    EXPORT.with(|export| {
+        let export = export.borrow();
+        let (manifest, bytes) = export.as_ref().ok_or(SnapshotError::NotExported)?;
+        if index >= manifest.chunk_count {
+            return Err(SnapshotError::ChunkOutOfRange(index));
+        }
+        let start = index as usize * CHUNK_SIZE;
+        let data = bytes[start..(start + CHUNK_SIZE).min(bytes.len())].to_vec();
+        Ok(SnapshotChunk {
+            version: manifest.version,
+            snapshot_checksum: manifest.checksum.clone(),
+            index,
+            checksum: sha256(&data),
+            data,
+        })
+    })
+}
+
+fn is_supported(version: u32) -> bool {
+    (MIN_SNAPSHOT_VERSION..=SNAPSHOT_VERSION).contains(&version)
+}
+
+/// A canister counts as fresh while nothing has been written to its main stores.
+fn is_fresh() -> bool {
+    !store::is_initialized()
+        && store::get_all_products().is_empty()
+        && store::get_all_user_profiles().is_empty()
+        && store::get_all_orders().is_empty()
+        && store::audit_log_len() == 0
+}
+
+/// Starts an import, discarding any previous unfinished one.
+pub fn begin_import(manifest: SnapshotManifest) -> Result<(), SnapshotError> {
+    if !is_supported(manifest.version) {
+        return Err(SnapshotError::UnsupportedVersion(manifest.version));
+    }
+    if manifest.chunk_count == 0
+        || manifest.total_size.div_ceil(manifest.chunk_size.max(1)) != manifest.chunk_count
+    {
+        return Err(SnapshotError::InvalidManifest);
+    }
+    if !is_fresh() {
+        return Err(SnapshotError::NotFresh);
+    }
+    IMPORT.with(|import| {
+        *import.borrow_mut() = Some(PendingImport {
+            manifest,
+            chunks: BTreeMap::new(),
+        })
+    });
+    Ok(())
+}
+
+/// Stores one chunk of the pending import. Returns the number of chunks received so far.
+pub fn import_chunk(chunk: SnapshotChunk) -> Result<u64, SnapshotError> {
+    IMPORT.with(|import| {
+        let mut import = import.borrow_mut();
+        let pending = import.as_mut().ok_or(SnapshotError::NoImportInProgress)?;
+        let manifest = &pending.manifest;
+        if chunk.version != manifest.version || chunk.snapshot_checksum != manifest.checksum {
+            return Err(SnapshotError::WrongSnapshot(chunk.index));
+        }
+        if chunk.index >= manifest.chunk_count {
+            return Err(SnapshotError::ChunkOutOfRange(chunk.index));
+        }
+        if sha256(&chunk.data) != chunk.checksum {
+            return Err(SnapshotError::ChecksumMismatch(Some(chunk.index)));
+        }
+        pending.chunks.insert(chunk.index, chunk.data);
+        Ok(pending.chunks.len() as u64)
+    })
+}
+
+/// Verifies the complete snapshot and restores it. Returns a summary of what was restored.
+/// An incomplete import stays pending so the missing chunks can still be sent.
+pub fn finish_import() -> Result<String, SnapshotError> {
+    let pending = IMPORT.with(|import| {
+        let mut import = import.borrow_mut();
+        let pending = import.as_ref().ok_or(SnapshotError::NoImportInProgress)?;
+        if pending.chunks.len() as u64 != pending.manifest.chunk_count {
+            return Err(SnapshotError::Incomplete {
+                received: pending.chunks.len() as u64,
+                expected: pending.manifest.chunk_count,
+            });
+        }
+        Ok(import.take().expect("checked above"))
+    })?;
+    let manifest = pending.manifest;
+    let bytes: Vec<u8> = pending.chunks.into_values().flatten().collect();
+    if bytes.len() as u64 != manifest.total_size || sha256(&bytes) != manifest.checksum {
+        return Err(SnapshotError::ChecksumMismatch(None));
+    }
+    let snapshot = Decode!(&bytes, Snapshot)
+        .map_err(|e| SnapshotError::InvalidSnapshot(format!("Failed to decode: {}", e)))?;
+    if !is_supported(snapshot.version) {
+        return Err(SnapshotError::UnsupportedVersion(snapshot.version));
+    }
+    // Checked again: the canister may have been written to while chunks were uploaded
+    if !is_fresh() {
+        return Err(SnapshotError::NotFresh);
+    }
+    restore(snapshot)
+}
+
+/// Checks everything that could make a write fail halfway through `restore`.
+fn validate(snapshot: &Snapshot) -> Result<(), String> {
+    store::verify_audit_entries(&snapshot.audit_entries)
+        .map_err(|id| format!("Audit entry {} does not continue the log.", id))?;
+    for product in &snapshot.products {
+        store::check_product_size(product).map_err(|e| format!("Product {}: {}", product.id, e))?;
+    }
+    for profile in &snapshot.user_profiles {
+        store::check_bounded_size(profile)
+            .map_err(|e| format!("Profile {}: {}", profile.phone_number, e))?;
+    }
+    for order in &snapshot.orders {
+        store::check_bounded_size(order).map_err(|e| format!("Order {}: {}", order.id, e))?;
+    }
+    for cart in snapshot.carts.iter().flatten() {
+        store::check_bounded_size(cart)
+            .map_err(|e| format!("Cart of {}: {}", cart.phone_number, e))?;
+    }
+    for request in &snapshot.deletion_requests {
+        store::check_bounded_size(request)
+            .map_err(|e| format!("Deletion request of {}: {}", request.phone_number, e))?;
+    }
+    for request in &snapshot.phone_change_requests {
+        store::check_bounded_size(request).map_err(|e| {
+            format!(
+                "Phone change request of {}: {}",
+                request.current_phone_number, e
+            )
+        })?;
+    }
+    for (old_phone_number, new_phone_number) in &snapshot.phone_redirects {
+        for phone_number in [old_phone_number, new_phone_number] {
+            store::check_bounded_size(&StorableString(phone_number.clone()))
+                .map_err(|e| format!("Redirect for {}: {}", old_phone_number, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Unwraps the result of a write, trapping on failure so that nothing is restored.
+fn applied<T, E: std::fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|e| {
+        ic_cdk::trap(&format!(
+            "Snapshot restore failed, nothing was restored: {}",
+            e
+        ))
+    })
+}
+
+fn restore(snapshot: Snapshot) -> Result<String, SnapshotError> {
+    validate(&snapshot).map_err(SnapshotError::InvalidSnapshot)?;
+    let summary = format!(
+        "Restored {} products, {} customers, {} orders and {} audit entries.",
+        snapshot.products.len(),
+        snapshot.user_profiles.len(),
+        snapshot.orders.len(),
+        snapshot.audit_entries.len()
+    );
+
+    // The audit log goes first so that its chain is restored exactly
+    for entry in snapshot.audit_entries {
+        applied(store::restore_audit_entry(entry));
+    }
+    for principal in snapshot.guard {
+        guard::add_to_list(principal);
+    }
+    for product in snapshot.products {
+        applied(store::add_product(product));
+    }
+    if snapshot.initialized {
+        applied(store::mark_initialized());
+    }
+    for profile in snapshot.user_profiles {
+        store::update_user_profile(profile);
+    }
+    for order in snapshot.orders {
+        applied(store::add_order(order));
+    }
+    applied(store::set_next_order_id(snapshot.next_order_id));
+    for request in snapshot.deletion_requests {
+        store::add_deletion_request(request);
+    }
+    applied(store::set_next_tombstone_id(snapshot.next_tombstone_id));
+    applied(store::set_phone_settings(snapshot.phone_settings));
+    for (old_phone_number, new_phone_number) in snapshot.phone_redirects {
+        store::set_phone_redirect(&old_phone_number, &new_phone_number);
+    }
+    for request in snapshot.phone_change_requests {
+        store::add_phone_change_request(request);
+    }
+    applied(store::set_verification_settings(
+        snapshot.verification_settings,
+    ));
+    applied(store::set_notification_settings(
+        snapshot.notification_settings,
+    ));
+    for notification in snapshot.notifications {
+        store::put_notification(notification);
+    }
+    applied(store::set_next_notification_id(
+        snapshot.next_notification_id,
+    ));
+    for api_key in snapshot.api_keys {
+        store::put_api_key(api_key);
+    }
+    applied(store::set_next_api_key_id(snapshot.next_api_key_id));
+    if let Some(jobs) = snapshot.jobs {
+        // Replaces the built-in jobs the fresh canister was installed with
+        for job in store::get_all_jobs() {
+            store::remove_job(job.id);
+        }
+        for job in jobs {
+            store::put_job(job);
+        }
+    }
+    if let Some(next_job_id) = snapshot.next_job_id {
+        applied(store::set_next_job_id(next_job_id));
+    }
+    for daily_summary in snapshot.daily_summaries.unwrap_or_default() {
+        store::put_daily_summary(daily_summary);
+    }
+    if let Some(settings) = snapshot.cycles_monitor_settings {
+        applied(store::set_cycles_monitor_settings(settings));
+    }
+    for coupon in snapshot.coupons.unwrap_or_default() {
+        store::put_coupon(coupon);
+    }
+    for price_list in snapshot.price_lists.unwrap_or_default() {
+        store::put_price_list(price_list);
+    }
+    for price_change in snapshot.price_changes.unwrap_or_default() {
+        store::put_price_change(price_change);
+    }
+    for category in snapshot.categories.unwrap_or_default() {
+        store::put_category(category);
+    }
+    for (image, content) in snapshot.images.unwrap_or_default() {
+        store::put_image(image, content);
+    }
+    for cart in snapshot.carts.unwrap_or_default() {
+        store::put_cart(cart);
+    }
+    for edit in snapshot.order_edits.unwrap_or_default() {
+        store::add_order_edit(edit);
+    }
+    Ok(summary)
+}
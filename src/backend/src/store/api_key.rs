@@ -0,0 +1,87 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, StableCell, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, MEMORY_MANAGER};
+use crate::models::ApiKey;
+
+// Implement Storable for ApiKey using Candid encoding
+impl Storable for ApiKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode ApiKey"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode ApiKey")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Thread-local API key storage
+thread_local! {
+    static NEXT_API_KEY_ID: RefCell<StableCell<u64, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::NEXT_API_KEY_ID_MEM_ID)),
+            0
+        ).expect("Failed to init StableCell for NEXT_API_KEY_ID")
+    );
+
+    // API keys: Map API Key ID (u64) -> ApiKey
+    static API_KEYS: RefCell<StableBTreeMap<u64, ApiKey, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::API_KEYS_MEM_ID))
+        )
+    );
+}
+
+/// Gets the next available API key ID and increments the counter.
+pub fn get_next_api_key_id() -> u64 {
+    NEXT_API_KEY_ID.with(|cell| {
+        let current_id = *cell.borrow().get();
+        cell.borrow_mut()
+            .set(current_id + 1)
+            .expect("Failed to increment API key ID counter");
+        current_id
+    })
+}
+
+/// Returns the next API key ID without incrementing the counter.
+pub fn peek_next_api_key_id() -> u64 {
+    NEXT_API_KEY_ID.with(|cell| *cell.borrow().get())
+}
+
+/// Sets the API key ID counter, e.g. when restoring a snapshot.
+pub fn set_next_api_key_id(next_id: u64) -> Result<(), String> {
+    NEXT_API_KEY_ID.with(|cell| {
+        cell.borrow_mut()
+            .set(next_id)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to set API key ID counter: {:?}", e))
+    })
+}
+
+/// Inserts or updates an API key.
+pub fn put_api_key(api_key: ApiKey) {
+    API_KEYS.with(|keys| keys.borrow_mut().insert(api_key.id, api_key));
+}
+
+/// Retrieves an API key by its ID.
+pub fn get_api_key(id: u64) -> Option<ApiKey> {
+    API_KEYS.with(|keys| keys.borrow().get(&id))
+}
+
+/// Retrieves all API keys, including revoked ones.
+pub fn get_all_api_keys() -> Vec<ApiKey> {
+    API_KEYS.with(|keys| keys.borrow().iter().map(|(_, key)| key).collect())
+}
+
+/// Finds the active (non-revoked) API key with the given hash.
+pub fn find_active_api_key(key_hash: &[u8]) -> Option<ApiKey> {
+    API_KEYS.with(|keys| {
+        keys.borrow()
+            .iter()
+            .map(|(_, key)| key)
+            .find(|key| !key.revoked && key.key_hash == key_hash)
+    })
+}
@@ -0,0 +1,171 @@
+use candid::{Decode, Encode, Principal};
+use ic_stable_structures::{storable::Bound, StableLog, Storable};
+use sha2::{Digest, Sha256};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, MEMORY_MANAGER};
+use crate::models::{AuditEntry, AuditOutcome};
+
+// Implement Storable for AuditEntry using Candid encoding
+impl Storable for AuditEntry {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode AuditEntry"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode AuditEntry")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Thread-local audit log storage (append-only, entries are never rewritten)
+thread_local! {
+    static AUDIT_LOG: RefCell<StableLog<AuditEntry, Memory, Memory>> = RefCell::new(
+        StableLog::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::AUDIT_LOG_INDEX_MEM_ID)),
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::AUDIT_LOG_DATA_MEM_ID)),
+        ).expect("Failed to init StableLog for AUDIT_LOG")
+    );
+}
+
+const GENESIS_HASH: [u8; 32] = [0; 32];
+
+/// Computes the chained hash of an entry from its predecessor's hash and its own fields.
+/// Variable-length fields are length-prefixed so that field boundaries can't be shifted.
+fn entry_hash(
+    prev_hash: &[u8],
+    id: u64,
+    timestamp: u64,
+    caller: &Principal,
+    method: &str,
+    args_summary: &str,
+    outcome: &AuditOutcome,
+) -> Vec<u8> {
+    fn put(hasher: &mut Sha256, bytes: &[u8]) {
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    }
+
+    let mut hasher = Sha256::new();
+    put(&mut hasher, prev_hash);
+    hasher.update(id.to_le_bytes());
+    hasher.update(timestamp.to_le_bytes());
+    put(&mut hasher, caller.as_slice());
+    put(&mut hasher, method.as_bytes());
+    put(&mut hasher, args_summary.as_bytes());
+    match outcome {
+        AuditOutcome::Success => hasher.update([0u8]),
+        AuditOutcome::Failure(reason) => {
+            hasher.update([1u8]);
+            put(&mut hasher, reason.as_bytes());
+        }
+    }
+    hasher.finalize().to_vec()
+}
+
+/// Appends a new entry to the audit log, chaining it to the last entry. Returns the entry ID.
+pub fn append_audit_entry(
+    caller: Principal,
+    method: String,
+    args_summary: String,
+    outcome: AuditOutcome,
+    timestamp: u64,
+) -> Result<u64, String> {
+    AUDIT_LOG.with(|log| {
+        let log = log.borrow();
+        let id = log.len();
+        let prev_hash = match id.checked_sub(1).and_then(|last| log.get(last)) {
+            Some(last_entry) => last_entry.hash,
+            None => GENESIS_HASH.to_vec(),
+        };
+        let hash = entry_hash(
+            &prev_hash,
+            id,
+            timestamp,
+            &caller,
+            &method,
+            &args_summary,
+            &outcome,
+        );
+        let entry = AuditEntry {
+            id,
+            timestamp,
+            caller,
+            method,
+            args_summary,
+            outcome,
+            prev_hash,
+            hash,
+        };
+        log.append(&entry)
+            .map_err(|e| format!("Failed to append audit entry: {:?}", e))
+    })
+}
+
+/// Appends an entry taken from a snapshot as-is. It must continue the current chain.
+pub fn restore_audit_entry(entry: AuditEntry) -> Result<(), String> {
+    AUDIT_LOG.with(|log| {
+        let log = log.borrow();
+        let expected_prev = match log.len().checked_sub(1).and_then(|last| log.get(last)) {
+            Some(last_entry) => last_entry.hash,
+            None => GENESIS_HASH.to_vec(),
+        };
+        if entry.id != log.len() || entry.prev_hash != expected_prev {
+            return Err(format!(
+                "Audit entry {} does not continue the log",
+                entry.id
+            ));
+        }
+        log.append(&entry)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to append audit entry: {:?}", e))
+    })
+}
+
+/// Returns the total number of entries in the audit log.
+pub fn audit_log_len() -> u64 {
+    AUDIT_LOG.with(|log| log.borrow().len())
+}
+
+/// Retrieves up to `limit` entries starting at entry ID `start`.
+pub fn get_audit_entries(start: u64, limit: u64) -> Vec<AuditEntry> {
+    AUDIT_LOG.with(|log| {
+        let log = log.borrow();
+        let end = start.saturating_add(limit).min(log.len());
+        (start..end).filter_map(|idx| log.get(idx)).collect()
+    })
+}
+
+/// Walks the whole log and recomputes every hash.
+/// Returns the ID of the first entry whose chain link or hash doesn't match, if any.
+pub fn verify_audit_chain() -> Result<(), u64> {
+    AUDIT_LOG.with(|log| verify_chain(log.borrow().iter()))
+}
+
+/// Same as [`verify_audit_chain`] for entries that are not in the log, e.g. from a snapshot.
+/// They must form a complete log starting at the genesis hash.
+pub fn verify_audit_entries(entries: &[AuditEntry]) -> Result<(), u64> {
+    verify_chain(entries.iter().cloned())
+}
+
+fn verify_chain(entries: impl Iterator<Item = AuditEntry>) -> Result<(), u64> {
+    let mut expected_prev = GENESIS_HASH.to_vec();
+    for (index, entry) in entries.enumerate() {
+        let recomputed = entry_hash(
+            &entry.prev_hash,
+            entry.id,
+            entry.timestamp,
+            &entry.caller,
+            &entry.method,
+            &entry.args_summary,
+            &entry.outcome,
+        );
+        if entry.id != index as u64 || entry.prev_hash != expected_prev || entry.hash != recomputed
+        {
+            return Err(entry.id);
+        }
+        expected_prev = entry.hash;
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, StorableString, MEMORY_MANAGER};
+use crate::models::Cart;
+
+// Implement Storable for Cart using Candid encoding
+impl Storable for Cart {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode Cart"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode Cart")
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: super::MAX_CART_SIZE,
+        is_fixed_size: false,
+    };
+}
+
+// Thread-local cart storage
+thread_local! {
+    // Shopping carts: Map Phone Number -> Cart (empty carts are removed)
+    static CARTS: RefCell<StableBTreeMap<StorableString, Cart, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::CARTS_MEM_ID))
+        )
+    );
+}
+
+/// Retrieves the cart of a customer.
+pub fn get_cart(phone_number: &str) -> Option<Cart> {
+    let key = StorableString(phone_number.to_string());
+    CARTS.with(|carts| carts.borrow().get(&key))
+}
+
+/// Retrieves every stored cart.
+pub fn get_all_carts() -> Vec<Cart> {
+    CARTS.with(|carts| carts.borrow().iter().map(|(_, cart)| cart).collect())
+}
+
+/// Stores the cart under the phone number within it, or removes it if it has no items.
+pub fn put_cart(cart: Cart) {
+    let key = StorableString(cart.phone_number.clone());
+    CARTS.with(|carts| {
+        let mut carts = carts.borrow_mut();
+        if cart.items.is_empty() {
+            carts.remove(&key);
+        } else {
+            carts.insert(key, cart);
+        }
+    });
+}
+
+/// Removes the cart of a customer. Returns it if there was one.
+pub fn remove_cart(phone_number: &str) -> Option<Cart> {
+    let key = StorableString(phone_number.to_string());
+    CARTS.with(|carts| carts.borrow_mut().remove(&key))
+}
@@ -0,0 +1,65 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, MEMORY_MANAGER};
+use crate::models::Category;
+
+// Implement Storable for Category using Candid encoding
+impl Storable for Category {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode Category"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode Category")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Thread-local category storage
+thread_local! {
+    // Categories: Map Category ID -> Category
+    static CATEGORIES: RefCell<StableBTreeMap<u64, Category, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::CATEGORIES_MEM_ID))
+        )
+    );
+}
+
+/// The ID the next new category gets.
+pub fn next_category_id() -> u64 {
+    CATEGORIES.with(|categories| {
+        categories
+            .borrow()
+            .last_key_value()
+            .map_or(0, |(id, _)| id + 1)
+    })
+}
+
+/// Retrieves a category by its ID.
+pub fn get_category(id: u64) -> Option<Category> {
+    CATEGORIES.with(|categories| categories.borrow().get(&id))
+}
+
+/// Retrieves all categories, ordered by ID.
+pub fn get_all_categories() -> Vec<Category> {
+    CATEGORIES.with(|categories| {
+        categories
+            .borrow()
+            .iter()
+            .map(|(_, category)| category)
+            .collect()
+    })
+}
+
+/// Inserts or replaces the category under its ID.
+pub fn put_category(category: Category) {
+    CATEGORIES.with(|categories| categories.borrow_mut().insert(category.id, category));
+}
+
+/// Removes a category, returning it if it existed.
+pub fn remove_category(id: u64) -> Option<Category> {
+    CATEGORIES.with(|categories| categories.borrow_mut().remove(&id))
+}
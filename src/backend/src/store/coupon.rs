@@ -0,0 +1,52 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, StorableString, MEMORY_MANAGER};
+use crate::models::Coupon;
+
+// Implement Storable for Coupon using Candid encoding
+impl Storable for Coupon {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode Coupon"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode Coupon")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Thread-local coupon storage
+thread_local! {
+    // Coupons: Map Code (upper case) -> Coupon
+    static COUPONS: RefCell<StableBTreeMap<StorableString, Coupon, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::COUPONS_MEM_ID))
+        )
+    );
+}
+
+/// Retrieves a coupon by its (normalised) code.
+pub fn get_coupon(code: &str) -> Option<Coupon> {
+    let key = StorableString(code.to_string());
+    COUPONS.with(|coupons| coupons.borrow().get(&key))
+}
+
+/// Retrieves all coupons, ordered by code.
+pub fn get_all_coupons() -> Vec<Coupon> {
+    COUPONS.with(|coupons| coupons.borrow().iter().map(|(_, coupon)| coupon).collect())
+}
+
+/// Inserts or replaces the coupon under its code.
+pub fn put_coupon(coupon: Coupon) {
+    let key = StorableString(coupon.code.clone());
+    COUPONS.with(|coupons| coupons.borrow_mut().insert(key, coupon));
+}
+
+/// Removes a coupon, returning it if it existed.
+pub fn remove_coupon(code: &str) -> Option<Coupon> {
+    let key = StorableString(code.to_string());
+    COUPONS.with(|coupons| coupons.borrow_mut().remove(&key))
+}
@@ -0,0 +1,159 @@
+use candid::{Decode, Encode, Principal};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, StorablePrincipal, MEMORY_MANAGER};
+use crate::models::{CyclesSample, MonitoredCanister, TopUp};
+
+// Implement Storable for CyclesSample using Candid encoding
+impl Storable for CyclesSample {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode CyclesSample"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode CyclesSample")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Implement Storable for MonitoredCanister using Candid encoding
+impl Storable for MonitoredCanister {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode MonitoredCanister"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode MonitoredCanister")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Implement Storable for TopUp using Candid encoding
+impl Storable for TopUp {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode TopUp"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode TopUp")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Thread-local cycles time series storage
+thread_local! {
+    // Samples: Map (Timestamp, Canister ID) -> CyclesSample, i.e. ordered by time
+    static CYCLES_SAMPLES: RefCell<StableBTreeMap<(u64, StorablePrincipal), CyclesSample, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::CYCLES_SAMPLES_MEM_ID))
+        )
+    );
+
+    // Monitored canisters: Map Canister ID -> MonitoredCanister
+    static MONITORED_CANISTERS: RefCell<StableBTreeMap<StorablePrincipal, MonitoredCanister, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::MONITORED_CANISTERS_MEM_ID))
+        )
+    );
+
+    // Top-ups: Map Top-up ID -> TopUp. Entries are never removed, so IDs are sequential.
+    static TOP_UPS: RefCell<StableBTreeMap<u64, TopUp, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::TOP_UPS_MEM_ID))
+        )
+    );
+}
+
+/// Retrieves all monitored canisters, ordered by canister ID.
+pub fn get_monitored_canisters() -> Vec<MonitoredCanister> {
+    MONITORED_CANISTERS.with(|canisters| {
+        canisters
+            .borrow()
+            .iter()
+            .map(|(_, canister)| canister)
+            .collect()
+    })
+}
+
+/// Adds a canister to the monitored list or changes its label.
+pub fn set_monitored_canister(canister: MonitoredCanister) {
+    MONITORED_CANISTERS.with(|canisters| {
+        canisters
+            .borrow_mut()
+            .insert(StorablePrincipal(canister.canister_id), canister)
+    });
+}
+
+/// Removes a canister from the monitored list. Returns the removed entry, if any.
+pub fn remove_monitored_canister(canister_id: Principal) -> Option<MonitoredCanister> {
+    MONITORED_CANISTERS.with(|canisters| {
+        canisters
+            .borrow_mut()
+            .remove(&StorablePrincipal(canister_id))
+    })
+}
+
+/// Appends a sample to the time series.
+pub fn add_cycles_sample(sample: CyclesSample) {
+    let key = (sample.timestamp, StorablePrincipal(sample.canister_id));
+    CYCLES_SAMPLES.with(|samples| samples.borrow_mut().insert(key, sample));
+}
+
+/// Retrieves the samples of a canister taken at or after `since`, oldest first.
+pub fn get_cycles_samples(canister_id: Principal, since: u64) -> Vec<CyclesSample> {
+    let start = (since, StorablePrincipal(Principal::management_canister()));
+    CYCLES_SAMPLES.with(|samples| {
+        samples
+            .borrow()
+            .range(start..)
+            .map(|(_, sample)| sample)
+            .filter(|sample| sample.canister_id == canister_id)
+            .collect()
+    })
+}
+
+/// Removes all samples taken before `cutoff`. Returns the number of samples removed.
+pub fn remove_cycles_samples_before(cutoff: u64) -> u64 {
+    CYCLES_SAMPLES.with(|samples| {
+        let mut samples = samples.borrow_mut();
+        let expired: Vec<(u64, StorablePrincipal)> = samples
+            .iter()
+            .take_while(|((timestamp, _), _)| *timestamp < cutoff)
+            .map(|(key, _)| key)
+            .collect();
+        for key in &expired {
+            samples.remove(key);
+        }
+        expired.len() as u64
+    })
+}
+
+/// Returns the ID the next top-up will be stored under.
+pub fn get_next_top_up_id() -> u64 {
+    TOP_UPS.with(|top_ups| top_ups.borrow().len())
+}
+
+/// Inserts or replaces a top-up.
+pub fn put_top_up(top_up: TopUp) {
+    TOP_UPS.with(|top_ups| top_ups.borrow_mut().insert(top_up.id, top_up));
+}
+
+pub fn get_top_up(id: u64) -> Option<TopUp> {
+    TOP_UPS.with(|top_ups| top_ups.borrow().get(&id))
+}
+
+/// Retrieves all top-ups, newest first.
+pub fn get_top_ups() -> Vec<TopUp> {
+    TOP_UPS.with(|top_ups| {
+        top_ups
+            .borrow()
+            .iter()
+            .rev()
+            .map(|(_, top_up)| top_up)
+            .collect()
+    })
+}
@@ -0,0 +1,99 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, StableCell, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, StorableString, MEMORY_MANAGER};
+use crate::models::DeletionRequest;
+
+// Implement Storable for DeletionRequest using Candid encoding
+impl Storable for DeletionRequest {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode DeletionRequest"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode DeletionRequest")
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: super::MAX_DELETION_REQUEST_SIZE,
+        is_fixed_size: false,
+    };
+}
+
+// Thread-local data-deletion storage
+thread_local! {
+    // Counter used to derive unique tombstone IDs for anonymised customers
+    static NEXT_TOMBSTONE_ID: RefCell<StableCell<u64, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::NEXT_TOMBSTONE_ID_MEM_ID)),
+            0
+        ).expect("Failed to init StableCell for NEXT_TOMBSTONE_ID")
+    );
+
+    // Pending customer deletion requests: Map Phone Number -> DeletionRequest
+    static DELETION_REQUESTS: RefCell<StableBTreeMap<StorableString, DeletionRequest, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::DELETION_REQUESTS_MEM_ID))
+        )
+    );
+}
+
+/// Prefix of the identifier that replaces the phone number, name and address of anonymised customers.
+pub const TOMBSTONE_PREFIX: &str = "deleted-";
+
+/// Returns a fresh tombstone ID (e.g. `deleted-000001`) and increments the counter.
+pub fn next_tombstone_id() -> String {
+    NEXT_TOMBSTONE_ID.with(|cell| {
+        let current_id = *cell.borrow().get();
+        cell.borrow_mut()
+            .set(current_id + 1)
+            .expect("Failed to increment tombstone ID counter");
+        format!("{}{:06}", TOMBSTONE_PREFIX, current_id)
+    })
+}
+
+/// Returns the next tombstone ID without incrementing the counter.
+pub fn peek_next_tombstone_id() -> u64 {
+    NEXT_TOMBSTONE_ID.with(|cell| *cell.borrow().get())
+}
+
+/// Sets the tombstone ID counter, e.g. when restoring a snapshot.
+pub fn set_next_tombstone_id(next_id: u64) -> Result<(), String> {
+    NEXT_TOMBSTONE_ID.with(|cell| {
+        cell.borrow_mut()
+            .set(next_id)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to set tombstone ID counter: {:?}", e))
+    })
+}
+
+/// Stores a deletion request. Returns false if one is already pending for the phone number.
+pub fn add_deletion_request(request: DeletionRequest) -> bool {
+    let key = StorableString(request.phone_number.clone());
+    DELETION_REQUESTS.with(|requests| {
+        let mut requests = requests.borrow_mut();
+        if requests.contains_key(&key) {
+            return false;
+        }
+        requests.insert(key, request);
+        true
+    })
+}
+
+/// Retrieves all pending deletion requests.
+pub fn get_deletion_requests() -> Vec<DeletionRequest> {
+    DELETION_REQUESTS.with(|requests| {
+        requests
+            .borrow()
+            .iter()
+            .map(|(_, request)| request)
+            .collect()
+    })
+}
+
+/// Removes the pending deletion request for a phone number, if any.
+pub fn remove_deletion_request(phone_number: &str) -> Option<DeletionRequest> {
+    let key = StorableString(phone_number.to_string());
+    DELETION_REQUESTS.with(|requests| requests.borrow_mut().remove(&key))
+}
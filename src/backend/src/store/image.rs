@@ -0,0 +1,79 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, MEMORY_MANAGER};
+use crate::models::ProductImage;
+
+// Implement Storable for ProductImage using Candid encoding
+impl Storable for ProductImage {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode ProductImage"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode ProductImage")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Thread-local image storage
+thread_local! {
+    // Image metadata: Map Image ID -> ProductImage
+    static IMAGES: RefCell<StableBTreeMap<u64, ProductImage, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::IMAGES_MEM_ID))
+        )
+    );
+
+    // Image content: Map Image ID -> bytes. Kept apart so listing images doesn't load them.
+    static IMAGE_CONTENT: RefCell<StableBTreeMap<u64, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::IMAGE_CONTENT_MEM_ID))
+        )
+    );
+}
+
+/// The ID the next new image gets.
+pub fn next_image_id() -> u64 {
+    IMAGES.with(|images| images.borrow().last_key_value().map_or(0, |(id, _)| id + 1))
+}
+
+/// Retrieves an image's metadata by its ID.
+pub fn get_image(id: u64) -> Option<ProductImage> {
+    IMAGES.with(|images| images.borrow().get(&id))
+}
+
+/// Retrieves an image's content by its ID.
+pub fn get_image_content(id: u64) -> Option<Vec<u8>> {
+    IMAGE_CONTENT.with(|content| content.borrow().get(&id))
+}
+
+/// Retrieves the metadata of all images, ordered by ID.
+pub fn get_all_images() -> Vec<ProductImage> {
+    IMAGES.with(|images| images.borrow().iter().map(|(_, image)| image).collect())
+}
+
+/// The image with the given SHA-256 hash, if one was stored.
+pub fn find_image_by_hash(sha256: &[u8]) -> Option<ProductImage> {
+    IMAGES.with(|images| {
+        images
+            .borrow()
+            .iter()
+            .map(|(_, image)| image)
+            .find(|image| image.sha256 == sha256)
+    })
+}
+
+/// Inserts or replaces an image and its content under the image's ID.
+pub fn put_image(image: ProductImage, content: Vec<u8>) {
+    IMAGE_CONTENT.with(|images| images.borrow_mut().insert(image.id, content));
+    IMAGES.with(|images| images.borrow_mut().insert(image.id, image));
+}
+
+/// Removes an image and its content, returning the metadata if it existed.
+pub fn remove_image(id: u64) -> Option<ProductImage> {
+    IMAGE_CONTENT.with(|content| content.borrow_mut().remove(&id));
+    IMAGES.with(|images| images.borrow_mut().remove(&id))
+}
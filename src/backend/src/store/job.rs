@@ -0,0 +1,131 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, StableCell, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, StorableString, MEMORY_MANAGER};
+use crate::models::{DailySummary, Job};
+
+// Implement Storable for Job using Candid encoding
+impl Storable for Job {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode Job"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode Job")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Implement Storable for DailySummary using Candid encoding
+impl Storable for DailySummary {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode DailySummary"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode DailySummary")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Thread-local scheduled job storage
+thread_local! {
+    static NEXT_JOB_ID: RefCell<StableCell<u64, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::NEXT_JOB_ID_MEM_ID)),
+            0
+        ).expect("Failed to init StableCell for NEXT_JOB_ID")
+    );
+
+    // Jobs: Map Job ID (u64) -> Job
+    static JOBS: RefCell<StableBTreeMap<u64, Job, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::JOBS_MEM_ID))
+        )
+    );
+
+    // Daily summaries: Map local date (YYYY-MM-DD) -> DailySummary
+    static DAILY_SUMMARIES: RefCell<StableBTreeMap<StorableString, DailySummary, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::DAILY_SUMMARIES_MEM_ID))
+        )
+    );
+}
+
+/// Gets the next available job ID and increments the counter.
+pub fn get_next_job_id() -> u64 {
+    NEXT_JOB_ID.with(|cell| {
+        let current_id = *cell.borrow().get();
+        cell.borrow_mut()
+            .set(current_id + 1)
+            .expect("Failed to increment job ID counter");
+        current_id
+    })
+}
+
+/// Returns the next job ID without incrementing the counter.
+pub fn peek_next_job_id() -> u64 {
+    NEXT_JOB_ID.with(|cell| *cell.borrow().get())
+}
+
+/// Sets the job ID counter, e.g. when restoring a snapshot.
+pub fn set_next_job_id(next_id: u64) -> Result<(), String> {
+    NEXT_JOB_ID.with(|cell| {
+        cell.borrow_mut()
+            .set(next_id)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to set job ID counter: {:?}", e))
+    })
+}
+
+/// Inserts or updates a job.
+pub fn put_job(job: Job) {
+    JOBS.with(|jobs| jobs.borrow_mut().insert(job.id, job));
+}
+
+/// Retrieves a job by its ID.
+pub fn get_job(id: u64) -> Option<Job> {
+    JOBS.with(|jobs| jobs.borrow().get(&id))
+}
+
+/// Retrieves all jobs.
+pub fn get_all_jobs() -> Vec<Job> {
+    JOBS.with(|jobs| jobs.borrow().iter().map(|(_, job)| job).collect())
+}
+
+/// Removes a job, returning it if it existed.
+pub fn remove_job(id: u64) -> Option<Job> {
+    JOBS.with(|jobs| jobs.borrow_mut().remove(&id))
+}
+
+/// Stores the summary of a day, replacing an earlier one for the same date.
+pub fn put_daily_summary(summary: DailySummary) {
+    let key = StorableString(summary.date.clone());
+    DAILY_SUMMARIES.with(|summaries| summaries.borrow_mut().insert(key, summary));
+}
+
+/// Retrieves the summaries from `from_date` to `to_date` (inclusive, YYYY-MM-DD).
+pub fn get_daily_summaries(from_date: &str, to_date: &str) -> Vec<DailySummary> {
+    let range = StorableString(from_date.to_string())..=StorableString(to_date.to_string());
+    DAILY_SUMMARIES.with(|summaries| {
+        summaries
+            .borrow()
+            .range(range)
+            .map(|(_, summary)| summary)
+            .collect()
+    })
+}
+
+/// Retrieves all daily summaries, oldest first.
+pub fn get_all_daily_summaries() -> Vec<DailySummary> {
+    DAILY_SUMMARIES.with(|summaries| {
+        summaries
+            .borrow()
+            .iter()
+            .map(|(_, summary)| summary)
+            .collect()
+    })
+}
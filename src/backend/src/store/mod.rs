@@ -1,33 +1,173 @@
 // Declare submodules
+pub mod api_key;
+pub mod audit;
+pub mod cart;
+pub mod category;
+pub mod coupon;
+pub mod cycles;
+pub mod deletion;
+pub mod image;
 pub mod init;
+pub mod job;
 pub mod memory;
 pub mod order;
+pub mod order_edit;
+pub mod otp;
+pub mod outbox;
+pub mod price_history;
+pub mod price_list;
 pub mod product;
+pub mod redirect;
+pub mod search_index;
+pub mod settings;
 pub mod user;
 
 // Re-export only needed functions for backward compatibility
 // Removed unused re-exports: `MEMORY_MANAGER`, `Memory`, `StorablePrincipal`, and `StorableString`
 
+pub use api_key::{
+    find_active_api_key, get_all_api_keys, get_api_key, get_next_api_key_id, peek_next_api_key_id,
+    put_api_key, set_next_api_key_id,
+};
+pub use audit::{
+    append_audit_entry, audit_log_len, get_audit_entries, restore_audit_entry, verify_audit_chain,
+    verify_audit_entries,
+};
+pub use cart::{get_all_carts, get_cart, put_cart, remove_cart};
+pub use category::{
+    get_all_categories, get_category, next_category_id, put_category, remove_category,
+};
+pub use coupon::{get_all_coupons, get_coupon, put_coupon, remove_coupon};
+pub use cycles::{
+    add_cycles_sample, get_cycles_samples, get_monitored_canisters, get_next_top_up_id, get_top_up,
+    get_top_ups, put_top_up, remove_cycles_samples_before, remove_monitored_canister,
+    set_monitored_canister,
+};
+pub use deletion::{
+    add_deletion_request, get_deletion_requests, next_tombstone_id, peek_next_tombstone_id,
+    remove_deletion_request, set_next_tombstone_id,
+};
+pub use image::{
+    find_image_by_hash, get_all_images, get_image, get_image_content, next_image_id, put_image,
+    remove_image,
+};
 pub use init::{is_initialized, mark_initialized};
+pub use job::{
+    get_all_daily_summaries, get_all_jobs, get_daily_summaries, get_job, get_next_job_id,
+    peek_next_job_id, put_daily_summary, put_job, remove_job, set_next_job_id,
+};
 pub use order::{
-    add_order, get_all_orders, get_next_order_id, get_order, get_orders_by_phone,
-    update_order_status,
+    add_order, get_all_orders, get_next_order_id, get_order, get_order_phone_numbers,
+    get_orders_by_phone, iter_orders, peek_next_order_id, set_next_order_id, update_order,
+    update_order_status, update_orders_by_phone,
+};
+pub use order_edit::{add_order_edit, get_all_order_edits, get_order_edits, update_order_edits};
+pub use otp::{
+    get_all_otp_challenges, get_all_otp_lockouts, get_otp_challenge, get_otp_lockout,
+    put_otp_challenge, put_otp_lockout, remove_otp_challenge, remove_otp_lockout,
+};
+pub use outbox::{
+    get_all_notifications, get_due_notifications, get_failed_notifications,
+    get_next_notification_id, get_notification, next_notification_due_at,
+    peek_next_notification_id, put_notification, remove_notification, set_next_notification_id,
+    update_notifications_by_phone,
+};
+pub use price_history::{
+    get_all_price_changes, get_price_change_at, get_price_changes, put_price_change,
+    remove_price_change,
+};
+pub use price_list::{
+    get_all_price_lists, get_price_list, next_price_list_id, put_price_list, remove_price_list,
+};
+pub use product::{
+    add_product, check_product_size, get_all_products, get_product_by_id, migrate_legacy_products,
+    update_product,
+};
+pub use redirect::{
+    add_phone_change_request, get_all_phone_redirects, get_phone_change_request,
+    get_phone_change_requests, get_phone_redirect, remove_phone_change_request,
+    remove_phone_redirect, remove_phone_redirects_to, set_phone_redirect,
+};
+pub use search_index::{ensure_search_index, find_products_by_word, search_words};
+pub use settings::{
+    get_cycles_monitor_settings, get_notification_settings, get_phone_settings,
+    get_top_up_settings, get_verification_settings, set_cycles_monitor_settings,
+    set_notification_settings, set_phone_settings, set_top_up_settings, set_verification_settings,
 };
-pub use product::{add_product, get_all_products, get_product_by_id, update_product};
 pub use user::{delete_user_profile, get_all_user_profiles, get_user_profile, update_user_profile};
 
-// Common memory ID constants for all store modules
+use crate::errors::StoreError;
 use ic_stable_structures::memory_manager::MemoryId;
+use ic_stable_structures::{storable::Bound, Storable};
 
+// Common memory ID constants for all store modules
 pub(crate) const USER_PROFILES_MEM_ID: MemoryId = MemoryId::new(0);
 pub(crate) const PRODUCTS_MEM_ID: MemoryId = MemoryId::new(1);
 pub(crate) const INITIALIZED_MEM_ID: MemoryId = MemoryId::new(2);
 pub(crate) const ORDERS_MEM_ID: MemoryId = MemoryId::new(3);
 pub(crate) const NEXT_ORDER_ID_MEM_ID: MemoryId = MemoryId::new(4);
+pub(crate) const AUDIT_LOG_INDEX_MEM_ID: MemoryId = MemoryId::new(5);
+pub(crate) const AUDIT_LOG_DATA_MEM_ID: MemoryId = MemoryId::new(6);
+pub(crate) const NEXT_TOMBSTONE_ID_MEM_ID: MemoryId = MemoryId::new(7);
+pub(crate) const DELETION_REQUESTS_MEM_ID: MemoryId = MemoryId::new(8);
+pub(crate) const PHONE_SETTINGS_MEM_ID: MemoryId = MemoryId::new(9);
+pub(crate) const PHONE_REDIRECTS_MEM_ID: MemoryId = MemoryId::new(10);
+pub(crate) const PHONE_CHANGE_REQUESTS_MEM_ID: MemoryId = MemoryId::new(11);
+pub(crate) const OTP_CHALLENGES_MEM_ID: MemoryId = MemoryId::new(12);
+pub(crate) const VERIFICATION_SETTINGS_MEM_ID: MemoryId = MemoryId::new(13);
+pub(crate) const OUTBOX_MEM_ID: MemoryId = MemoryId::new(14);
+pub(crate) const NEXT_NOTIFICATION_ID_MEM_ID: MemoryId = MemoryId::new(15);
+pub(crate) const NOTIFICATION_SETTINGS_MEM_ID: MemoryId = MemoryId::new(16);
+pub(crate) const API_KEYS_MEM_ID: MemoryId = MemoryId::new(17);
+pub(crate) const NEXT_API_KEY_ID_MEM_ID: MemoryId = MemoryId::new(18);
+pub(crate) const JOBS_MEM_ID: MemoryId = MemoryId::new(19);
+pub(crate) const NEXT_JOB_ID_MEM_ID: MemoryId = MemoryId::new(20);
+pub(crate) const DAILY_SUMMARIES_MEM_ID: MemoryId = MemoryId::new(21);
+pub(crate) const CYCLES_MONITOR_SETTINGS_MEM_ID: MemoryId = MemoryId::new(22);
+pub(crate) const CYCLES_SAMPLES_MEM_ID: MemoryId = MemoryId::new(23);
+pub(crate) const MONITORED_CANISTERS_MEM_ID: MemoryId = MemoryId::new(24);
+pub(crate) const TOP_UP_SETTINGS_MEM_ID: MemoryId = MemoryId::new(25);
+pub(crate) const TOP_UPS_MEM_ID: MemoryId = MemoryId::new(26);
+pub(crate) const PRODUCT_MAP_MEM_ID: MemoryId = MemoryId::new(27);
+pub(crate) const CARTS_MEM_ID: MemoryId = MemoryId::new(28);
+pub(crate) const COUPONS_MEM_ID: MemoryId = MemoryId::new(29);
+pub(crate) const PRICE_LISTS_MEM_ID: MemoryId = MemoryId::new(30);
+pub(crate) const PRICE_CHANGES_MEM_ID: MemoryId = MemoryId::new(31);
+pub(crate) const CATEGORIES_MEM_ID: MemoryId = MemoryId::new(32);
+pub(crate) const SEARCH_INDEX_MEM_ID: MemoryId = MemoryId::new(33);
+pub(crate) const IMAGES_MEM_ID: MemoryId = MemoryId::new(34);
+pub(crate) const IMAGE_CONTENT_MEM_ID: MemoryId = MemoryId::new(35);
+pub(crate) const ORDER_EDITS_MEM_ID: MemoryId = MemoryId::new(36);
+pub(crate) const OTP_LOCKOUTS_MEM_ID: MemoryId = MemoryId::new(37);
+
+/// Fails if `value` is larger than the bound of the stable map it is stored in, where
+/// inserting it would trap.
+pub fn check_bounded_size<T: Storable>(value: &T) -> Result<(), StoreError> {
+    match T::BOUND {
+        Bound::Bounded { max_size, .. } => {
+            let size = value.to_bytes().len() as u64;
+            if size > max_size as u64 {
+                return Err(StoreError::TooLarge {
+                    size,
+                    max: max_size as u64,
+                });
+            }
+            Ok(())
+        }
+        Bound::Unbounded => Ok(()),
+    }
+}
 
 // Size constants
-pub(crate) const MAX_PRODUCT_SIZE: u32 = 512;
-pub(crate) const MAX_USER_PROFILE_SIZE: u32 = 1024;
+pub(crate) const LEGACY_PRODUCT_SIZE: u32 = 512;
+pub(crate) const MAX_PRODUCT_SIZE: u32 = 4096;
+pub(crate) const MAX_USER_PROFILE_SIZE: u32 = 8192;
 pub(crate) const MAX_PHONE_NUMBER_SIZE: u32 = 30;
 pub(crate) const MAX_PRINCIPAL_SIZE: u32 = 38;
 pub(crate) const MAX_ORDER_SIZE: u32 = 4096;
+pub(crate) const MAX_DELETION_REQUEST_SIZE: u32 = 128;
+pub(crate) const MAX_PHONE_CHANGE_REQUEST_SIZE: u32 = 192;
+pub(crate) const MAX_OTP_CHALLENGE_SIZE: u32 = 512;
+pub(crate) const MAX_OTP_LOCKOUT_SIZE: u32 = 128;
+pub(crate) const MAX_CART_SIZE: u32 = 4096;
+pub(crate) const MAX_SEARCH_TOKEN_SIZE: u32 = 48;
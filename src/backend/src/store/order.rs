@@ -1,8 +1,9 @@
 use candid::{Decode, Encode};
 use ic_stable_structures::{storable::Bound, StableBTreeMap, StableCell, Storable};
-use std::{borrow::Cow, cell::RefCell};
+use std::{borrow::Cow, cell::RefCell, collections::BTreeSet};
 
 use super::memory::{Memory, MEMORY_MANAGER};
+use crate::errors::StoreError;
 use crate::models::{Order, OrderStatus};
 use crate::store::user::get_user_profile;
 
@@ -51,8 +52,24 @@ pub fn get_next_order_id() -> u64 {
     })
 }
 
+/// Returns the next order ID without incrementing the counter.
+pub fn peek_next_order_id() -> u64 {
+    NEXT_ORDER_ID.with(|cell| *cell.borrow().get())
+}
+
+/// Sets the order ID counter, e.g. when restoring a snapshot.
+pub fn set_next_order_id(next_id: u64) -> Result<(), String> {
+    NEXT_ORDER_ID.with(|cell| {
+        cell.borrow_mut()
+            .set(next_id)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to set order ID counter: {:?}", e))
+    })
+}
+
 /// Adds a new order to the store. Assumes order.id is already set correctly.
 pub fn add_order(order: Order) -> Result<(), String> {
+    super::check_bounded_size(&order).map_err(|e| e.to_string())?;
     ORDERS.with(|orders| -> Result<(), String> {
         let key = order.id;
         // Insert the order and ignore previous value if it existed
@@ -83,8 +100,8 @@ pub fn update_order_status(
     order_id: u64,
     status: OrderStatus,
     timestamp: u64,
-) -> Result<Order, String> {
-    ORDERS.with(|orders| -> Result<Order, String> {
+) -> Result<Order, StoreError> {
+    ORDERS.with(|orders| -> Result<Order, StoreError> {
         let mut order_map = orders.borrow_mut();
         match order_map.remove(&order_id) {
             Some(mut order) => {
@@ -96,10 +113,64 @@ pub fn update_order_status(
                 order_map.insert(order_id, order);
                 Ok(updated_order)
             }
-            None => Err(format!(
-                "Order with ID {} not found for status update.",
-                order_id
-            )),
+            None => Err(StoreError::NotFound(order_id)),
+        }
+    })
+}
+
+/// Replaces an existing order. Fails if the order has grown beyond its size bound.
+pub fn update_order(order: Order) -> Result<Order, StoreError> {
+    super::check_bounded_size(&order)?;
+    ORDERS.with(|orders| {
+        let mut order_map = orders.borrow_mut();
+        if !order_map.contains_key(&order.id) {
+            return Err(StoreError::NotFound(order.id));
+        }
+        order_map.insert(order.id, order.clone());
+        Ok(order)
+    })
+}
+
+/// Retrieves the distinct phone numbers that orders are linked to.
+pub fn get_order_phone_numbers() -> Vec<String> {
+    ORDERS.with(|orders_map| {
+        let phone_numbers: BTreeSet<String> = orders_map
+            .borrow()
+            .iter()
+            .map(|(_, order)| order.user_phone_number)
+            .collect();
+        phone_numbers.into_iter().collect()
+    })
+}
+
+/// Applies `update` to every order placed by a phone number. Returns the IDs of the updated orders.
+pub fn update_orders_by_phone(
+    phone_number: &String,
+    mut update: impl FnMut(&mut Order),
+) -> Vec<u64> {
+    ORDERS.with(|orders| {
+        let mut order_map = orders.borrow_mut();
+        let order_ids: Vec<u64> = order_map
+            .iter()
+            .filter(|(_, order)| order.user_phone_number == *phone_number)
+            .map(|(id, _)| id)
+            .collect();
+
+        for order_id in &order_ids {
+            if let Some(mut order) = order_map.get(order_id) {
+                update(&mut order);
+                order_map.insert(*order_id, order);
+            }
+        }
+        order_ids
+    })
+}
+
+/// Calls `visit` with every order exactly as stored, ordered by ID.
+pub fn iter_orders(mut visit: impl FnMut(Order)) {
+    ORDERS.with(|orders| {
+        for (_, order) in orders.borrow().iter() {
+            visit(order);
         }
     })
 }
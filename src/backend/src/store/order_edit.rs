@@ -0,0 +1,75 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, MEMORY_MANAGER};
+use crate::models::OrderEdit;
+
+// Implement Storable for OrderEdit using Candid encoding
+impl Storable for OrderEdit {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode OrderEdit"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode OrderEdit")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Thread-local order edit storage
+thread_local! {
+    // Order edits: Map (Order ID, Sequence number) -> OrderEdit, i.e. oldest first per order.
+    // Kept apart from the orders so that the history doesn't count towards their size bound.
+    static ORDER_EDITS: RefCell<StableBTreeMap<(u64, u64), OrderEdit, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::ORDER_EDITS_MEM_ID))
+        )
+    );
+}
+
+/// Appends an edit to the history of its order.
+pub fn add_order_edit(edit: OrderEdit) {
+    ORDER_EDITS.with(|edits| {
+        let mut edits = edits.borrow_mut();
+        let seq = edits
+            .range((edit.order_id, 0)..=(edit.order_id, u64::MAX))
+            .last()
+            .map_or(0, |((_, seq), _)| seq + 1);
+        edits.insert((edit.order_id, seq), edit);
+    })
+}
+
+/// The edits of an order, oldest first.
+pub fn get_order_edits(order_id: u64) -> Vec<OrderEdit> {
+    ORDER_EDITS.with(|edits| {
+        edits
+            .borrow()
+            .range((order_id, 0)..=(order_id, u64::MAX))
+            .map(|(_, edit)| edit)
+            .collect()
+    })
+}
+
+/// Applies `update` to every edit of an order.
+pub fn update_order_edits(order_id: u64, mut update: impl FnMut(&mut OrderEdit)) {
+    ORDER_EDITS.with(|edits| {
+        let mut edits = edits.borrow_mut();
+        let keys: Vec<(u64, u64)> = edits
+            .range((order_id, 0)..=(order_id, u64::MAX))
+            .map(|(key, _)| key)
+            .collect();
+        for key in keys {
+            if let Some(mut edit) = edits.get(&key) {
+                update(&mut edit);
+                edits.insert(key, edit);
+            }
+        }
+    })
+}
+
+/// All order edits, ordered by order and time.
+pub fn get_all_order_edits() -> Vec<OrderEdit> {
+    ORDER_EDITS.with(|edits| edits.borrow().iter().map(|(_, edit)| edit).collect())
+}
@@ -0,0 +1,129 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, StorableString, MEMORY_MANAGER};
+use crate::models::{OtpChallenge, OtpLockout};
+
+// Implement Storable for OtpChallenge using Candid encoding
+impl Storable for OtpChallenge {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode OtpChallenge"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode OtpChallenge")
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: super::MAX_OTP_CHALLENGE_SIZE,
+        is_fixed_size: false,
+    };
+}
+
+// Implement Storable for OtpLockout using Candid encoding
+impl Storable for OtpLockout {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode OtpLockout"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode OtpLockout")
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: super::MAX_OTP_LOCKOUT_SIZE,
+        is_fixed_size: false,
+    };
+}
+
+// Thread-local OTP storage
+thread_local! {
+    // Outstanding verification codes: Map Phone Number -> OtpChallenge (at most one per number)
+    static OTP_CHALLENGES: RefCell<StableBTreeMap<StorableString, OtpChallenge, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::OTP_CHALLENGES_MEM_ID))
+        )
+    );
+
+    // Numbers that used up the attempts of a challenge: Map Phone Number -> OtpLockout
+    static OTP_LOCKOUTS: RefCell<StableBTreeMap<StorableString, OtpLockout, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::OTP_LOCKOUTS_MEM_ID))
+        )
+    );
+}
+
+/// Retrieves the outstanding challenge for a phone number.
+pub fn get_otp_challenge(phone_number: &str) -> Option<OtpChallenge> {
+    let key = StorableString(phone_number.to_string());
+    OTP_CHALLENGES.with(|challenges| challenges.borrow().get(&key))
+}
+
+/// Retrieves all outstanding challenges.
+pub fn get_all_otp_challenges() -> Vec<OtpChallenge> {
+    OTP_CHALLENGES.with(|challenges| {
+        challenges
+            .borrow()
+            .iter()
+            .map(|(_, challenge)| challenge)
+            .collect()
+    })
+}
+
+/// Inserts or replaces the challenge for the phone number within it.
+pub fn put_otp_challenge(challenge: OtpChallenge) {
+    let key = StorableString(challenge.phone_number.clone());
+    OTP_CHALLENGES.with(|challenges| challenges.borrow_mut().insert(key, challenge));
+}
+
+/// Removes the challenge for a phone number, if any.
+pub fn remove_otp_challenge(phone_number: &str) -> Option<OtpChallenge> {
+    let key = StorableString(phone_number.to_string());
+    OTP_CHALLENGES.with(|challenges| challenges.borrow_mut().remove(&key))
+}
+
+/// Removes all challenges that expired before `now`. Returns how many were removed.
+pub fn remove_expired_otp_challenges(now: u64) -> u64 {
+    OTP_CHALLENGES.with(|challenges| {
+        let mut challenges = challenges.borrow_mut();
+        let expired: Vec<StorableString> = challenges
+            .iter()
+            .filter(|(_, challenge)| challenge.expires_at <= now)
+            .map(|(key, _)| key)
+            .collect();
+        for key in &expired {
+            challenges.remove(key);
+        }
+        expired.len() as u64
+    })
+}
+
+/// Retrieves the lockout record of a phone number.
+pub fn get_otp_lockout(phone_number: &str) -> Option<OtpLockout> {
+    let key = StorableString(phone_number.to_string());
+    OTP_LOCKOUTS.with(|lockouts| lockouts.borrow().get(&key))
+}
+
+/// Retrieves all lockout records.
+pub fn get_all_otp_lockouts() -> Vec<OtpLockout> {
+    OTP_LOCKOUTS.with(|lockouts| {
+        lockouts
+            .borrow()
+            .iter()
+            .map(|(_, lockout)| lockout)
+            .collect()
+    })
+}
+
+/// Inserts or replaces the lockout record for the phone number within it.
+pub fn put_otp_lockout(lockout: OtpLockout) {
+    let key = StorableString(lockout.phone_number.clone());
+    OTP_LOCKOUTS.with(|lockouts| lockouts.borrow_mut().insert(key, lockout));
+}
+
+/// Removes the lockout record of a phone number, if any.
+pub fn remove_otp_lockout(phone_number: &str) -> Option<OtpLockout> {
+    let key = StorableString(phone_number.to_string());
+    OTP_LOCKOUTS.with(|lockouts| lockouts.borrow_mut().remove(&key))
+}
@@ -0,0 +1,143 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, StableCell, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, MEMORY_MANAGER};
+use crate::models::{Notification, NotificationState};
+
+// Implement Storable for Notification using Candid encoding
+impl Storable for Notification {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode Notification"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode Notification")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Thread-local notification outbox storage
+thread_local! {
+    static NEXT_NOTIFICATION_ID: RefCell<StableCell<u64, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::NEXT_NOTIFICATION_ID_MEM_ID)),
+            0
+        ).expect("Failed to init StableCell for NEXT_NOTIFICATION_ID")
+    );
+
+    // Outbox: Map Notification ID (u64) -> Notification
+    static OUTBOX: RefCell<StableBTreeMap<u64, Notification, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::OUTBOX_MEM_ID))
+        )
+    );
+}
+
+/// Gets the next available notification ID and increments the counter.
+pub fn get_next_notification_id() -> u64 {
+    NEXT_NOTIFICATION_ID.with(|cell| {
+        let current_id = *cell.borrow().get();
+        cell.borrow_mut()
+            .set(current_id + 1)
+            .expect("Failed to increment notification ID counter");
+        current_id
+    })
+}
+
+/// Returns the next notification ID without incrementing the counter.
+pub fn peek_next_notification_id() -> u64 {
+    NEXT_NOTIFICATION_ID.with(|cell| *cell.borrow().get())
+}
+
+/// Sets the notification ID counter, e.g. when restoring a snapshot.
+pub fn set_next_notification_id(next_id: u64) -> Result<(), String> {
+    NEXT_NOTIFICATION_ID.with(|cell| {
+        cell.borrow_mut()
+            .set(next_id)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to set notification ID counter: {:?}", e))
+    })
+}
+
+/// Inserts or updates a notification in the outbox.
+pub fn put_notification(notification: Notification) {
+    OUTBOX.with(|outbox| outbox.borrow_mut().insert(notification.id, notification));
+}
+
+/// Retrieves every notification in the outbox, delivered ones included.
+pub fn get_all_notifications() -> Vec<Notification> {
+    OUTBOX.with(|outbox| outbox.borrow().iter().map(|(_, n)| n).collect())
+}
+
+/// Retrieves a notification by its ID.
+pub fn get_notification(id: u64) -> Option<Notification> {
+    OUTBOX.with(|outbox| outbox.borrow().get(&id))
+}
+
+/// Retrieves up to `limit` pending notifications that are due at `now`, oldest first.
+pub fn get_due_notifications(now: u64, limit: usize) -> Vec<Notification> {
+    OUTBOX.with(|outbox| {
+        outbox
+            .borrow()
+            .iter()
+            .map(|(_, notification)| notification)
+            .filter(|n| n.state == NotificationState::Pending && n.next_attempt_at <= now)
+            .take(limit)
+            .collect()
+    })
+}
+
+/// Returns the earliest time a pending notification is due, if any.
+pub fn next_notification_due_at() -> Option<u64> {
+    OUTBOX.with(|outbox| {
+        outbox
+            .borrow()
+            .iter()
+            .filter(|(_, n)| n.state == NotificationState::Pending)
+            .map(|(_, n)| n.next_attempt_at)
+            .min()
+    })
+}
+
+/// Retrieves notifications that were given up on, plus pending ones that failed at least once.
+pub fn get_failed_notifications() -> Vec<Notification> {
+    OUTBOX.with(|outbox| {
+        outbox
+            .borrow()
+            .iter()
+            .map(|(_, notification)| notification)
+            .filter(|n| n.state == NotificationState::Failed || n.last_error.is_some())
+            .filter(|n| !matches!(n.state, NotificationState::Delivered { .. }))
+            .collect()
+    })
+}
+
+/// Applies `update` to every notification sent to a phone number. Returns the IDs of the
+/// updated notifications.
+pub fn update_notifications_by_phone(
+    phone_number: &String,
+    mut update: impl FnMut(&mut Notification),
+) -> Vec<u64> {
+    OUTBOX.with(|outbox| {
+        let mut outbox = outbox.borrow_mut();
+        let ids: Vec<u64> = outbox
+            .iter()
+            .filter(|(_, n)| n.phone_number == *phone_number)
+            .map(|(id, _)| id)
+            .collect();
+        for id in &ids {
+            if let Some(mut notification) = outbox.get(id) {
+                update(&mut notification);
+                outbox.insert(*id, notification);
+            }
+        }
+        ids
+    })
+}
+
+/// Removes a notification from the outbox.
+pub fn remove_notification(id: u64) -> Option<Notification> {
+    OUTBOX.with(|outbox| outbox.borrow_mut().remove(&id))
+}
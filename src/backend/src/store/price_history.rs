@@ -0,0 +1,67 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, MEMORY_MANAGER};
+use crate::models::PriceChange;
+
+// Implement Storable for PriceChange using Candid encoding
+impl Storable for PriceChange {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode PriceChange"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode PriceChange")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Thread-local price history storage
+thread_local! {
+    // Price changes: Map (Product ID, Effective From) -> PriceChange, i.e. ordered by time per product
+    static PRICE_CHANGES: RefCell<StableBTreeMap<(u64, u64), PriceChange, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::PRICE_CHANGES_MEM_ID))
+        )
+    );
+}
+
+/// Inserts a price change, replacing one for the same product and time.
+pub fn put_price_change(change: PriceChange) {
+    let key = (change.product_id, change.effective_from);
+    PRICE_CHANGES.with(|changes| changes.borrow_mut().insert(key, change));
+}
+
+/// Removes the price change of a product at `effective_from`, returning it if it existed.
+pub fn remove_price_change(product_id: u64, effective_from: u64) -> Option<PriceChange> {
+    PRICE_CHANGES.with(|changes| changes.borrow_mut().remove(&(product_id, effective_from)))
+}
+
+/// The price changes of a product, oldest first, including scheduled ones.
+pub fn get_price_changes(product_id: u64) -> Vec<PriceChange> {
+    PRICE_CHANGES.with(|changes| {
+        changes
+            .borrow()
+            .range((product_id, 0)..=(product_id, u64::MAX))
+            .map(|(_, change)| change)
+            .collect()
+    })
+}
+
+/// The latest price change of a product that is effective at `at`.
+pub fn get_price_change_at(product_id: u64, at: u64) -> Option<PriceChange> {
+    PRICE_CHANGES.with(|changes| {
+        changes
+            .borrow()
+            .range((product_id, 0)..=(product_id, at))
+            .last()
+            .map(|(_, change)| change)
+    })
+}
+
+/// All price changes, ordered by product and time.
+pub fn get_all_price_changes() -> Vec<PriceChange> {
+    PRICE_CHANGES.with(|changes| changes.borrow().iter().map(|(_, change)| change).collect())
+}
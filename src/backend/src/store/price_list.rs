@@ -0,0 +1,54 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, MEMORY_MANAGER};
+use crate::models::PriceList;
+
+// Implement Storable for PriceList using Candid encoding
+impl Storable for PriceList {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode PriceList"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode PriceList")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Thread-local price list storage
+thread_local! {
+    // Price lists: Map Price List ID -> PriceList
+    static PRICE_LISTS: RefCell<StableBTreeMap<u64, PriceList, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::PRICE_LISTS_MEM_ID))
+        )
+    );
+}
+
+/// The ID the next new price list gets.
+pub fn next_price_list_id() -> u64 {
+    PRICE_LISTS.with(|lists| lists.borrow().last_key_value().map_or(0, |(id, _)| id + 1))
+}
+
+/// Retrieves a price list by its ID.
+pub fn get_price_list(id: u64) -> Option<PriceList> {
+    PRICE_LISTS.with(|lists| lists.borrow().get(&id))
+}
+
+/// Retrieves all price lists, ordered by ID.
+pub fn get_all_price_lists() -> Vec<PriceList> {
+    PRICE_LISTS.with(|lists| lists.borrow().iter().map(|(_, list)| list).collect())
+}
+
+/// Inserts or replaces the price list under its ID.
+pub fn put_price_list(list: PriceList) {
+    PRICE_LISTS.with(|lists| lists.borrow_mut().insert(list.id, list));
+}
+
+/// Removes a price list, returning it if it existed.
+pub fn remove_price_list(id: u64) -> Option<PriceList> {
+    PRICE_LISTS.with(|lists| lists.borrow_mut().remove(&id))
+}
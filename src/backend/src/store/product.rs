@@ -1,9 +1,9 @@
 use candid::{Decode, Encode};
-use ic_stable_structures::{storable::Bound, StableVec, Storable};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, StableVec, Storable};
 use std::{borrow::Cow, cell::RefCell};
 
 use super::memory::{Memory, MEMORY_MANAGER};
-use crate::models::Product;
+use crate::{certified, errors::StoreError, models::Product};
 
 // Implement Storable for Product using Candid encoding
 impl Storable for Product {
@@ -15,64 +15,126 @@ impl Storable for Product {
         Decode!(bytes.as_ref(), Self).expect("Failed to decode Product")
     }
 
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Products as they were stored before translations: a StableVec needs a size bound, which
+// translated names and descriptions don't fit in. Only read to migrate them.
+struct LegacyProduct(Product);
+
+impl Storable for LegacyProduct {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(&self.0).expect("Failed to encode Product"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        LegacyProduct(Decode!(bytes.as_ref(), Product).expect("Failed to decode Product"))
+    }
+
     const BOUND: Bound = Bound::Bounded {
-        max_size: super::MAX_PRODUCT_SIZE,
+        max_size: super::LEGACY_PRODUCT_SIZE,
         is_fixed_size: false,
     };
 }
 
 // Thread-local product storage
 thread_local! {
-    static PRODUCTS: RefCell<StableVec<Product, Memory>> = RefCell::new(
+    static LEGACY_PRODUCTS: RefCell<StableVec<LegacyProduct, Memory>> = RefCell::new(
         StableVec::init(
             MEMORY_MANAGER.with(|m| m.borrow().get(super::PRODUCTS_MEM_ID))
         ).expect("Failed to init StableVec for Products")
     );
+
+    // Products: Map Product ID -> Product
+    static PRODUCTS: RefCell<StableBTreeMap<u64, Product, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::PRODUCT_MAP_MEM_ID))
+        )
+    );
 }
 
-/// Adds a new product to the store. Returns the new product's ID (index).
-pub fn add_product(product: Product) -> Result<u64, String> {
+/// Moves products from the old StableVec into the map, keyed by their index (products
+/// added before IDs were assigned on insert carry id 0). Returns the number moved.
+pub fn migrate_legacy_products() -> u64 {
+    let legacy: Vec<Product> = LEGACY_PRODUCTS.with(|v| {
+        let v = v.borrow_mut();
+        let products = v.iter().map(|LegacyProduct(product)| product).collect();
+        while v.pop().is_some() {}
+        products
+    });
     PRODUCTS.with(|p| {
-        let products = p.borrow_mut();
-        // The ID will be the index where it's inserted
-        let len_before_push = products.len();
-        match products.push(&product) {
-            Ok(_) => Ok(len_before_push),
-            Err(e) => Err(format!("Failed to add product: {:?}", e)),
+        let mut products = p.borrow_mut();
+        for (index, product) in legacy.iter().enumerate() {
+            let id = index as u64;
+            products.insert(
+                id,
+                Product {
+                    id,
+                    ..product.clone()
+                },
+            );
         }
+    });
+    legacy.len() as u64
+}
+
+/// Fails if a product is larger than products may be.
+pub fn check_product_size(product: &Product) -> Result<(), StoreError> {
+    let size = Encode!(product)
+        .map(|bytes| bytes.len() as u64)
+        .unwrap_or(u64::MAX);
+    if size > super::MAX_PRODUCT_SIZE as u64 {
+        return Err(StoreError::TooLarge {
+            size,
+            max: super::MAX_PRODUCT_SIZE as u64,
+        });
+    }
+    Ok(())
+}
+
+/// Adds a new product to the store, indexed for search and certified. Returns the new
+/// product's ID.
+pub fn add_product(product: Product) -> Result<u64, StoreError> {
+    check_product_size(&product)?;
+    PRODUCTS.with(|p| {
+        let mut products = p.borrow_mut();
+        // Products are never removed, so IDs are sequential
+        let id = products.last_key_value().map_or(0, |(id, _)| id + 1);
+        let product = Product { id, ..product };
+        super::search_index::index_product(None, &product);
+        certified::certify_product(&product);
+        products.insert(id, product);
+        Ok(id)
     })
 }
 
-/// Retrieves all products from the store.
+/// Retrieves all products from the store, ordered by ID.
 pub fn get_all_products() -> Vec<Product> {
-    PRODUCTS.with(|p| p.borrow().iter().collect())
+    PRODUCTS.with(|p| p.borrow().iter().map(|(_, product)| product).collect())
 }
 
-/// Retrieves a product by its ID (index).
+/// Retrieves a product by its ID.
 pub fn get_product_by_id(id: u64) -> Option<Product> {
-    PRODUCTS.with(|p| p.borrow().get(id))
+    PRODUCTS.with(|p| p.borrow().get(&id))
 }
 
-/// Updates an existing product. Returns the updated product or an error if not found.
-pub fn update_product(id: u64, updated_product: Product) -> Result<Product, String> {
+/// Updates an existing product, re-indexing and re-certifying it. Returns the updated product
+/// or an error if not found.
+pub fn update_product(id: u64, updated_product: Product) -> Result<Product, StoreError> {
+    let product = Product {
+        id, // Keep the original ID
+        ..updated_product
+    };
+    check_product_size(&product)?;
     PRODUCTS.with(|p| {
-        let products = p.borrow_mut();
-        if id >= products.len() {
-            return Err(format!("Product with ID {} not found", id));
-        }
-
-        // Create a new product with the same ID but updated fields
-        let product_to_update = Product {
-            id, // Keep the original ID
-            name: updated_product.name,
-            description: updated_product.description,
-            price: updated_product.price,
-            unit: updated_product.unit,
+        let mut products = p.borrow_mut();
+        let Some(previous) = products.get(&id) else {
+            return Err(StoreError::NotFound(id));
         };
-
-        // set method returns () on success, not a Result
-        products.set(id, &product_to_update);
-        Ok(product_to_update)
+        super::search_index::index_product(Some(&previous), &product);
+        certified::certify_product(&product);
+        products.insert(id, product.clone());
+        Ok(product)
     })
 }
 
@@ -0,0 +1,129 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, StorableString, MEMORY_MANAGER};
+use crate::models::PhoneChangeRequest;
+
+// Implement Storable for PhoneChangeRequest using Candid encoding
+impl Storable for PhoneChangeRequest {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode PhoneChangeRequest"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode PhoneChangeRequest")
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: super::MAX_PHONE_CHANGE_REQUEST_SIZE,
+        is_fixed_size: false,
+    };
+}
+
+// Thread-local phone number change storage
+thread_local! {
+    // Redirects left behind by phone number changes: Map Old Phone Number -> Current Phone Number
+    static PHONE_REDIRECTS: RefCell<StableBTreeMap<StorableString, StorableString, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::PHONE_REDIRECTS_MEM_ID))
+        )
+    );
+
+    // Customer-initiated change requests: Map Current Phone Number -> PhoneChangeRequest
+    static PHONE_CHANGE_REQUESTS: RefCell<StableBTreeMap<StorableString, PhoneChangeRequest, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::PHONE_CHANGE_REQUESTS_MEM_ID))
+        )
+    );
+}
+
+/// Returns the phone number an old number now redirects to, if any.
+pub fn get_phone_redirect(phone_number: &str) -> Option<String> {
+    let key = StorableString(phone_number.to_string());
+    PHONE_REDIRECTS.with(|redirects| redirects.borrow().get(&key).map(|target| target.0))
+}
+
+/// Returns all redirects as `(old phone number, new phone number)` pairs.
+pub fn get_all_phone_redirects() -> Vec<(String, String)> {
+    PHONE_REDIRECTS.with(|redirects| {
+        redirects
+            .borrow()
+            .iter()
+            .map(|(source, target)| (source.0, target.0))
+            .collect()
+    })
+}
+
+/// Points `old_phone_number` at `new_phone_number`. Existing redirects that pointed at the
+/// old number are updated as well, so that lookups never need to follow a chain.
+pub fn set_phone_redirect(old_phone_number: &str, new_phone_number: &str) {
+    PHONE_REDIRECTS.with(|redirects| {
+        let mut redirects = redirects.borrow_mut();
+        let chained: Vec<StorableString> = redirects
+            .iter()
+            .filter(|(_, target)| target.0 == *old_phone_number)
+            .map(|(source, _)| source)
+            .collect();
+        for source in chained {
+            redirects.insert(source, StorableString(new_phone_number.to_string()));
+        }
+        redirects.insert(
+            StorableString(old_phone_number.to_string()),
+            StorableString(new_phone_number.to_string()),
+        );
+        // The new number belongs to an active profile now
+        redirects.remove(&StorableString(new_phone_number.to_string()));
+    })
+}
+
+/// Removes the redirect for a phone number, e.g. when the number is reassigned to a new customer.
+pub fn remove_phone_redirect(phone_number: &str) -> Option<String> {
+    let key = StorableString(phone_number.to_string());
+    PHONE_REDIRECTS.with(|redirects| redirects.borrow_mut().remove(&key).map(|target| target.0))
+}
+
+/// Removes every redirect that points at a phone number. Returns the old numbers.
+pub fn remove_phone_redirects_to(phone_number: &str) -> Vec<String> {
+    PHONE_REDIRECTS.with(|redirects| {
+        let mut redirects = redirects.borrow_mut();
+        let sources: Vec<StorableString> = redirects
+            .iter()
+            .filter(|(_, target)| target.0 == *phone_number)
+            .map(|(source, _)| source)
+            .collect();
+        for source in &sources {
+            redirects.remove(source);
+        }
+        sources.into_iter().map(|source| source.0).collect()
+    })
+}
+
+/// Stores a customer's phone change request, replacing any previous one.
+pub fn add_phone_change_request(request: PhoneChangeRequest) {
+    let key = StorableString(request.current_phone_number.clone());
+    PHONE_CHANGE_REQUESTS.with(|requests| requests.borrow_mut().insert(key, request));
+}
+
+/// Retrieves the pending phone change request for a phone number.
+pub fn get_phone_change_request(current_phone_number: &str) -> Option<PhoneChangeRequest> {
+    let key = StorableString(current_phone_number.to_string());
+    PHONE_CHANGE_REQUESTS.with(|requests| requests.borrow().get(&key))
+}
+
+/// Retrieves all pending phone change requests.
+pub fn get_phone_change_requests() -> Vec<PhoneChangeRequest> {
+    PHONE_CHANGE_REQUESTS.with(|requests| {
+        requests
+            .borrow()
+            .iter()
+            .map(|(_, request)| request)
+            .collect()
+    })
+}
+
+/// Removes the pending phone change request for a phone number, if any.
+pub fn remove_phone_change_request(current_phone_number: &str) -> Option<PhoneChangeRequest> {
+    let key = StorableString(current_phone_number.to_string());
+    PHONE_CHANGE_REQUESTS.with(|requests| requests.borrow_mut().remove(&key))
+}
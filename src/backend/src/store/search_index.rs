@@ -0,0 +1,115 @@
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use std::{borrow::Cow, cell::RefCell, collections::BTreeSet};
+
+use super::memory::{Memory, MEMORY_MANAGER};
+use crate::models::Product;
+
+// A lowercase word of a product name, or the end of one, cut to the key size.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct SearchToken(String);
+
+impl Storable for SearchToken {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Borrowed(self.0.as_bytes())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        SearchToken(String::from_utf8(bytes.into_owned()).expect("Failed to decode SearchToken"))
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: super::MAX_SEARCH_TOKEN_SIZE,
+        is_fixed_size: false,
+    };
+}
+
+// Thread-local search index
+thread_local! {
+    // Inverted index over product names: (Token, Product ID) -> (). Every suffix of every word
+    // is a token, so a prefix scan finds words that start with or contain the search text.
+    static SEARCH_INDEX: RefCell<StableBTreeMap<(SearchToken, u64), (), Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::SEARCH_INDEX_MEM_ID))
+        )
+    );
+}
+
+/// Splits text into lowercase words. Only whitespace and ASCII punctuation separate words,
+/// so Devanagari vowel signs stay part of their word.
+pub fn search_words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+        .filter(|word| !word.is_empty())
+        .map(truncate)
+        .collect()
+}
+
+// Cuts a word to the token size at a character boundary
+fn truncate(word: &str) -> String {
+    let max = super::MAX_SEARCH_TOKEN_SIZE as usize;
+    let mut end = word.len().min(max);
+    while !word.is_char_boundary(end) {
+        end -= 1;
+    }
+    word[..end].to_string()
+}
+
+// The tokens of a product: the suffixes of the words of its names in all languages
+fn product_tokens(product: &Product) -> BTreeSet<String> {
+    let names = std::iter::once(&product.name).chain(
+        product
+            .translations
+            .iter()
+            .flatten()
+            .map(|translation| &translation.name),
+    );
+    names
+        .flat_map(|name| search_words(name))
+        .flat_map(|word| {
+            word.char_indices()
+                .map(|(start, _)| word[start..].to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Updates the index for a product that changed from `previous` (None for new products).
+pub fn index_product(previous: Option<&Product>, product: &Product) {
+    SEARCH_INDEX.with(|index| {
+        let mut index = index.borrow_mut();
+        if let Some(previous) = previous {
+            for token in product_tokens(previous) {
+                index.remove(&(SearchToken(token), previous.id));
+            }
+        }
+        for token in product_tokens(product) {
+            index.insert((SearchToken(token), product.id), ());
+        }
+    });
+}
+
+/// IDs of the products with a word that starts with or contains `word`.
+pub fn find_products_by_word(word: &str) -> BTreeSet<u64> {
+    let word = truncate(&word.to_lowercase());
+    SEARCH_INDEX.with(|index| {
+        index
+            .borrow()
+            .range((SearchToken(word.clone()), 0)..)
+            .take_while(|((token, _), _)| token.0.starts_with(&word))
+            .map(|((_, product_id), _)| product_id)
+            .collect()
+    })
+}
+
+/// Indexes every product if the index is empty, e.g. after upgrading from a release
+/// without search. Returns the number of products indexed.
+pub fn ensure_search_index() -> u64 {
+    if SEARCH_INDEX.with(|index| !index.borrow().is_empty()) {
+        return 0;
+    }
+    let products = super::get_all_products();
+    for product in &products {
+        index_product(None, product);
+    }
+    products.len() as u64
+}
@@ -0,0 +1,186 @@
+use candid::{Decode, Encode};
+use ic_stable_structures::{storable::Bound, StableCell, Storable};
+use std::{borrow::Cow, cell::RefCell};
+
+use super::memory::{Memory, MEMORY_MANAGER};
+use crate::models::{
+    CyclesMonitorSettings, NotificationSettings, PhoneSettings, TopUpSettings, VerificationSettings,
+};
+
+// Implement Storable for PhoneSettings using Candid encoding
+impl Storable for PhoneSettings {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode PhoneSettings"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode PhoneSettings")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Implement Storable for VerificationSettings using Candid encoding
+impl Storable for VerificationSettings {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode VerificationSettings"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode VerificationSettings")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Implement Storable for NotificationSettings using Candid encoding
+impl Storable for NotificationSettings {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode NotificationSettings"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode NotificationSettings")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Implement Storable for CyclesMonitorSettings using Candid encoding
+impl Storable for CyclesMonitorSettings {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode CyclesMonitorSettings"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode CyclesMonitorSettings")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Implement Storable for TopUpSettings using Candid encoding
+impl Storable for TopUpSettings {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("Failed to encode TopUpSettings"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("Failed to decode TopUpSettings")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Thread-local settings storage
+thread_local! {
+    static PHONE_SETTINGS: RefCell<StableCell<PhoneSettings, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::PHONE_SETTINGS_MEM_ID)),
+            PhoneSettings::default()
+        ).expect("Failed to init StableCell for PHONE_SETTINGS")
+    );
+
+    static VERIFICATION_SETTINGS: RefCell<StableCell<VerificationSettings, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::VERIFICATION_SETTINGS_MEM_ID)),
+            VerificationSettings::default()
+        ).expect("Failed to init StableCell for VERIFICATION_SETTINGS")
+    );
+
+    static NOTIFICATION_SETTINGS: RefCell<StableCell<NotificationSettings, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::NOTIFICATION_SETTINGS_MEM_ID)),
+            NotificationSettings::default()
+        ).expect("Failed to init StableCell for NOTIFICATION_SETTINGS")
+    );
+
+    static CYCLES_MONITOR_SETTINGS: RefCell<StableCell<CyclesMonitorSettings, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::CYCLES_MONITOR_SETTINGS_MEM_ID)),
+            CyclesMonitorSettings::default()
+        ).expect("Failed to init StableCell for CYCLES_MONITOR_SETTINGS")
+    );
+
+    static TOP_UP_SETTINGS: RefCell<StableCell<TopUpSettings, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(super::TOP_UP_SETTINGS_MEM_ID)),
+            TopUpSettings::default()
+        ).expect("Failed to init StableCell for TOP_UP_SETTINGS")
+    );
+}
+
+/// Retrieves the phone number normalisation settings.
+pub fn get_phone_settings() -> PhoneSettings {
+    PHONE_SETTINGS.with(|cell| cell.borrow().get().clone())
+}
+
+/// Replaces the phone number normalisation settings.
+pub fn set_phone_settings(settings: PhoneSettings) -> Result<(), String> {
+    PHONE_SETTINGS.with(|cell| {
+        cell.borrow_mut()
+            .set(settings)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to set phone settings: {:?}", e))
+    })
+}
+
+/// Retrieves the OTP / SMS gateway settings.
+pub fn get_verification_settings() -> VerificationSettings {
+    VERIFICATION_SETTINGS.with(|cell| cell.borrow().get().clone())
+}
+
+/// Replaces the OTP / SMS gateway settings.
+pub fn set_verification_settings(settings: VerificationSettings) -> Result<(), String> {
+    VERIFICATION_SETTINGS.with(|cell| {
+        cell.borrow_mut()
+            .set(settings)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to set verification settings: {:?}", e))
+    })
+}
+
+/// Retrieves the order notification webhook settings.
+pub fn get_notification_settings() -> NotificationSettings {
+    NOTIFICATION_SETTINGS.with(|cell| cell.borrow().get().clone())
+}
+
+/// Replaces the order notification webhook settings.
+pub fn set_notification_settings(settings: NotificationSettings) -> Result<(), String> {
+    NOTIFICATION_SETTINGS.with(|cell| {
+        cell.borrow_mut()
+            .set(settings)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to set notification settings: {:?}", e))
+    })
+}
+
+/// Retrieves the cycles monitoring and alert settings.
+pub fn get_cycles_monitor_settings() -> CyclesMonitorSettings {
+    CYCLES_MONITOR_SETTINGS.with(|cell| cell.borrow().get().clone())
+}
+
+/// Replaces the cycles monitoring and alert settings.
+pub fn set_cycles_monitor_settings(settings: CyclesMonitorSettings) -> Result<(), String> {
+    CYCLES_MONITOR_SETTINGS.with(|cell| {
+        cell.borrow_mut()
+            .set(settings)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to set cycles monitor settings: {:?}", e))
+    })
+}
+
+/// Retrieves the ICP top-up settings.
+pub fn get_top_up_settings() -> TopUpSettings {
+    TOP_UP_SETTINGS.with(|cell| cell.borrow().get().clone())
+}
+
+/// Replaces the ICP top-up settings.
+pub fn set_top_up_settings(settings: TopUpSettings) -> Result<(), String> {
+    TOP_UP_SETTINGS.with(|cell| {
+        cell.borrow_mut()
+            .set(settings)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to set top-up settings: {:?}", e))
+    })
+}
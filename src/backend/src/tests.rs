@@ -1,6 +1,2032 @@
 #[cfg(test)]
 mod tests {
+    use crate::{
+        add_address, address, anonymise_customer, audit, bulk, cart, catalog, certified,
+        change_customer_phone_number, csv, cycles, date, edit_order,
+        errors::PhoneNumberError,
+        errors::{
+            AddressError, CartError, CategoryError, CouponError, CyclesError, DataDeletionError,
+            ErrorCode, FieldErrorKind, ImageError, JobError, OrderError, OtpError,
+            PhoneChangeError, PriceListError, ProductError, QuantityError, StoreError, TopUpError,
+        },
+        finish_snapshot_import, get_my_orders, hex, i18n, images, jobs,
+        models::{
+            AddressInput, AuditOutcome, Cart, CartItem, Category, Coupon, CouponScope, CouponTerms,
+            CyclesSample, DeletionRequest, Discount, GeoPoint, ImportRowStatus, Job, JobKind,
+            JobSchedule, MonitoredCanister, Notification, NotificationChannel,
+            NotificationSettings, NotificationState, Order, OrderDiscount, OrderEditor, OrderItem,
+            OrderStatus, OtpChallenge, OtpLockout, OtpPurpose, PhoneChangeRequest, PhoneSettings,
+            PriceList, PriceListKind, PriceTier, Product, ProductPrice, ProductSearch, ProductSort,
+            ProductTranslation, ProductVariant, SnapshotChunk, SnapshotManifest, TopUp,
+            TopUpSettings, TopUpState, UserProfile, VerificationSettings,
+        },
+        notifications, phone, place_order, pricing, promotions, remove_address,
+        resolve_customer_phone, snapshot, store, topup, update_address, verification,
+        OrderItemInput,
+    };
+    use candid::{Encode, Principal};
     use ic_cdk::api::time;
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn audit_summaries_refer_to_phone_numbers_by_hash() {
+        let reference = audit::phone_ref("+919876500001");
+        assert_eq!(reference.len(), 16);
+        assert!(!reference.contains("9876500001"));
+        assert_eq!(audit::phone_ref("098765 00001"), reference);
+        assert_ne!(audit::phone_ref("+919876500002"), reference);
+    }
+
+    #[test]
+    fn audit_log_entries_are_hash_chained() {
+        let caller = Principal::anonymous();
+        for (i, method) in ["add_product_admin", "delete_profile_admin"]
+            .iter()
+            .enumerate()
+        {
+            store::append_audit_entry(
+                caller,
+                method.to_string(),
+                format!("call {}", i),
+                AuditOutcome::Success,
+                i as u64,
+            )
+            .unwrap();
+        }
+
+        let entries = store::get_audit_entries(0, 10);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].prev_hash, entries[0].hash);
+        assert_eq!(store::verify_audit_chain(), Ok(()));
+    }
+
+    #[test]
+    fn phone_numbers_are_normalized_to_e164() {
+        let settings = PhoneSettings::default();
+        for raw in [
+            "9876543210",
+            "+91 98765 43210",
+            "098765-43210",
+            "919876543210",
+        ] {
+            assert_eq!(
+                phone::normalize_phone_number(raw, &settings),
+                Ok("+919876543210".to_string()),
+                "{}",
+                raw
+            );
+        }
+        assert_eq!(
+            phone::normalize_phone_number("0044 20 7946 0958", &settings),
+            Ok("+442079460958".to_string())
+        );
+        assert_eq!(
+            phone::normalize_phone_number("98765", &settings),
+            Err(PhoneNumberError::InvalidLength(5))
+        );
+        assert_eq!(
+            phone::normalize_phone_number(&"9".repeat(300), &settings),
+            Err(PhoneNumberError::InvalidLength(u8::MAX))
+        );
+        assert_eq!(
+            phone::normalize_phone_number("98765x43210", &settings),
+            Err(PhoneNumberError::InvalidCharacter("x".to_string()))
+        );
+        assert_eq!(
+            phone::normalize_phone_number("  ", &settings),
+            Err(PhoneNumberError::Empty)
+        );
+    }
+
+    #[test]
+    fn otp_codes_are_checked_against_their_hash() {
+        let phone_number = "+919876500000".to_string();
+        let salt = vec![7; 16];
+        store::put_otp_challenge(OtpChallenge {
+            phone_number: phone_number.clone(),
+            purpose: OtpPurpose::VerifyProfile,
+            code_hash: verification::hash_code(&salt, &phone_number, "123456"),
+            salt,
+            created_at: 0,
+            expires_at: 100,
+            attempts_left: 2,
+        });
+
+        assert!(matches!(
+            verification::check_code(&phone_number, "000000", 10),
+            Err(OtpError::InvalidCode { attempts_left: 1 })
+        ));
+        assert_eq!(
+            verification::check_code(&phone_number, "123456", 20).ok(),
+            Some(OtpPurpose::VerifyProfile)
+        );
+        // Codes are single-use
+        assert!(matches!(
+            verification::check_code(&phone_number, "123456", 30),
+            Err(OtpError::NoPendingCode)
+        ));
+
+        // A second request is throttled while the first is still generating its code
+        let settings = VerificationSettings::default();
+        let second = date::NANOS_PER_SECOND;
+        verification::reserve_send(&phone_number, &settings, 40 * second).unwrap();
+        assert!(matches!(
+            verification::reserve_send(&phone_number, &settings, 41 * second),
+            Err(OtpError::TooManyRequests { .. })
+        ));
+
+        // Using up the attempts locks the number out, so a fresh code doesn't bring fresh guesses
+        let phone_number = "+919876500001";
+        let challenge = |created_at: u64| OtpChallenge {
+            phone_number: phone_number.to_string(),
+            purpose: OtpPurpose::VerifyProfile,
+            code_hash: verification::hash_code(&[7; 16], phone_number, "123456"),
+            salt: vec![7; 16],
+            created_at,
+            expires_at: created_at + 300 * second,
+            attempts_left: 1,
+        };
+        store::put_otp_challenge(challenge(0));
+        assert!(matches!(
+            verification::check_code(phone_number, "000000", second),
+            Err(OtpError::TooManyAttempts)
+        ));
+        assert!(matches!(
+            verification::reserve_send(phone_number, &settings, 120 * second),
+            Err(OtpError::TooManyRequests {
+                retry_after_seconds: 781
+            })
+        ));
+        verification::reserve_send(phone_number, &settings, 901 * second).unwrap();
+        store::put_otp_challenge(challenge(901 * second));
+        assert!(matches!(
+            verification::check_code(phone_number, "000000", 902 * second),
+            Err(OtpError::TooManyAttempts)
+        ));
+        // The second lockout lasts twice as long
+        assert!(matches!(
+            verification::reserve_send(phone_number, &settings, 2000 * second),
+            Err(OtpError::TooManyRequests {
+                retry_after_seconds: 702
+            })
+        ));
+        // A correct code ends the lockouts
+        store::put_otp_challenge(challenge(2702 * second));
+        assert!(verification::check_code(phone_number, "123456", 2703 * second).is_ok());
+        assert!(store::get_otp_lockout(phone_number).is_none());
+    }
+
+    #[test]
+    fn local_days_map_to_timestamp_ranges() {
+        let days = date::parse_date("2024-03-01").unwrap();
+        let (start, end) = date::day_range(days, date::DEFAULT_UTC_OFFSET_MINUTES);
+        assert_eq!(date::format_timestamp(start, 330), "2024-03-01 00:00");
+        assert_eq!(date::format_timestamp(end - 1, 330), "2024-03-01 23:59");
+        assert_eq!(date::format_timestamp(start, 0), "2024-02-29 18:30");
+        assert!(date::parse_date("2023-02-29").is_err());
+        assert!(date::parse_date("2024-13-01").is_err());
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        let mut out = String::new();
+        csv::write_row(&mut out, &["1", "Flat 2, MG Road", "say \"hi\""]);
+        assert_eq!(out, "1,\"Flat 2, MG Road\",\"say \"\"hi\"\"\"\r\n");
+    }
+
+    #[test]
+    fn product_witness_matches_certified_root_hash() {
+        // Stored products are certified without further calls
+        let id = store::add_product(Product {
+            name: "Khatti Dahi".to_string(),
+            description: "Sour Curd".to_string(),
+            price: 50.0,
+            unit: "kg".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let before = certified::certified_root_hash();
+
+        let mut product = store::get_product_by_id(id).unwrap();
+        product.price = 55.0;
+        let product = store::update_product(id, product).unwrap();
+        assert_ne!(certified::certified_root_hash(), before);
+
+        let witness: ic_certification::HashTree =
+            serde_cbor::from_slice(&certified::products_witness().unwrap()).unwrap();
+        assert_eq!(witness.digest(), certified::certified_root_hash());
+        let key = certified::product_key(id);
+        assert!(matches!(
+            witness.lookup_path([b"products".as_slice(), key.as_slice()]),
+            ic_certification::LookupResult::Found(leaf) if leaf == Encode!(&product).unwrap()
+        ));
+    }
+
+    #[test]
+    fn csv_parser_handles_quotes_and_line_breaks() {
+        let records = csv::parse("a,b\r\n\"x, \"\"y\"\"\",\"line 1\nline 2\"\n\n1,").unwrap();
+        assert_eq!(
+            records,
+            vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec!["x, \"y\"".to_string(), "line 1\nline 2".to_string()],
+                vec!["1".to_string(), String::new()],
+            ]
+        );
+        assert!(csv::parse("\"unterminated").is_err());
+    }
+
+    #[test]
+    fn customer_import_validates_rows_and_honours_dry_run() {
+        let input = format!(
+            "Name,Phone_Number,Address\n\
+             Asha,098765 11111,Ward 4\n\
+             Ravi,12345,Ward 5\n\
+             Asha again,+91 98765 11111,Ward 6\n\
+             Meena,098765 22222,{}\n",
+            "x".repeat(9000)
+        );
+        let settings = PhoneSettings::default();
+
+        let report = bulk::import_customers(&input, true, &settings).unwrap();
+        assert_eq!((report.created, report.invalid), (1, 3));
+        assert_eq!(report.rows[0].key, "+919876511111");
+        assert_eq!(report.rows[2].row, 4);
+        assert!(store::get_user_profile(&"+919876511111".to_string()).is_none());
+
+        // The oversized row is reported instead of trapping the whole import
+        let report = bulk::import_customers(&input, false, &settings).unwrap();
+        assert_eq!(report.rows[0].status, ImportRowStatus::Created);
+        assert_eq!(report.rows[3].key, "+919876522222");
+        assert!(matches!(report.rows[3].status, ImportRowStatus::Invalid(_)));
+        let profile = store::get_user_profile(&"+919876511111".to_string()).unwrap();
+        assert_eq!(profile.address, "Ward 4");
+        assert!(store::get_user_profile(&"+919876522222".to_string()).is_none());
+    }
+
+    #[test]
+    fn snapshots_restore_into_a_fresh_canister_only_when_complete() {
+        store::add_product(Product {
+            name: "Milk".to_string(),
+            price: 70.0,
+            unit: "litre".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        store::append_audit_entry(
+            Principal::anonymous(),
+            "add_product_admin".to_string(),
+            String::new(),
+            AuditOutcome::Success,
+            1,
+        )
+        .unwrap();
+        assert_eq!(store::get_next_order_id(), 0);
+        // No profile: only the stored order keeps the name it was placed under
+        store::add_order(Order {
+            id: 0,
+            user_phone_number: "+919876500001".to_string(),
+            customer_name: "Asha".to_string(),
+            items: Vec::new(),
+            total_amount: 70.0,
+            status: OrderStatus::Pending,
+            timestamp: 1,
+            delivery_address: "Ward 4".to_string(),
+            delivery_address_id: None,
+            last_updated: 1,
+            discounts: None,
+        })
+        .unwrap();
+        store::put_cart(Cart {
+            phone_number: "+919876500001".to_string(),
+            items: vec![CartItem {
+                product_id: 0,
+                quantity: 2.0,
+                price_per_unit: 70.0,
+                variant_id: None,
+            }],
+            updated_at: 1,
+        });
+
+        let manifest = snapshot::export(2).unwrap();
+        let chunks: Vec<_> = (0..manifest.chunk_count)
+            .map(|index| snapshot::export_chunk(index).unwrap())
+            .collect();
+        // Not fresh: this canister already holds data
+        assert!(snapshot::begin_import(manifest.clone()).is_err());
+
+        // Thread-local stores give the new thread its own, freshly installed canister
+        std::thread::spawn(move || {
+            let wrong_version = SnapshotManifest {
+                version: snapshot::SNAPSHOT_VERSION + 1,
+                ..manifest.clone()
+            };
+            assert!(snapshot::begin_import(wrong_version).is_err());
+
+            // A complete upload that doesn't decode fails without using up the fresh canister
+            let garbage = b"not a snapshot".to_vec();
+            let garbage_checksum = Sha256::digest(&garbage).to_vec();
+            snapshot::begin_import(SnapshotManifest {
+                version: snapshot::SNAPSHOT_VERSION,
+                created_at: 2,
+                total_size: garbage.len() as u64,
+                chunk_size: garbage.len() as u64,
+                chunk_count: 1,
+                checksum: garbage_checksum.clone(),
+            })
+            .unwrap();
+            snapshot::import_chunk(SnapshotChunk {
+                version: snapshot::SNAPSHOT_VERSION,
+                snapshot_checksum: garbage_checksum.clone(),
+                index: 0,
+                data: garbage,
+                checksum: garbage_checksum,
+            })
+            .unwrap();
+            assert!(finish_snapshot_import(Principal::anonymous(), 3).is_err());
+            assert_eq!(store::audit_log_len(), 0);
+
+            snapshot::begin_import(manifest).unwrap();
+            assert!(finish_snapshot_import(Principal::anonymous(), 3).is_err());
+            let mut tampered = chunks[0].clone();
+            tampered.data[0] ^= 1;
+            assert!(snapshot::import_chunk(tampered).is_err());
+            for chunk in chunks {
+                snapshot::import_chunk(chunk).unwrap();
+            }
+            finish_snapshot_import(Principal::anonymous(), 3).unwrap();
+
+            assert_eq!(store::get_all_products()[0].name, "Milk");
+            assert_eq!(store::get_next_order_id(), 1);
+            assert_eq!(store::get_order(0).unwrap().customer_name, "Asha");
+            let cart = store::get_cart("+919876500001").unwrap();
+            assert_eq!(cart.items[0].quantity, 2.0);
+            // The exported log plus the import itself
+            assert_eq!(store::audit_log_len(), 2);
+            assert_eq!(store::verify_audit_chain(), Ok(()));
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn auto_cancel_job_cancels_stale_pending_orders_and_reschedules() {
+        const HOUR: u64 = 3600 * date::NANOS_PER_SECOND;
+        // The built-in job ships disabled, so an upgrade doesn't cancel old pending orders
+        jobs::ensure_builtin_jobs(0);
+        let builtin = store::get_all_jobs()
+            .into_iter()
+            .find(|job| job.name == jobs::AUTO_CANCEL_JOB_NAME)
+            .unwrap();
+        assert!(!builtin.enabled);
+        for job in store::get_all_jobs() {
+            store::remove_job(job.id);
+        }
+
+        for (id, placed_at) in [(0, 0), (1, 5 * HOUR)] {
+            store::add_order(Order {
+                id,
+                user_phone_number: "+919876500001".to_string(),
+                customer_name: "Asha".to_string(),
+                items: Vec::new(),
+                total_amount: 100.0,
+                status: OrderStatus::Pending,
+                timestamp: placed_at,
+                delivery_address: "Ward 4".to_string(),
+                delivery_address_id: None,
+                last_updated: placed_at,
+                discounts: None,
+            })
+            .unwrap();
+        }
+        store::put_job(Job {
+            id: 0,
+            name: jobs::AUTO_CANCEL_JOB_NAME.to_string(),
+            kind: JobKind::AutoCancelPendingOrders {
+                timeout_seconds: 2 * 3600,
+            },
+            schedule: JobSchedule::Recurring {
+                interval_seconds: 3600,
+            },
+            enabled: true,
+            next_run_at: 6 * HOUR,
+            last_run_at: None,
+            last_result: None,
+        });
+
+        jobs::run_due_jobs(6 * HOUR);
+        assert_eq!(store::get_order(0).unwrap().status, OrderStatus::Cancelled);
+        assert_eq!(store::get_order(1).unwrap().status, OrderStatus::Pending);
+        assert_eq!(store::get_job(0).unwrap().next_run_at, 7 * HOUR);
+        assert_eq!(jobs::next_due_at(), Some(7 * HOUR));
+
+        let summary = jobs::summarize_day(0, 0, 6 * HOUR);
+        assert_eq!(summary.date, "1970-01-01");
+        assert_eq!(
+            (
+                summary.orders_placed,
+                summary.orders_cancelled,
+                summary.orders_open
+            ),
+            (2, 1, 1)
+        );
+        assert_eq!(summary.revenue, 100.0);
+        assert_eq!(summary.first_time_customers, 1);
+    }
+
+    #[test]
+    fn cycles_burn_rate_ignores_top_ups_and_samples_are_pruned() {
+        const DAY: u64 = 86_400 * date::NANOS_PER_SECOND;
+        let canister_id = Principal::anonymous();
+        let sample = |day: u64, cycles: u128| CyclesSample {
+            canister_id,
+            timestamp: day * DAY,
+            cycles,
+            memory_size: 0,
+            idle_cycles_burned_per_day: 10,
+            freezing_threshold_seconds: 30 * 86_400,
+            alerted: false,
+        };
+        // 100 burned on the first day, a top-up of 500, then 200 burned
+        for s in [
+            sample(1, 1_000),
+            sample(2, 900),
+            sample(3, 1_400),
+            sample(5, 1_200),
+        ] {
+            store::add_cycles_sample(s);
+        }
+
+        let samples = store::get_cycles_samples(canister_id, 0);
+        assert_eq!(samples.len(), 4);
+        let burn = cycles::burn_per_day(&samples);
+        assert_eq!(burn, 75);
+        // 300 of the 1200 cycles are reserved for the 30-day freezing threshold
+        assert_eq!(
+            cycles::projected_days_until_freeze(samples.last().unwrap(), burn),
+            Some(12.0)
+        );
+        assert_eq!(cycles::burn_per_day(&samples[..1]), 10);
+
+        assert_eq!(store::remove_cycles_samples_before(3 * DAY), 2);
+        assert_eq!(store::get_cycles_samples(canister_id, 0).len(), 2);
+    }
+
+    #[test]
+    fn monitored_canisters_default_to_self_and_are_validated() {
+        let self_id = Principal::from_slice(&[1; 10]);
+        let frontend_id = Principal::from_slice(&[2; 10]);
+        cycles::init_monitored_canisters(None, self_id).unwrap();
+        assert_eq!(
+            store::get_monitored_canisters(),
+            vec![MonitoredCanister {
+                canister_id: self_id,
+                label: "Backend".to_string()
+            }]
+        );
+
+        let frontend = cycles::validate_monitored_canister(MonitoredCanister {
+            canister_id: frontend_id,
+            label: "  Frontend ".to_string(),
+        })
+        .unwrap();
+        assert_eq!(frontend.label, "Frontend");
+        store::set_monitored_canister(frontend);
+        assert_eq!(store::get_monitored_canisters().len(), 2);
+
+        assert!(cycles::validate_monitored_canister(MonitoredCanister {
+            canister_id: frontend_id,
+            label: " ".to_string(),
+        })
+        .is_err());
+        assert!(cycles::validate_monitored_canister(MonitoredCanister {
+            canister_id: Principal::anonymous(),
+            label: "Anonymous".to_string(),
+        })
+        .is_err());
+
+        // Upgrades don't re-add the backend once the list has been configured
+        store::remove_monitored_canister(self_id).unwrap();
+        cycles::ensure_monitored_canisters(self_id);
+        assert_eq!(store::get_monitored_canisters().len(), 1);
+    }
+
+    #[test]
+    fn top_up_accounts_and_automatic_top_ups() {
+        assert_eq!(
+            hex::encode(&topup::account_identifier(Principal::anonymous(), &[0; 32])),
+            "1c7a48ba6a562aa9eaa2481a9049cdf0433b9738c992d698c31d8abf89cadc79"
+        );
+        let canister_id = Principal::from_slice(&[1; 10]);
+        let subaccount = topup::principal_to_subaccount(canister_id);
+        assert_eq!(subaccount[0], 10);
+        assert_eq!(&subaccount[1..11], canister_id.as_slice());
+
+        let settings = TopUpSettings {
+            auto_top_up: true,
+            ..Default::default()
+        };
+        assert!(topup::validate(canister_id, 10_000_000, &settings).is_err());
+        store::set_monitored_canister(MonitoredCanister {
+            canister_id,
+            label: "Backend".to_string(),
+        });
+        assert!(topup::validate(canister_id, 10_000_000, &settings).is_ok());
+        assert!(topup::validate(canister_id, settings.max_top_up_e8s + 1, &settings).is_err());
+
+        const HOUR: u64 = 3600 * date::NANOS_PER_SECOND;
+        let low = settings.min_cycles_balance - 1;
+        assert!(!topup::auto_top_up_due(
+            &settings,
+            canister_id,
+            settings.min_cycles_balance,
+            0
+        ));
+        assert!(topup::auto_top_up_due(
+            &settings,
+            canister_id,
+            low,
+            10 * HOUR
+        ));
+        store::put_top_up(TopUp {
+            id: store::get_next_top_up_id(),
+            canister_id,
+            icp_e8s: settings.auto_top_up_e8s,
+            automatic: true,
+            requested_by: canister_id,
+            created_at: 10 * HOUR,
+            state: TopUpState::Transferred { block_index: 7 },
+            last_error: None,
+        });
+        // Not repeated while a recent automatic top-up exists
+        assert!(!topup::auto_top_up_due(
+            &settings,
+            canister_id,
+            low,
+            12 * HOUR
+        ));
+        assert!(topup::auto_top_up_due(
+            &settings,
+            canister_id,
+            low,
+            17 * HOUR
+        ));
+    }
+
+    #[test]
+    fn products_are_localized_with_english_fallback() {
+        let translations = i18n::validate_translations(vec![ProductTranslation {
+            lang: "hi-IN".to_string(),
+            name: " पनीर ".to_string(),
+            description: "ताज़ा पनीर".to_string(),
+            unit: None,
+        }])
+        .unwrap();
+        assert_eq!(translations[0].lang, "hi");
+        assert_eq!(translations[0].name, "पनीर");
+        let id = store::add_product(Product {
+            name: "Paneer".to_string(),
+            description: "Fresh Paneer".to_string(),
+            price: 300.0,
+            unit: "kg".to_string(),
+            translations: Some(translations),
+            ..Default::default()
+        })
+        .unwrap();
+        let product = store::get_product_by_id(id).unwrap();
+
+        let hindi = i18n::localize_product(product.clone(), "hi");
+        assert_eq!((hindi.name.as_str(), hindi.unit.as_str()), ("पनीर", "kg"));
+        assert!(hindi.translations.is_none());
+        // Unsupported languages fall back to English
+        assert_eq!(i18n::localize_product(product, "fr").name, "Paneer");
+
+        assert!(i18n::validate_translations(vec![ProductTranslation {
+            lang: "fr".to_string(),
+            name: "Fromage".to_string(),
+            ..Default::default()
+        }])
+        .is_err());
+
+        // Every error code has a message in each language
+        let codes = [
+            OrderError::OrderNotFound.code(),
+            OtpError::InvalidCode { attempts_left: 2 }.code(),
+            OtpError::InvalidPhoneNumber(PhoneNumberError::Empty).code(),
+        ];
+        for code in codes {
+            assert!(i18n::error_message(code, "en").is_some(), "{}", code);
+            assert_ne!(
+                i18n::error_message(code, "hi"),
+                i18n::error_message(code, "en")
+            );
+        }
+        assert_eq!(
+            i18n::error_messages("hi").len(),
+            i18n::error_messages("en").len()
+        );
+    }
+
+    #[test]
+    fn validation_reports_every_invalid_field_and_store_errors_are_typed() {
+        let errors = address::validate_address_input(AddressInput {
+            label: " ".to_string(),
+            address_lines: vec!["12 MG Road".to_string()],
+            landmark: None,
+            zone: None,
+            geo: Some(GeoPoint {
+                latitude: 95.0,
+                longitude: 200.0,
+            }),
+        })
+        .unwrap_err();
+        let fields: Vec<(&str, &FieldErrorKind)> = errors
+            .iter()
+            .map(|error| (error.field.as_str(), &error.kind))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("label", &FieldErrorKind::Required),
+                ("geo.latitude", &FieldErrorKind::OutOfRange),
+                ("geo.longitude", &FieldErrorKind::OutOfRange),
+            ]
+        );
+        assert!(errors
+            .iter()
+            .all(|error| i18n::error_message(error.code(), "hi").is_some()));
+
+        let errors = i18n::validate_translations(vec![
+            ProductTranslation {
+                lang: "hi".to_string(),
+                name: "पनीर".to_string(),
+                ..Default::default()
+            },
+            ProductTranslation {
+                lang: "hi".to_string(),
+                name: "".to_string(),
+                ..Default::default()
+            },
+        ])
+        .unwrap_err();
+        assert_eq!(errors[0].field, "translations[1].name");
+        assert_eq!(errors[1].kind, FieldErrorKind::Duplicate);
+
+        // Missing records map to the "not found" variant of the endpoint's error
+        let missing = store::update_order_status(404, OrderStatus::Cancelled, 0).unwrap_err();
+        assert_eq!(missing, StoreError::NotFound(404));
+        assert!(matches!(
+            OrderError::from(missing),
+            OrderError::OrderNotFound
+        ));
+        let missing = store::update_product(404, Product::default()).unwrap_err();
+        assert!(matches!(
+            ProductError::from(missing),
+            ProductError::ProductNotFound(404)
+        ));
+        let too_large = store::add_product(Product {
+            description: "x".repeat(5000),
+            ..Default::default()
+        })
+        .unwrap_err();
+        match ProductError::from(too_large) {
+            ProductError::InvalidInput(errors) => {
+                assert_eq!(errors[0].kind, FieldErrorKind::TooLong)
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn admin_endpoints_report_typed_errors() {
+        let error = jobs::validate(
+            &JobKind::AutoCancelPendingOrders {
+                timeout_seconds: 10,
+            },
+            &JobSchedule::Recurring {
+                interval_seconds: 1,
+            },
+        )
+        .unwrap_err();
+        match &error {
+            JobError::InvalidInput(errors) => {
+                let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+                assert_eq!(
+                    fields,
+                    vec!["schedule.interval_seconds", "kind.timeout_seconds"]
+                );
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let error = cycles::validate_monitored_canister(MonitoredCanister {
+            canister_id: Principal::anonymous(),
+            label: " ".to_string(),
+        })
+        .unwrap_err();
+        match &error {
+            CyclesError::InvalidInput(errors) => assert_eq!(errors.len(), 2),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let codes = [
+            error.code(),
+            JobError::JobNotFound(1).code(),
+            snapshot::finish_import().unwrap_err().code(),
+            bulk::import_products("", true, 0).unwrap_err().code(),
+            TopUpError::NotRetryable(1).code(),
+        ];
+        assert_eq!(codes[2], "SNAPSHOT_NO_IMPORT");
+        assert_eq!(codes[3], "CSV_INVALID");
+        for code in codes {
+            assert!(i18n::error_message(code, "hi").is_some(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn cart_items_are_merged_and_repriced() {
+        let id = store::add_product(Product {
+            name: "Ghee".to_string(),
+            price: 600.0,
+            unit: "kg".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let mut cart = Cart {
+            phone_number: "+919876543210".to_string(),
+            ..Default::default()
+        };
+        cart::add_item(&mut cart, id, None, 0.5, 0).unwrap();
+        cart::add_item(&mut cart, id, None, 1.0, 0).unwrap();
+        assert_eq!(cart.items.len(), 1);
+        assert_eq!(cart.items[0].quantity, 1.5);
+        assert!(matches!(
+            cart::add_item(&mut cart, id, None, 0.0, 0),
+            Err(CartError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            cart::add_item(&mut cart, 404, None, 1.0, 0),
+            Err(CartError::ProductNotFound(404))
+        ));
+        assert!(matches!(
+            cart::update_item(&mut cart, 404, None, 1.0, 0),
+            Err(CartError::ProductNotFound(404))
+        ));
+        store::put_cart(cart.clone());
+
+        // A price change is flagged in the view and taken over once refreshed
+        let product = store::get_product_by_id(id).unwrap();
+        store::update_product(
+            id,
+            Product {
+                price: 650.0,
+                ..product
+            },
+        )
+        .unwrap();
+        let mut cart = store::get_cart(&cart.phone_number).unwrap();
+        let view = cart::view(&cart, 0);
+        assert!(view.prices_changed);
+        assert_eq!(view.lines[0].previous_price_per_unit, Some(600.0));
+        assert_eq!(view.total_amount, 975.0);
+        assert_eq!(cart::refresh_prices(&mut cart, 0), vec![id]);
+        assert!(cart::refresh_prices(&mut cart, 0).is_empty());
+        assert!(!cart::view(&cart, 0).prices_changed);
+
+        // Empty carts are not stored
+        cart::remove_item(&mut cart, id, None).unwrap();
+        store::put_cart(cart.clone());
+        assert!(store::get_cart(&cart.phone_number).is_none());
+    }
+
+    #[test]
+    fn coupons_discount_eligible_items_within_their_limits() {
+        let ghee = store::add_product(Product {
+            name: "Ghee".to_string(),
+            price: 600.0,
+            unit: "kg".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let milk = store::add_product(Product {
+            name: "Milk".to_string(),
+            price: 60.0,
+            unit: "litre".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let coupon = promotions::validate_coupon(Coupon {
+            code: " diwali10 ".to_string(),
+            description: "10% off ghee".to_string(),
+            discount: Discount::Percentage(10.0),
+            scope: CouponScope::Products(vec![ghee]),
+            valid_from: Some(100),
+            valid_until: Some(200),
+            min_order_amount: None,
+            max_uses: None,
+            max_uses_per_customer: Some(1),
+            enabled: true,
+        })
+        .unwrap();
+        assert_eq!(coupon.code, "DIWALI10");
+        store::put_coupon(coupon.clone());
+
+        let items = vec![
+            OrderItem {
+                product_id: ghee,
+                quantity: 0.5,
+                price_per_unit_at_order: 600.0,
+                price_list_id: None,
+                variant_id: None,
+            },
+            OrderItem {
+                product_id: milk,
+                quantity: 2.0,
+                price_per_unit_at_order: 60.0,
+                price_list_id: None,
+                variant_id: None,
+            },
+        ];
+        let customer = "+919876500001";
+        let discount = promotions::discount_for("Diwali10", customer, &items, 150).unwrap();
+        assert_eq!(discount.amount, 30.0);
+        assert_eq!(
+            promotions::discount_for("DIWALI10", customer, &items, 200),
+            Err(CouponError::Expired)
+        );
+        assert_eq!(
+            promotions::discount_for("DIWALI10", customer, &items[1..], 150),
+            Err(CouponError::NotApplicable)
+        );
+
+        // The customer's one use is taken by a placed order and given back on cancellation
+        store::add_order(Order {
+            id: 0,
+            user_phone_number: customer.to_string(),
+            customer_name: "Asha".to_string(),
+            items: items.clone(),
+            total_amount: 390.0,
+            status: OrderStatus::Pending,
+            timestamp: 150,
+            delivery_address: "Ward 4".to_string(),
+            delivery_address_id: None,
+            last_updated: 150,
+            discounts: Some(vec![discount]),
+        })
+        .unwrap();
+        assert_eq!(
+            promotions::discount_for("DIWALI10", customer, &items, 150),
+            Err(CouponError::CustomerLimitReached)
+        );
+        assert!(promotions::discount_for("DIWALI10", "+919876500002", &items, 150).is_ok());
+        store::update_order_status(0, OrderStatus::Cancelled, 160).unwrap();
+        assert_eq!(promotions::times_used("DIWALI10", None), 0);
+
+        let invalid = promotions::validate_coupon(Coupon {
+            code: "X".to_string(),
+            discount: Discount::Percentage(150.0),
+            valid_from: Some(200),
+            valid_until: Some(100),
+            ..coupon
+        });
+        match invalid {
+            Err(CouponError::InvalidInput(errors)) => {
+                let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+                assert_eq!(fields, vec!["code", "discount", "valid_until"]);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn price_lists_resolve_quantity_tiers_with_fallbacks() {
+        let paneer = store::add_product(Product {
+            name: "Paneer".to_string(),
+            price: 300.0,
+            unit: "kg".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let milk = store::add_product(Product {
+            name: "Milk".to_string(),
+            price: 60.0,
+            unit: "litre".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let tiers = |tiers: &[(f64, f64)]| {
+            tiers
+                .iter()
+                .map(|&(min_quantity, price_per_unit)| PriceTier {
+                    min_quantity,
+                    price_per_unit,
+                })
+                .collect::<Vec<_>>()
+        };
+        let retail = pricing::validate_price_list(PriceList {
+            id: 0,
+            name: "Retail".to_string(),
+            kind: PriceListKind::Retail,
+            prices: vec![ProductPrice {
+                product_id: milk,
+                tiers: tiers(&[(0.0, 58.0)]),
+            }],
+        })
+        .unwrap();
+        store::put_price_list(retail);
+        // Tiers are sorted on validation
+        let wholesale = pricing::validate_price_list(PriceList {
+            id: 1,
+            name: " Hotels ".to_string(),
+            kind: PriceListKind::Wholesale,
+            prices: vec![ProductPrice {
+                product_id: paneer,
+                tiers: tiers(&[(20.0, 240.0), (5.0, 270.0)]),
+            }],
+        })
+        .unwrap();
+        assert_eq!(wholesale.name, "Hotels");
+        assert_eq!(wholesale.prices[0].tiers[0].min_quantity, 5.0);
+        store::put_price_list(wholesale);
+
+        let price = |list: Option<u64>, product_id: u64, quantity: f64| {
+            let product = store::get_product_by_id(product_id).unwrap();
+            let price = pricing::unit_price(list, &product, quantity, 0);
+            (price.price_per_unit, price.price_list_id)
+        };
+        assert_eq!(price(Some(1), paneer, 2.0), (300.0, None));
+        assert_eq!(price(Some(1), paneer, 5.0), (270.0, Some(1)));
+        assert_eq!(price(Some(1), paneer, 25.0), (240.0, Some(1)));
+        // Products missing from the customer's list come from the retail list
+        assert_eq!(price(Some(1), milk, 1.0), (58.0, Some(0)));
+        assert_eq!(price(None, paneer, 25.0), (300.0, None));
+
+        // Only one retail list, and tiers may not start at the same quantity
+        let invalid = pricing::validate_price_list(PriceList {
+            id: 2,
+            name: "Retail 2".to_string(),
+            kind: PriceListKind::Retail,
+            prices: vec![ProductPrice {
+                product_id: paneer,
+                tiers: tiers(&[(1.0, 290.0), (1.0, 280.0)]),
+            }],
+        });
+        match invalid {
+            Err(PriceListError::InvalidInput(errors)) => {
+                let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+                assert_eq!(fields, vec!["kind", "prices[0].tiers"]);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn scheduled_price_changes_apply_at_their_effective_time() {
+        let id = store::add_product(Product {
+            name: "Curd".to_string(),
+            price: 80.0,
+            unit: "kg".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let product = store::get_product_by_id(id).unwrap();
+        let schedule = |price: f64, at: u64| pricing::schedule_price_change(id, price, at, 100);
+
+        assert!(matches!(
+            schedule(90.0, 100),
+            Err(ProductError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            pricing::schedule_price_change(404, 90.0, 200, 100),
+            Err(ProductError::ProductNotFound(404))
+        ));
+        schedule(90.0, 200).unwrap();
+        schedule(95.0, 300).unwrap();
+        // The price before the first change is kept, so every time has a price
+        let history: Vec<(u64, f64)> = store::get_price_changes(id)
+            .iter()
+            .map(|change| (change.effective_from, change.price))
+            .collect();
+        assert_eq!(history, vec![(0, 80.0), (200, 90.0), (300, 95.0)]);
+        assert_eq!(pricing::base_price(&product, 150), 80.0);
+        assert_eq!(pricing::base_price(&product, 250), 90.0);
+        assert_eq!(
+            pricing::unit_price(None, &product, 1.0, 300).price_per_unit,
+            95.0
+        );
+        assert_eq!(pricing::next_price_change_at(100), Some(200));
+
+        // Due changes are applied to the product; cancelling only works ahead of time
+        assert_eq!(pricing::apply_due_price_changes(250), 1);
+        assert_eq!(store::get_product_by_id(id).unwrap().price, 90.0);
+        assert_eq!(pricing::apply_due_price_changes(250), 0);
+        assert!(matches!(
+            pricing::cancel_price_change(id, 200, 250),
+            Err(ProductError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            pricing::cancel_price_change(id, 400, 250),
+            Err(ProductError::PriceChangeNotFound { .. })
+        ));
+        pricing::cancel_price_change(id, 300, 250).unwrap();
+        assert_eq!(pricing::next_price_change_at(250), None);
+        assert_eq!(pricing::base_price(&product, 300), 90.0);
+    }
+
+    #[test]
+    fn products_are_found_by_name_category_and_tags() {
+        let category = |id: u64, name: &str, parent_id: Option<u64>| {
+            let category = catalog::validate_category(Category {
+                id,
+                name: name.to_string(),
+                parent_id,
+            })
+            .unwrap();
+            store::put_category(category);
+        };
+        category(0, "Sweets", None);
+        category(1, "Festive", Some(0));
+        category(2, "Dairy", None);
+        let product =
+            |name: &str, hindi_name: &str, price: f64, category_id: u64, tags: &[&str]| {
+                store::add_product(Product {
+                    name: name.to_string(),
+                    price,
+                    unit: "kg".to_string(),
+                    translations: Some(vec![ProductTranslation {
+                        lang: "hi".to_string(),
+                        name: hindi_name.to_string(),
+                        ..Default::default()
+                    }]),
+                    category_id: Some(category_id),
+                    tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+                    ..Default::default()
+                })
+                .unwrap()
+            };
+        let kaju_katli = product("Kaju Katli", "काजू कतली", 900.0, 1, &["diwali"]);
+        let barfi = product("Milk Barfi", "दूध बर्फी", 500.0, 0, &[]);
+        let milk = product("Milk", "दूध", 70.0, 2, &["daily"]);
+
+        let search = |text: &str, sort: Option<ProductSort>| -> Vec<u64> {
+            catalog::search_products(ProductSearch {
+                text: Some(text.to_string()),
+                sort,
+                ..Default::default()
+            })
+            .iter()
+            .map(|product| product.id)
+            .collect()
+        };
+        // Prefix and substring matches, in any language, words at the start ranked first
+        assert_eq!(search("mil", None), vec![milk, barfi]);
+        assert_eq!(search("ilk", None), vec![milk, barfi]);
+        assert_eq!(search("KATLI kaju", None), vec![kaju_katli]);
+        assert_eq!(search("दूध", None), vec![milk, barfi]);
+        assert_eq!(
+            search("", Some(ProductSort::PriceDescending)),
+            vec![kaju_katli, barfi, milk]
+        );
+
+        // Renamed products are found by their new name only
+        let renamed = Product {
+            name: "Toned Milk".to_string(),
+            translations: None,
+            ..store::get_product_by_id(milk).unwrap()
+        };
+        store::update_product(milk, renamed).unwrap();
+        assert_eq!(search("दूध", None), vec![barfi]);
+        assert_eq!(search("toned", None), vec![milk]);
+
+        // Categories include their subcategories
+        let in_category = |category_id: u64, tags: Option<Vec<String>>| -> Vec<u64> {
+            catalog::search_products(ProductSearch {
+                category_id: Some(category_id),
+                tags,
+                sort: Some(ProductSort::Name),
+                ..Default::default()
+            })
+            .iter()
+            .map(|product| product.id)
+            .collect()
+        };
+        assert_eq!(in_category(0, None), vec![kaju_katli, barfi]);
+        assert_eq!(
+            in_category(0, Some(vec![" Diwali".to_string()])),
+            vec![kaju_katli]
+        );
+
+        // No loops, and removing a category moves its products up
+        match catalog::validate_category(Category {
+            id: 0,
+            name: "Sweets".to_string(),
+            parent_id: Some(1),
+        }) {
+            Err(CategoryError::InvalidInput(errors)) => assert_eq!(errors[0].field, "parent_id"),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            catalog::remove_category(0),
+            Err(CategoryError::HasSubcategories(0))
+        ));
+        catalog::remove_category(1).unwrap();
+        assert_eq!(
+            store::get_product_by_id(kaju_katli).unwrap().category_id,
+            Some(0)
+        );
+        assert_eq!(
+            catalog::validate_tags(vec!["Diwali".to_string(), "diwali ".to_string()]).unwrap(),
+            vec!["diwali"]
+        );
+    }
+
+    #[test]
+    fn images_are_uploaded_in_chunks_and_served_with_caching_headers() {
+        use crate::http::{handle_query, HttpRequest};
+        use sha2::{Digest, Sha256};
+
+        let caller = Principal::anonymous();
+        let png = b"\x89PNG\r\n\x1A\nimage data".to_vec();
+        let sha256 = Sha256::digest(&png).to_vec();
+        images::append_upload_chunk(caller, "a".to_string(), &png[..6]).unwrap();
+        assert_eq!(
+            images::append_upload_chunk(caller, "a".to_string(), &png[6..]).unwrap(),
+            png.len() as u64
+        );
+        assert!(matches!(
+            images::append_upload_chunk(
+                caller,
+                "b".to_string(),
+                &vec![0; images::MAX_IMAGE_BYTES + 1]
+            ),
+            Err(ImageError::TooLarge { .. })
+        ));
+        let image = images::finish_upload(caller, "a", "image/png", &sha256, 1).unwrap();
+        assert_eq!(image.size, png.len() as u64);
+        assert!(matches!(
+            images::finish_upload(caller, "a", "image/png", &sha256, 1),
+            Err(ImageError::UploadNotFound(_))
+        ));
+
+        // Identical content is stored once; the type and hash must match the content
+        images::append_upload_chunk(caller, "c".to_string(), &png).unwrap();
+        let again = images::finish_upload(caller, "c", "IMAGE/PNG", &sha256, 2).unwrap();
+        assert_eq!(again.id, image.id);
+        images::append_upload_chunk(caller, "d".to_string(), &png).unwrap();
+        assert!(matches!(
+            images::finish_upload(caller, "d", "image/jpeg", &sha256, 2),
+            Err(ImageError::InvalidInput(_))
+        ));
+        images::append_upload_chunk(caller, "e".to_string(), &png).unwrap();
+        assert!(matches!(
+            images::finish_upload(caller, "e", "image/png", &[0; 32], 2),
+            Err(ImageError::ChecksumMismatch)
+        ));
+
+        let request = |etag: Option<&str>| HttpRequest {
+            method: "GET".to_string(),
+            url: format!("/api/images/{}", image.id),
+            headers: etag
+                .map(|etag| vec![("If-None-Match".to_string(), etag.to_string())])
+                .unwrap_or_default(),
+            body: Vec::new(),
+        };
+        let response = handle_query(&request(None));
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.body, png);
+        let header = |name: &str| {
+            response
+                .headers
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+                .unwrap()
+        };
+        assert_eq!(header("Content-Type"), "image/png");
+        assert!(header("Cache-Control").contains("immutable"));
+        assert_eq!(
+            handle_query(&request(Some(&header("ETag")))).status_code,
+            304
+        );
+
+        // Images shown for a product can't be removed
+        let product_id = store::add_product(Product {
+            name: "Paneer".to_string(),
+            image_id: Some(image.id),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(matches!(
+            images::remove_image(image.id),
+            Err(ImageError::InUse(id)) if id == product_id
+        ));
+    }
+
+    #[test]
+    fn variants_are_sold_in_whole_packs_from_stock() {
+        let variant =
+            |id: u64, sku: &str, pack_size: f64, price: f64, stock: Option<u64>| ProductVariant {
+                id,
+                sku: sku.to_string(),
+                label: format!("{} litre tin", pack_size),
+                pack_size,
+                price,
+                stock,
+            };
+        let variants = catalog::validate_variants(
+            None,
+            vec![
+                variant(0, " ghee-500 ", 0.5, 320.0, Some(3)),
+                variant(1, "GHEE-1000", 1.0, 600.0, None),
+            ],
+        )
+        .unwrap();
+        assert_eq!(variants[0].sku, "GHEE-500");
+        let ghee = store::add_product(Product {
+            name: "Ghee".to_string(),
+            price: 600.0,
+            unit: "litre".to_string(),
+            variants: Some(variants),
+            ..Default::default()
+        })
+        .unwrap();
+        let paneer = store::add_product(Product {
+            name: "Paneer".to_string(),
+            price: 300.0,
+            unit: "kg".to_string(),
+            quantity_step: Some(0.25),
+            ..Default::default()
+        })
+        .unwrap();
+
+        // SKUs are unique across products
+        match catalog::validate_variants(Some(paneer), vec![variant(0, "Ghee-500", 1.0, 1.0, None)])
+        {
+            Err(errors) => assert_eq!(errors[0].field, "variants[0].sku"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let ghee_product = store::get_product_by_id(ghee).unwrap();
+        let paneer_product = store::get_product_by_id(paneer).unwrap();
+        assert_eq!(
+            catalog::check_quantity(&ghee_product, None, 1.0),
+            Err(QuantityError::VariantRequired)
+        );
+        assert_eq!(
+            catalog::check_quantity(&ghee_product, Some(0), 1.5),
+            Err(QuantityError::NotWholePacks)
+        );
+        assert_eq!(
+            catalog::check_quantity(&ghee_product, Some(7), 1.0),
+            Err(QuantityError::VariantNotFound(7))
+        );
+        assert_eq!(
+            catalog::check_quantity(&paneer_product, None, 0.3),
+            Err(QuantityError::NotAStep(0.25))
+        );
+        assert_eq!(
+            catalog::check_quantity(&paneer_product, None, 0.75),
+            Ok(None)
+        );
+
+        // Packs of each variant are separate cart items, priced per pack
+        let mut cart = Cart {
+            phone_number: "+919876500002".to_string(),
+            ..Default::default()
+        };
+        cart::add_item(&mut cart, ghee, Some(0), 2.0, 0).unwrap();
+        cart::add_item(&mut cart, ghee, Some(1), 1.0, 0).unwrap();
+        assert!(matches!(
+            cart::add_item(&mut cart, ghee, Some(0), 0.5, 0),
+            Err(CartError::InvalidInput(_))
+        ));
+        let view = cart::view(&cart, 0);
+        assert_eq!(view.lines.len(), 2);
+        assert_eq!(
+            view.lines[0].variant_label.as_deref(),
+            Some("0.5 litre tin")
+        );
+        assert_eq!(view.total_amount, 1240.0);
+
+        // Stock is only taken if every variant has enough, and returned on cancellation
+        let item = |variant_id: u64, quantity: f64| OrderItem {
+            product_id: ghee,
+            quantity,
+            price_per_unit_at_order: 0.0,
+            price_list_id: None,
+            variant_id: Some(variant_id),
+        };
+        let stock = || store::get_product_by_id(ghee).unwrap().variants.unwrap()[0].stock;
+        catalog::take_stock(&[item(0, 2.0), item(1, 5.0)]).unwrap();
+        assert_eq!(stock(), Some(1));
+        assert!(matches!(
+            catalog::take_stock(&[item(0, 2.0)]),
+            Err(OrderError::OutOfStock { available: 1, .. })
+        ));
+        catalog::restock(&[item(0, 2.0)]);
+        assert_eq!(stock(), Some(3));
+    }
 
     // Add more tests for other functions
+
+    #[test]
+    fn order_edits_reprice_items_move_stock_and_are_recorded() {
+        let phone_number = "+919876500003";
+        store::update_user_profile(UserProfile {
+            phone_number: phone_number.to_string(),
+            name: "Asha".to_string(),
+            ..Default::default()
+        });
+        let ghee = store::add_product(Product {
+            name: "Ghee".to_string(),
+            price: 600.0,
+            unit: "litre".to_string(),
+            variants: Some(vec![ProductVariant {
+                id: 0,
+                sku: "GHEE-1000".to_string(),
+                label: "1 litre tin".to_string(),
+                pack_size: 1.0,
+                price: 600.0,
+                stock: Some(3),
+            }]),
+            ..Default::default()
+        })
+        .unwrap();
+        let paneer = store::add_product(Product {
+            name: "Paneer".to_string(),
+            price: 300.0,
+            unit: "kg".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        store::put_coupon(Coupon {
+            code: "FLAT50".to_string(),
+            description: "50 off orders from 500".to_string(),
+            discount: Discount::Flat(50.0),
+            scope: CouponScope::AllProducts,
+            valid_from: None,
+            valid_until: Some(100),
+            min_order_amount: Some(500.0),
+            max_uses: None,
+            max_uses_per_customer: Some(1),
+            enabled: true,
+        });
+
+        let items = vec![OrderItem {
+            product_id: ghee,
+            quantity: 2.0,
+            price_per_unit_at_order: 600.0,
+            price_list_id: None,
+            variant_id: Some(0),
+        }];
+        catalog::take_stock(&items).unwrap();
+        store::add_order(Order {
+            id: 0,
+            user_phone_number: phone_number.to_string(),
+            customer_name: "Asha".to_string(),
+            items,
+            total_amount: 1150.0,
+            status: OrderStatus::Pending,
+            timestamp: 50,
+            delivery_address: "Ward 4".to_string(),
+            delivery_address_id: None,
+            last_updated: 50,
+            discounts: Some(vec![OrderDiscount {
+                coupon_code: "FLAT50".to_string(),
+                description: "50 off orders from 500".to_string(),
+                amount: 50.0,
+                terms: Some(CouponTerms {
+                    discount: Discount::Flat(50.0),
+                    scope: CouponScope::AllProducts,
+                    min_order_amount: Some(500.0),
+                }),
+            }]),
+        })
+        .unwrap();
+        let input = |product_id: u64, variant_id: Option<u64>, quantity: f64| OrderItemInput {
+            product_id,
+            quantity,
+            variant_id,
+        };
+        let stock = || store::get_product_by_id(ghee).unwrap().variants.unwrap()[0].stock;
+
+        // The coupon has expired since, but still applies to the edited order
+        let order = edit_order(
+            store::get_order(0).unwrap(),
+            vec![input(ghee, Some(0), 1.0), input(paneer, None, 0.5)],
+            None,
+            None,
+            OrderEditor::Customer,
+            None,
+            200,
+        )
+        .unwrap();
+        assert_eq!(order.total_amount, 700.0);
+        assert_eq!(order.delivery_address, "Ward 4");
+        assert_eq!(stock(), Some(2));
+        let edit = &store::get_order_edits(0)[0];
+        assert_eq!(edit.previous_total_amount, 1150.0);
+        assert_eq!(edit.previous_items[0].quantity, 2.0);
+
+        // A failed edit changes neither the order nor the stock
+        assert!(matches!(
+            edit_order(
+                order,
+                vec![input(ghee, Some(0), 5.0)],
+                None,
+                None,
+                OrderEditor::Customer,
+                None,
+                250,
+            ),
+            Err(OrderError::OutOfStock { available: 3, .. })
+        ));
+        assert_eq!(stock(), Some(2));
+        assert_eq!(store::get_order(0).unwrap().total_amount, 700.0);
+
+        // Below the coupon's minimum the edit is refused rather than dropping the discount
+        let admin = OrderEditor::Admin(Principal::anonymous());
+        assert!(matches!(
+            edit_order(
+                store::get_order(0).unwrap(),
+                vec![input(paneer, None, 1.0)],
+                None,
+                None,
+                admin.clone(),
+                None,
+                260,
+            ),
+            Err(OrderError::InvalidCoupon(CouponError::MinimumOrderAmount(
+                _
+            )))
+        ));
+        assert_eq!(stock(), Some(2));
+        assert_eq!(store::get_order_edits(0).len(), 1);
+
+        // Changing the coupon later doesn't re-price the order's discount
+        store::put_coupon(Coupon {
+            code: "FLAT50".to_string(),
+            description: "200 off orders from 5000".to_string(),
+            discount: Discount::Flat(200.0),
+            scope: CouponScope::AllProducts,
+            valid_from: None,
+            valid_until: None,
+            min_order_amount: Some(5000.0),
+            max_uses: None,
+            max_uses_per_customer: None,
+            enabled: true,
+        });
+        let order = edit_order(
+            store::get_order(0).unwrap(),
+            vec![input(paneer, None, 2.0)],
+            Some("Shop 7, Ward 2".to_string()),
+            None,
+            admin.clone(),
+            Some("Customer called".to_string()),
+            300,
+        )
+        .unwrap();
+        assert_eq!(order.total_amount, 550.0);
+        assert_eq!(order.discounts.as_ref().unwrap()[0].amount, 50.0);
+        assert_eq!(order.delivery_address, "Shop 7, Ward 2");
+        assert_eq!(stock(), Some(3));
+        // Nor does removing it: the edits below still apply the recorded discount
+        store::remove_coupon("FLAT50");
+        let edits = store::get_order_edits(0);
+        assert_eq!(edits.len(), 2);
+        assert_eq!(edits[1].edited_by, admin);
+        assert_eq!(edits[1].previous_delivery_address, "Ward 4");
+
+        // The history is stored apart from the order, so repeated edits don't grow it
+        for round in 0..50 {
+            edit_order(
+                store::get_order(0).unwrap(),
+                vec![input(paneer, None, 2.0 + round as f64)],
+                None,
+                None,
+                OrderEditor::Customer,
+                None,
+                400 + round,
+            )
+            .unwrap();
+        }
+        assert_eq!(store::get_order_edits(0).len(), 52);
+        // An order beyond the size bound is refused instead of trapping
+        let too_many_items = vec![input(paneer, None, 1.0); 200];
+        assert!(matches!(
+            edit_order(
+                store::get_order(0).unwrap(),
+                too_many_items,
+                None,
+                None,
+                OrderEditor::Customer,
+                None,
+                500,
+            ),
+            Err(OrderError::StorageError(_))
+        ));
+        assert_eq!(store::get_order_edits(0).len(), 52);
+
+        // Anonymising the customer scrubs the history too
+        store::update_order_status(0, OrderStatus::Cancelled, 600).unwrap();
+        let tombstone = anonymise_customer(&phone_number.to_string(), 600)
+            .unwrap()
+            .phone_number;
+        assert!(store::get_order_edits(0)
+            .iter()
+            .all(|edit| edit.reason.is_none()
+                && edit.previous_items.is_empty()
+                && edit.previous_delivery_address == tombstone));
+    }
+
+    #[test]
+    fn status_notifications_are_queued_and_retried_with_backoff() {
+        const SECOND: u64 = date::NANOS_PER_SECOND;
+        let settings = NotificationSettings {
+            enabled: true,
+            webhook_url: Some("https://hooks.example.com/orders".to_string()),
+            max_attempts: 3,
+            base_backoff_seconds: 60,
+            ..Default::default()
+        };
+        store::set_notification_settings(settings.clone()).unwrap();
+        for (phone_number, opt_out) in [("+919876500001", None), ("+919876500002", Some(true))] {
+            store::update_user_profile(UserProfile {
+                phone_number: phone_number.to_string(),
+                name: "Asha".to_string(),
+                notifications_opt_out: opt_out,
+                ..Default::default()
+            });
+        }
+        let order = |id: u64, phone_number: &str| Order {
+            id,
+            user_phone_number: phone_number.to_string(),
+            customer_name: "Asha".to_string(),
+            items: Vec::new(),
+            total_amount: 120.0,
+            status: OrderStatus::Confirmed,
+            timestamp: 0,
+            delivery_address: "Ward 4".to_string(),
+            delivery_address_id: None,
+            last_updated: 0,
+            discounts: None,
+        };
+
+        let queued =
+            notifications::queue_status_change(&order(7, "+919876500001"), SECOND).unwrap();
+        assert_eq!(queued.order_id, 7);
+        assert_eq!(
+            queued.message,
+            "Hello Asha, your order #7 (Rs. 120.00) is confirmed."
+        );
+        assert_eq!(store::get_due_notifications(SECOND, 10).len(), 1);
+        // Opted out customers get nothing
+        assert!(notifications::queue_status_change(&order(8, "+919876500002"), SECOND).is_none());
+
+        // Every replica delivers, so every channel carries the notification's idempotency key
+        let key = notifications::idempotency_key(&queued);
+        assert_eq!(key, format!("notification:{}", queued.id));
+        for channel in [
+            NotificationChannel::WhatsApp,
+            NotificationChannel::Sms,
+            NotificationChannel::GenericJson,
+        ] {
+            let settings = NotificationSettings {
+                channel,
+                ..settings.clone()
+            };
+            let payload = notifications::payload(&settings, &queued);
+            assert!(payload.contains(&format!(r#""{}""#, key)), "{}", payload);
+        }
+
+        // Failed deliveries back off exponentially and give up after max_attempts
+        let mut notification = queued;
+        notifications::record_attempt(&mut notification, &settings, Err("503".to_string()), SECOND);
+        assert_eq!(notification.next_attempt_at, 61 * SECOND);
+        assert_eq!(notification.state, NotificationState::Pending);
+        store::put_notification(notification.clone());
+        assert_eq!(store::get_failed_notifications().len(), 1);
+        assert!(store::get_due_notifications(60 * SECOND, 10).is_empty());
+        notifications::record_attempt(
+            &mut notification,
+            &settings,
+            Err("503".to_string()),
+            61 * SECOND,
+        );
+        assert_eq!(notification.next_attempt_at, 181 * SECOND);
+        notifications::record_attempt(
+            &mut notification,
+            &settings,
+            Err("503".to_string()),
+            181 * SECOND,
+        );
+        assert_eq!(notification.state, NotificationState::Failed);
+        assert_eq!(notification.attempts, 3);
+        store::put_notification(notification.clone());
+        assert_eq!(store::next_notification_due_at(), None);
+
+        notifications::record_attempt(&mut notification, &settings, Ok(()), 200 * SECOND);
+        assert_eq!(
+            notification.state,
+            NotificationState::Delivered { at: 200 * SECOND }
+        );
+        assert_eq!(notification.last_error, None);
+        store::put_notification(notification);
+        assert!(store::get_failed_notifications().is_empty());
+
+        // Disabled notifications queue nothing
+        store::set_notification_settings(NotificationSettings::default()).unwrap();
+        assert!(notifications::queue_status_change(&order(9, "+919876500001"), SECOND).is_none());
+    }
+
+    #[test]
+    fn migrating_phone_numbers_rekeys_every_store() {
+        let legacy = "098765 00001";
+        let normalized = "+919876500001";
+        store::update_user_profile(UserProfile {
+            phone_number: legacy.to_string(),
+            name: "Asha".to_string(),
+            ..Default::default()
+        });
+        store::put_cart(Cart {
+            phone_number: legacy.to_string(),
+            items: vec![CartItem {
+                product_id: 0,
+                quantity: 1.0,
+                price_per_unit: 70.0,
+                variant_id: None,
+            }],
+            updated_at: 1,
+        });
+        let challenge = |phone_number: &str, purpose: OtpPurpose| OtpChallenge {
+            phone_number: phone_number.to_string(),
+            purpose,
+            code_hash: Vec::new(),
+            salt: Vec::new(),
+            created_at: 1,
+            expires_at: 100,
+            attempts_left: 3,
+        };
+        store::put_otp_challenge(challenge(legacy, OtpPurpose::VerifyProfile));
+        store::put_otp_challenge(challenge(
+            "+919876500006",
+            OtpPurpose::ChangePhoneNumber {
+                current_phone_number: legacy.to_string(),
+            },
+        ));
+        store::put_otp_lockout(OtpLockout {
+            phone_number: legacy.to_string(),
+            lockouts: 1,
+            locked_until: 100,
+        });
+        store::set_phone_redirect("098765 00005", legacy);
+        store::add_phone_change_request(PhoneChangeRequest {
+            current_phone_number: legacy.to_string(),
+            new_phone_number: "098765 00006".to_string(),
+            requested_at: 1,
+        });
+        store::put_notification(Notification {
+            id: 0,
+            order_id: 0,
+            phone_number: legacy.to_string(),
+            status: OrderStatus::Confirmed,
+            message: "Hello Asha".to_string(),
+            created_at: 1,
+            state: NotificationState::Pending,
+            attempts: 0,
+            next_attempt_at: 1,
+            last_error: None,
+            event: None,
+        });
+
+        let report = phone::migrate_stored_phone_numbers(&PhoneSettings::default());
+        assert_eq!(report.profiles_rewritten, 1);
+        assert!(store::get_user_profile(&normalized.to_string()).is_some());
+        assert!(store::get_cart(legacy).is_none());
+        assert!(store::get_cart(normalized).is_some());
+        // A code hashed with the old key can't be entered any more, so it is dropped
+        assert!(store::get_otp_challenge(legacy).is_none());
+        assert!(store::get_otp_challenge(normalized).is_none());
+        assert_eq!(
+            store::get_otp_challenge("+919876500006").unwrap().purpose,
+            OtpPurpose::ChangePhoneNumber {
+                current_phone_number: normalized.to_string()
+            }
+        );
+        assert!(store::get_otp_lockout(legacy).is_none());
+        assert_eq!(
+            store::get_otp_lockout(normalized).unwrap().locked_until,
+            100
+        );
+        assert_eq!(
+            store::get_all_phone_redirects(),
+            vec![("+919876500005".to_string(), normalized.to_string())]
+        );
+        assert!(store::get_phone_change_request(legacy).is_none());
+        assert_eq!(
+            store::get_phone_change_request(normalized)
+                .unwrap()
+                .new_phone_number,
+            "+919876500006"
+        );
+        assert_eq!(store::get_notification(0).unwrap().phone_number, normalized);
+
+        // Running it again changes nothing
+        let report = phone::migrate_stored_phone_numbers(&PhoneSettings::default());
+        assert_eq!((report.profiles_rewritten, report.orders_rewritten), (0, 0));
+        assert_eq!(store::get_all_phone_redirects().len(), 1);
+        assert!(store::get_otp_challenge("+919876500006").is_some());
+    }
+
+    #[test]
+    fn anonymising_a_customer_scrubs_every_store() {
+        let phone_number = "+919876500001".to_string();
+        let old_number = "+919876500009".to_string();
+        store::update_user_profile(UserProfile {
+            phone_number: phone_number.clone(),
+            name: "Asha".to_string(),
+            address: "Ward 4".to_string(),
+            order_ids: vec![0],
+            verified_at: Some(1),
+            ..Default::default()
+        });
+        let mut order = Order {
+            id: 0,
+            user_phone_number: phone_number.clone(),
+            customer_name: "Asha".to_string(),
+            items: Vec::new(),
+            total_amount: 120.0,
+            status: OrderStatus::OutForDelivery,
+            timestamp: 0,
+            delivery_address: "Ward 4".to_string(),
+            delivery_address_id: None,
+            last_updated: 0,
+            discounts: None,
+        };
+        store::add_order(order.clone()).unwrap();
+        assert!(matches!(
+            anonymise_customer(&phone_number, 10),
+            Err(DataDeletionError::HasActiveOrders(ids)) if ids == vec![0]
+        ));
+
+        order.status = OrderStatus::Delivered;
+        store::add_order(order.clone()).unwrap();
+        store::set_notification_settings(NotificationSettings {
+            enabled: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let mut delivered = notifications::queue_status_change(&order, 5).unwrap();
+        delivered.state = NotificationState::Delivered { at: 6 };
+        store::put_notification(delivered.clone());
+        let pending = notifications::queue_status_change(&order, 7).unwrap();
+        store::put_cart(Cart {
+            phone_number: phone_number.clone(),
+            items: vec![CartItem {
+                product_id: 0,
+                quantity: 1.0,
+                price_per_unit: 120.0,
+                variant_id: None,
+            }],
+            ..Default::default()
+        });
+        store::add_deletion_request(DeletionRequest {
+            phone_number: phone_number.clone(),
+            requested_at: 8,
+        });
+        store::add_phone_change_request(PhoneChangeRequest {
+            current_phone_number: phone_number.clone(),
+            new_phone_number: "+919876500002".to_string(),
+            requested_at: 8,
+        });
+        store::put_otp_challenge(OtpChallenge {
+            phone_number: phone_number.clone(),
+            purpose: OtpPurpose::VerifyProfile,
+            code_hash: Vec::new(),
+            salt: Vec::new(),
+            created_at: 8,
+            expires_at: 100,
+            attempts_left: 3,
+        });
+        store::set_phone_redirect(&old_number, &phone_number);
+
+        let anonymised = anonymise_customer(&phone_number, 10).unwrap();
+        let tombstone = anonymised.phone_number.clone();
+        assert!(tombstone.starts_with("deleted-"));
+        assert_eq!(anonymised.name, tombstone);
+        assert_eq!(anonymised.address, tombstone);
+        assert_eq!(anonymised.verified_at, None);
+        assert_eq!(anonymised.deleted_at, Some(10));
+        assert_eq!(anonymised.order_ids, vec![0]);
+        assert!(store::get_user_profile(&phone_number).is_none());
+        assert!(store::get_user_profile(&tombstone).is_some());
+
+        let order = store::get_order(0).unwrap();
+        assert_eq!(order.user_phone_number, tombstone);
+        assert_eq!(order.customer_name, tombstone);
+        assert_eq!(order.delivery_address, tombstone);
+        assert!(store::get_orders_by_phone(&phone_number).is_empty());
+
+        assert!(store::get_notification(pending.id).is_none());
+        let delivered = store::get_notification(delivered.id).unwrap();
+        assert_eq!(delivered.phone_number, tombstone);
+        assert!(!delivered.message.contains("Asha"));
+
+        assert!(store::get_cart(&phone_number).is_none());
+        assert!(store::get_deletion_requests().is_empty());
+        assert!(store::get_phone_change_requests().is_empty());
+        assert!(store::get_otp_challenge(&phone_number).is_none());
+        assert!(store::get_phone_redirect(&old_number).is_none());
+
+        assert!(matches!(
+            anonymise_customer(&tombstone, 11),
+            Err(DataDeletionError::AlreadyAnonymised)
+        ));
+    }
+
+    #[test]
+    fn orders_are_delivered_to_saved_or_explicit_addresses() {
+        let phone_number = "+919876500001";
+        store::update_user_profile(UserProfile {
+            phone_number: phone_number.to_string(),
+            name: "Asha".to_string(),
+            ..Default::default()
+        });
+        let input = |label: &str, line: &str| AddressInput {
+            label: label.to_string(),
+            address_lines: vec![line.to_string()],
+            landmark: None,
+            zone: Some("Ward 4".to_string()),
+            geo: None,
+        };
+        let home = add_address("98765 00001".to_string(), input("Home", "12 MG Road")).unwrap();
+        let shop = add_address(phone_number.to_string(), input("Shop", "3 Bazaar")).unwrap();
+        assert_ne!(home.id, shop.id);
+        let shop = update_address(
+            phone_number.to_string(),
+            shop.id,
+            input("Shop", "5 Bazaar Lane"),
+        )
+        .unwrap();
+        assert_eq!(shop.address_lines, vec!["5 Bazaar Lane".to_string()]);
+        assert!(matches!(
+            update_address(
+                phone_number.to_string(),
+                99,
+                input("Office", "1 Station Road")
+            ),
+            Err(AddressError::AddressNotFound(99))
+        ));
+        let profile = store::get_user_profile(&phone_number.to_string()).unwrap();
+        assert_eq!(profile.default_address_id, Some(home.id));
+
+        let paneer = store::add_product(Product {
+            name: "Paneer".to_string(),
+            price: 300.0,
+            unit: "kg".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let items = || {
+            vec![OrderItemInput {
+                product_id: paneer,
+                quantity: 1.0,
+                variant_id: None,
+            }]
+        };
+        let place = |delivery_address: &str, address_id: Option<u64>| {
+            place_order(
+                phone_number.to_string(),
+                items(),
+                delivery_address.to_string(),
+                address_id,
+                None,
+                100,
+            )
+        };
+
+        // A saved address wins over the explicit one
+        let order_id = place("ignored", Some(shop.id)).unwrap();
+        let order = store::get_order(order_id).unwrap();
+        assert_eq!(order.delivery_address, "5 Bazaar Lane, Ward 4");
+        assert_eq!(order.delivery_address_id, Some(shop.id));
+
+        let order_id = place("Stall 9, Sabzi Mandi", None).unwrap();
+        let order = store::get_order(order_id).unwrap();
+        assert_eq!(order.delivery_address, "Stall 9, Sabzi Mandi");
+        assert_eq!(order.delivery_address_id, None);
+
+        assert!(matches!(
+            place("", Some(42)),
+            Err(OrderError::AddressNotFound(42))
+        ));
+        assert!(matches!(place(" ", None), Err(OrderError::InvalidInput(_))));
+
+        // Removing the default address makes the next one the default
+        remove_address(phone_number.to_string(), home.id).unwrap();
+        let profile = store::get_user_profile(&phone_number.to_string()).unwrap();
+        assert_eq!(profile.default_address_id, Some(shop.id));
+        assert_eq!(profile.addresses.unwrap().len(), 1);
+        assert!(matches!(
+            remove_address(phone_number.to_string(), home.id),
+            Err(AddressError::AddressNotFound(_))
+        ));
+        assert!(matches!(
+            place("", Some(home.id)),
+            Err(OrderError::AddressNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn phone_number_changes_rekey_the_customer_and_redirect_old_numbers() {
+        let (first, second, third) = (
+            "+919876500001".to_string(),
+            "+919876500002".to_string(),
+            "+919876500003".to_string(),
+        );
+        store::update_user_profile(UserProfile {
+            phone_number: first.clone(),
+            name: "Asha".to_string(),
+            order_ids: vec![0],
+            verified_at: Some(1),
+            ..Default::default()
+        });
+        store::update_user_profile(UserProfile {
+            phone_number: "+919876500004".to_string(),
+            name: "Ravi".to_string(),
+            ..Default::default()
+        });
+        let order = Order {
+            id: 0,
+            user_phone_number: first.clone(),
+            customer_name: "Asha".to_string(),
+            items: Vec::new(),
+            total_amount: 120.0,
+            status: OrderStatus::Pending,
+            timestamp: 0,
+            delivery_address: "Ward 4".to_string(),
+            delivery_address_id: None,
+            last_updated: 0,
+            discounts: None,
+        };
+        store::add_order(order.clone()).unwrap();
+        store::put_cart(Cart {
+            phone_number: first.clone(),
+            items: vec![CartItem {
+                product_id: 0,
+                quantity: 1.0,
+                price_per_unit: 120.0,
+                variant_id: None,
+            }],
+            ..Default::default()
+        });
+        store::add_deletion_request(DeletionRequest {
+            phone_number: first.clone(),
+            requested_at: 5,
+        });
+        store::set_notification_settings(NotificationSettings {
+            enabled: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let notification = notifications::queue_status_change(&order, 5).unwrap();
+
+        let profile = change_customer_phone_number(&first, &second).unwrap();
+        assert_eq!(profile.phone_number, second);
+        assert_eq!(profile.verified_at, None);
+        assert!(store::get_user_profile(&first).is_none());
+        assert_eq!(store::get_order(0).unwrap().user_phone_number, second);
+        assert!(store::get_cart(&first).is_none());
+        assert!(store::get_cart(&second).is_some());
+        assert_eq!(store::get_deletion_requests()[0].phone_number, second);
+        assert_eq!(
+            store::get_notification(notification.id)
+                .unwrap()
+                .phone_number,
+            second
+        );
+        // The old number keeps working for the customer
+        assert_eq!(resolve_customer_phone(&first), Ok(second.clone()));
+        assert_eq!(get_my_orders(first.clone()).unwrap().len(), 1);
+
+        // A second change rewrites the first redirect instead of chaining it
+        change_customer_phone_number(&second, &third).unwrap();
+        assert_eq!(store::get_phone_redirect(&first), Some(third.clone()));
+        assert_eq!(store::get_phone_redirect(&second), Some(third.clone()));
+        assert_eq!(resolve_customer_phone(&first), Ok(third.clone()));
+        assert_eq!(store::get_order(0).unwrap().user_phone_number, third);
+
+        assert!(matches!(
+            change_customer_phone_number(&third, &third),
+            Err(PhoneChangeError::SameNumber)
+        ));
+        assert!(matches!(
+            change_customer_phone_number(&third, &"+919876500004".to_string()),
+            Err(PhoneChangeError::NumberAlreadyInUse(_))
+        ));
+        assert!(matches!(
+            change_customer_phone_number(&first, &"+919876500005".to_string()),
+            Err(PhoneChangeError::ProfileNotFound)
+        ));
+    }
 }
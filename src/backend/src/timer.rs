@@ -0,0 +1,50 @@
+use crate::{jobs, notifications, pricing};
+use ic_cdk::api::time;
+use ic_cdk_timers::TimerId;
+use std::{cell::Cell, time::Duration};
+
+// The pending timer and the time it fires at (nanoseconds since epoch).
+// Not persisted: timers are cleared on upgrade and re-armed in post_upgrade.
+thread_local! {
+    static ARMED: Cell<Option<(TimerId, u64)>> = const { Cell::new(None) };
+}
+
+/// Makes sure background work runs no later than `timestamp`.
+pub fn schedule_at(timestamp: u64) {
+    ARMED.with(|armed| {
+        if let Some((timer_id, at)) = armed.get() {
+            if at <= timestamp {
+                return;
+            }
+            ic_cdk_timers::clear_timer(timer_id);
+        }
+        let delay = Duration::from_nanos(timestamp.saturating_sub(time()));
+        let timer_id = ic_cdk_timers::set_timer(delay, run_due_work);
+        armed.set(Some((timer_id, timestamp)));
+    })
+}
+
+/// Arms the timer for everything that is already pending, e.g. after an upgrade.
+pub fn rearm() {
+    let due_at = [
+        notifications::next_due_at(),
+        jobs::next_due_at(),
+        pricing::next_price_change_at(time()),
+    ]
+    .into_iter()
+    .flatten()
+    .min();
+    if let Some(due_at) = due_at {
+        schedule_at(due_at);
+    }
+}
+
+fn run_due_work() {
+    ARMED.with(|armed| armed.set(None));
+    pricing::apply_due_price_changes(time());
+    jobs::run_due_jobs(time());
+    ic_cdk::spawn(async {
+        notifications::process_outbox().await;
+        rearm();
+    });
+}
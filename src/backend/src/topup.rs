@@ -0,0 +1,315 @@
+// Cycles top-ups paid in ICP.
+//
+// A top-up transfers ICP from the backend's ledger account to the cycles minting canister
+// (CMC), in the CMC subaccount of the canister to be topped up, and then calls the CMC's
+// `notify_top_up` to mint the cycles. The ICP has left the account once the transfer
+// succeeds, so a failed notify keeps the block index and can be retried.
+use crate::{
+    audit,
+    errors::{FieldError, FieldErrorKind, TopUpError},
+    models::{TopUp, TopUpSettings, TopUpState},
+    store,
+};
+use candid::{CandidType, Deserialize, Nat, Principal};
+use sha2::{Digest, Sha224};
+
+// Memo the CMC expects on top-up transfers ("TPUP")
+const MEMO_TOP_UP_CANISTER: u64 = 0x5055_5054;
+pub const LEDGER_FEE_E8S: u64 = 10_000;
+pub const MIN_TOP_UP_E8S: u64 = 1_000_000;
+// An automatic top-up isn't repeated for the same canister within this time
+const AUTO_TOP_UP_COOLDOWN_SECONDS: u64 = 6 * 60 * 60;
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+struct Tokens {
+    e8s: u64,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+struct TimeStamp {
+    timestamp_nanos: u64,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+struct TransferArgs {
+    memo: u64,
+    amount: Tokens,
+    fee: Tokens,
+    from_subaccount: Option<Vec<u8>>,
+    to: Vec<u8>,
+    created_at_time: Option<TimeStamp>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+enum TransferError {
+    BadFee { expected_fee: Tokens },
+    InsufficientFunds { balance: Tokens },
+    TxTooOld { allowed_window_nanos: u64 },
+    TxCreatedInFuture,
+    TxDuplicate { duplicate_of: u64 },
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+struct NotifyTopUpArg {
+    block_index: u64,
+    canister_id: Principal,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+enum NotifyError {
+    Refunded {
+        reason: String,
+        block_index: Option<u64>,
+    },
+    Processing,
+    TransactionTooOld(u64),
+    InvalidTransaction(String),
+    Other {
+        error_code: u64,
+        error_message: String,
+    },
+}
+
+/// Subaccount derived from a principal: its length followed by its bytes.
+pub fn principal_to_subaccount(principal: Principal) -> [u8; 32] {
+    let bytes = principal.as_slice();
+    let mut subaccount = [0u8; 32];
+    subaccount[0] = bytes.len() as u8;
+    subaccount[1..=bytes.len()].copy_from_slice(bytes);
+    subaccount
+}
+
+/// Ledger account identifier: CRC32 of the SHA-224 hash, followed by the hash itself.
+pub fn account_identifier(owner: Principal, subaccount: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha224::new();
+    hasher.update(b"\x0Aaccount-id");
+    hasher.update(owner.as_slice());
+    hasher.update(subaccount);
+    let hash = hasher.finalize();
+    let mut account = [0u8; 32];
+    account[..4].copy_from_slice(&crc32fast::hash(&hash).to_be_bytes());
+    account[4..].copy_from_slice(&hash);
+    account
+}
+
+/// Checks a top-up request before any ICP is moved.
+pub fn validate(
+    canister_id: Principal,
+    icp_e8s: u64,
+    settings: &TopUpSettings,
+) -> Result<(), TopUpError> {
+    if !store::get_monitored_canisters()
+        .iter()
+        .any(|canister| canister.canister_id == canister_id)
+    {
+        return Err(TopUpError::NotMonitored(canister_id));
+    }
+    if icp_e8s < MIN_TOP_UP_E8S || icp_e8s > settings.max_top_up_e8s {
+        return Err(TopUpError::InvalidInput(vec![FieldError::new(
+            "icp_e8s",
+            FieldErrorKind::OutOfRange,
+            format!(
+                "The amount must be between {} and {} e8s.",
+                MIN_TOP_UP_E8S, settings.max_top_up_e8s
+            ),
+        )]));
+    }
+    Ok(())
+}
+
+/// Checks the top-up settings before they are stored.
+pub fn validate_settings(settings: &TopUpSettings) -> Result<(), TopUpError> {
+    let mut errors = Vec::new();
+    if settings.max_top_up_e8s < MIN_TOP_UP_E8S {
+        errors.push(FieldError::new(
+            "max_top_up_e8s",
+            FieldErrorKind::OutOfRange,
+            format!(
+                "The maximum top-up must be at least {} e8s.",
+                MIN_TOP_UP_E8S
+            ),
+        ));
+    } else if !(MIN_TOP_UP_E8S..=settings.max_top_up_e8s).contains(&settings.auto_top_up_e8s) {
+        errors.push(FieldError::new(
+            "auto_top_up_e8s",
+            FieldErrorKind::OutOfRange,
+            "The automatic top-up amount must be within the allowed range.",
+        ));
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(TopUpError::InvalidInput(errors))
+    }
+}
+
+/// Converts `icp_e8s` (plus the ledger fee) from the backend's account into cycles for
+/// `canister_id`. Fails without moving ICP if the transfer is rejected; if only minting
+/// fails the top-up stays `Transferred` for `retry_notify`.
+pub async fn top_up(
+    canister_id: Principal,
+    icp_e8s: u64,
+    automatic: bool,
+    requested_by: Principal,
+    now: u64,
+) -> Result<TopUp, TopUpError> {
+    let settings = store::get_top_up_settings();
+    validate(canister_id, icp_e8s, &settings)?;
+
+    let mut top_up = TopUp {
+        id: store::get_next_top_up_id(),
+        canister_id,
+        icp_e8s,
+        automatic,
+        requested_by,
+        created_at: now,
+        state: TopUpState::Pending,
+        last_error: None,
+    };
+    store::put_top_up(top_up.clone());
+
+    let args = TransferArgs {
+        memo: MEMO_TOP_UP_CANISTER,
+        amount: Tokens { e8s: icp_e8s },
+        fee: Tokens {
+            e8s: LEDGER_FEE_E8S,
+        },
+        from_subaccount: None,
+        to: account_identifier(
+            settings.cmc_canister_id,
+            &principal_to_subaccount(canister_id),
+        )
+        .to_vec(),
+        created_at_time: Some(TimeStamp {
+            timestamp_nanos: now,
+        }),
+    };
+    let transfer: Result<(Result<u64, TransferError>,), _> =
+        ic_cdk::call(settings.ledger_canister_id, "transfer", (args,)).await;
+    top_up.state = match transfer {
+        Ok((Ok(block_index),)) => TopUpState::Transferred { block_index },
+        Ok((Err(e),)) => TopUpState::Failed(format!("Transfer rejected: {:?}", e)),
+        Err((code, msg)) => TopUpState::Failed(format!(
+            "Ledger call failed: code={:?}, message={}",
+            code, msg
+        )),
+    };
+    store::put_top_up(top_up.clone());
+    if let TopUpState::Failed(_) = &top_up.state {
+        return Err(failure(&top_up));
+    }
+
+    let top_up = notify(top_up, &settings).await;
+    match &top_up.state {
+        TopUpState::Completed { .. } => Ok(top_up),
+        _ => Err(failure(&top_up)),
+    }
+}
+
+/// Asks the CMC to mint the cycles for a transferred top-up. Errors the CMC may recover
+/// from leave the top-up `Transferred`; refunds and invalid transactions fail it.
+async fn notify(mut top_up: TopUp, settings: &TopUpSettings) -> TopUp {
+    let TopUpState::Transferred { block_index } = top_up.state else {
+        return top_up;
+    };
+    let arg = NotifyTopUpArg {
+        block_index,
+        canister_id: top_up.canister_id,
+    };
+    let result: Result<(Result<Nat, NotifyError>,), _> =
+        ic_cdk::call(settings.cmc_canister_id, "notify_top_up", (arg,)).await;
+    match result {
+        Ok((Ok(cycles),)) => {
+            top_up.state = TopUpState::Completed {
+                block_index,
+                cycles: cycles.0.try_into().unwrap_or(u128::MAX),
+            };
+            top_up.last_error = None;
+        }
+        Ok((Err(NotifyError::Refunded { reason, .. }),)) => {
+            top_up.state = TopUpState::Failed(format!("Refunded by the CMC: {}", reason));
+        }
+        Ok(
+            (Err(e @ (NotifyError::TransactionTooOld(_) | NotifyError::InvalidTransaction(_))),),
+        ) => {
+            top_up.state = TopUpState::Failed(format!("Rejected by the CMC: {:?}", e));
+        }
+        Ok((Err(e),)) => top_up.last_error = Some(format!("{:?}", e)),
+        Err((code, msg)) => {
+            top_up.last_error = Some(format!("CMC call failed: code={:?}, message={}", code, msg))
+        }
+    }
+    store::put_top_up(top_up.clone());
+    top_up
+}
+
+fn failure(top_up: &TopUp) -> TopUpError {
+    match &top_up.state {
+        TopUpState::Failed(reason) => TopUpError::Failed {
+            top_up_id: top_up.id,
+            reason: reason.clone(),
+        },
+        TopUpState::Transferred { block_index } => TopUpError::NotMinted {
+            top_up_id: top_up.id,
+            block_index: *block_index,
+            reason: top_up
+                .last_error
+                .clone()
+                .unwrap_or("unknown error".to_string()),
+        },
+        _ => TopUpError::Failed {
+            top_up_id: top_up.id,
+            reason: "The top-up did not complete.".to_string(),
+        },
+    }
+}
+
+/// Repeats the CMC notification of a top-up whose ICP was transferred.
+pub async fn retry_notify(id: u64) -> Result<TopUp, TopUpError> {
+    let top_up = store::get_top_up(id).ok_or(TopUpError::TopUpNotFound(id))?;
+    if !matches!(top_up.state, TopUpState::Transferred { .. }) {
+        return Err(TopUpError::NotRetryable(id));
+    }
+    let top_up = notify(top_up, &store::get_top_up_settings()).await;
+    match &top_up.state {
+        TopUpState::Completed { .. } => Ok(top_up),
+        _ => Err(failure(&top_up)),
+    }
+}
+
+/// Whether an automatic top-up is due for a canister holding `cycles`.
+pub fn auto_top_up_due(
+    settings: &TopUpSettings,
+    canister_id: Principal,
+    cycles: u128,
+    now: u64,
+) -> bool {
+    if !settings.auto_top_up || cycles >= settings.min_cycles_balance {
+        return false;
+    }
+    let cooldown_start =
+        now.saturating_sub(AUTO_TOP_UP_COOLDOWN_SECONDS * crate::date::NANOS_PER_SECOND);
+    !store::get_top_ups().iter().any(|top_up| {
+        top_up.canister_id == canister_id && top_up.automatic && top_up.created_at >= cooldown_start
+    })
+}
+
+/// Tops up a canister from the cycles monitor when its balance is below the threshold.
+/// Recorded in the audit log with the backend itself as the caller.
+pub async fn auto_top_up(canister_id: Principal, cycles: u128, now: u64) {
+    let settings = store::get_top_up_settings();
+    if !auto_top_up_due(&settings, canister_id, cycles, now) {
+        return;
+    }
+    let self_id = ic_cdk::id();
+    let result = top_up(canister_id, settings.auto_top_up_e8s, true, self_id, now).await;
+    audit::record_for(
+        self_id,
+        "auto_top_up",
+        format!(
+            "canister={}, icp_e8s={}, cycles={}",
+            canister_id, settings.auto_top_up_e8s, cycles
+        ),
+        &result,
+    );
+}
@@ -0,0 +1,204 @@
+use crate::{
+    errors::OtpError,
+    json,
+    models::{OtpChallenge, OtpLockout, OtpPurpose, VerificationSettings},
+    outcall, store,
+};
+use ic_cdk::api::management_canister::{http_request::HttpHeader, main::raw_rand};
+use sha2::{Digest, Sha256};
+use std::{cell::RefCell, collections::BTreeMap};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const SALT_LEN: usize = 16;
+// A number that used up the attempts of a code can't request another one for this long,
+// doubling with every further lockout until a code is entered correctly
+const BASE_LOCKOUT_SECONDS: u64 = 15 * 60;
+const MAX_LOCKOUT_SECONDS: u64 = 24 * 60 * 60;
+
+thread_local! {
+    // Codes being generated and sent: phone number -> when the send was requested. Kept on
+    // the heap only; calls in flight don't survive an upgrade.
+    static SENDING: RefCell<BTreeMap<String, u64>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Hashes a code together with its salt and phone number, so a leaked hash can't be
+/// brute-forced once and reused for other numbers.
+pub fn hash_code(salt: &[u8], phone_number: &str, code: &str) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(phone_number.as_bytes());
+    hasher.update(code.as_bytes());
+    hasher.finalize().to_vec()
+}
+
+/// Issues a fresh 6-digit code for `phone_number`, stores its hash and sends it by SMS.
+pub async fn issue_code(
+    phone_number: String,
+    purpose: OtpPurpose,
+    now: u64,
+) -> Result<(), OtpError> {
+    let settings = store::get_verification_settings();
+    let endpoint_url = settings
+        .sms_endpoint_url
+        .clone()
+        .ok_or(OtpError::NotConfigured)?;
+
+    reserve_send(&phone_number, &settings, now)?;
+    let (random_bytes,) = match raw_rand().await {
+        Ok(bytes) => bytes,
+        Err((code, msg)) => {
+            release_send(&phone_number);
+            return Err(OtpError::RandomnessUnavailable(format!(
+                "{:?}: {}",
+                code, msg
+            )));
+        }
+    };
+    let number = u32::from_le_bytes(random_bytes[0..4].try_into().unwrap()) % 1_000_000;
+    let code = format!("{:06}", number);
+    let salt = random_bytes[4..4 + SALT_LEN].to_vec();
+
+    store::put_otp_challenge(OtpChallenge {
+        phone_number: phone_number.clone(),
+        purpose,
+        code_hash: hash_code(&salt, &phone_number, &code),
+        salt,
+        created_at: now,
+        expires_at: now + settings.code_ttl_seconds * NANOS_PER_SECOND,
+        attempts_left: settings.max_attempts,
+    });
+    // The stored challenge throttles further requests from here on
+    release_send(&phone_number);
+
+    if let Err(e) = send_sms(&settings, &endpoint_url, &phone_number, &code, now).await {
+        store::remove_otp_challenge(&phone_number);
+        return Err(OtpError::SendFailed(e));
+    }
+    Ok(())
+}
+
+/// Claims the right to send a code to `phone_number` at `now`, unless the number is locked
+/// out or a code was sent or requested less than `resend_interval_seconds` ago. Claimed before
+/// the first await, so concurrent requests can't both pass the throttle.
+pub fn reserve_send(
+    phone_number: &str,
+    settings: &VerificationSettings,
+    now: u64,
+) -> Result<(), OtpError> {
+    if let Some(lockout) = store::get_otp_lockout(phone_number) {
+        if now < lockout.locked_until {
+            return Err(OtpError::TooManyRequests {
+                retry_after_seconds: (lockout.locked_until - now).div_ceil(NANOS_PER_SECOND),
+            });
+        }
+    }
+    let last_sent_at = SENDING
+        .with(|sending| sending.borrow().get(phone_number).copied())
+        .into_iter()
+        .chain(store::get_otp_challenge(phone_number).map(|existing| existing.created_at))
+        .max();
+    if let Some(last_sent_at) = last_sent_at {
+        let resend_at = last_sent_at + settings.resend_interval_seconds * NANOS_PER_SECOND;
+        if now < resend_at {
+            return Err(OtpError::TooManyRequests {
+                retry_after_seconds: (resend_at - now).div_ceil(NANOS_PER_SECOND),
+            });
+        }
+    }
+    SENDING.with(|sending| sending.borrow_mut().insert(phone_number.to_string(), now));
+    Ok(())
+}
+
+fn release_send(phone_number: &str) {
+    SENDING.with(|sending| sending.borrow_mut().remove(phone_number));
+}
+
+/// Consumes a challenge whose attempts are used up and locks the number out of new codes.
+fn lock_out(phone_number: &str, now: u64) {
+    store::remove_otp_challenge(phone_number);
+    let lockouts = store::get_otp_lockout(phone_number).map_or(0, |lockout| lockout.lockouts) + 1;
+    let seconds = BASE_LOCKOUT_SECONDS
+        .saturating_mul(1u64 << (lockouts - 1).min(16))
+        .min(MAX_LOCKOUT_SECONDS);
+    store::put_otp_lockout(OtpLockout {
+        phone_number: phone_number.to_string(),
+        lockouts,
+        locked_until: now + seconds * NANOS_PER_SECOND,
+    });
+}
+
+/// Sends the code by SMS. Every replica sends the request, so it carries an idempotency key
+/// (the phone number and the challenge's `created_at`) in the body and in an
+/// `Idempotency-Key` header, and the gateway must deliver each key only once.
+async fn send_sms(
+    settings: &VerificationSettings,
+    endpoint_url: &str,
+    phone_number: &str,
+    code: &str,
+    created_at: u64,
+) -> Result<(), String> {
+    let idempotency_key = format!("otp:{}:{}", phone_number, created_at);
+    let message = format!(
+        "Your verification code is {}. It expires in {} minutes.",
+        code,
+        settings.code_ttl_seconds.div_ceil(60)
+    );
+    let body = format!(
+        r#"{{"to":"{}","from":"{}","message":"{}","idempotency_key":"{}"}}"#,
+        json::escape(phone_number),
+        json::escape(&settings.sms_sender_id),
+        json::escape(&message),
+        json::escape(&idempotency_key)
+    );
+    let mut headers = vec![HttpHeader {
+        name: "Idempotency-Key".to_string(),
+        value: idempotency_key,
+    }];
+    if let (Some(name), Some(value)) = (
+        &settings.sms_auth_header_name,
+        &settings.sms_auth_header_value,
+    ) {
+        headers.push(HttpHeader {
+            name: name.clone(),
+            value: value.clone(),
+        });
+    }
+
+    let status = outcall::post_json(endpoint_url, headers, body).await?;
+    if (200..300).contains(&status) {
+        Ok(())
+    } else {
+        Err(format!("SMS gateway responded with status {}", status))
+    }
+}
+
+/// Checks a code against the outstanding challenge. The challenge is consumed on success,
+/// on expiry and once the attempts are used up, which also locks the number out of new codes
+/// for a while (see [`reserve_send`]). Returns the purpose it was issued for.
+pub fn check_code(phone_number: &str, code: &str, now: u64) -> Result<OtpPurpose, OtpError> {
+    let mut challenge = store::get_otp_challenge(phone_number).ok_or(OtpError::NoPendingCode)?;
+
+    if now >= challenge.expires_at {
+        store::remove_otp_challenge(phone_number);
+        return Err(OtpError::Expired);
+    }
+    if challenge.attempts_left == 0 {
+        lock_out(phone_number, now);
+        return Err(OtpError::TooManyAttempts);
+    }
+
+    if hash_code(&challenge.salt, phone_number, code.trim()) != challenge.code_hash {
+        challenge.attempts_left -= 1;
+        let attempts_left = challenge.attempts_left;
+        if attempts_left == 0 {
+            lock_out(phone_number, now);
+            return Err(OtpError::TooManyAttempts);
+        }
+        store::put_otp_challenge(challenge);
+        return Err(OtpError::InvalidCode { attempts_left });
+    }
+
+    store::remove_otp_challenge(phone_number);
+    store::remove_otp_lockout(phone_number);
+    Ok(challenge.purpose)
+}
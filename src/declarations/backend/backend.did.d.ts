@@ -4,109 +4,864 @@ import type { IDL } from '@dfinity/candid';
 
 export interface AddProductPayload {
   'name' : string,
+  'tags' : [] | [Array<string>],
   'unit' : string,
   'description' : string,
+  'variants' : [] | [Array<ProductVariant>],
+  'image_id' : [] | [bigint],
+  'quantity_step' : [] | [number],
+  'translations' : [] | [Array<ProductTranslation>],
   'price' : number,
+  'category_id' : [] | [bigint],
+}
+export type AddressError = { 'InvalidInput' : Array<FieldError> } |
+  { 'ProfileNotFound' : null } |
+  { 'TooManyAddresses' : bigint } |
+  { 'AddressNotFound' : bigint } |
+  { 'InvalidPhoneNumber' : PhoneNumberError };
+export interface AddressInput {
+  'geo' : [] | [GeoPoint],
+  'address_lines' : Array<string>,
+  'zone' : [] | [string],
+  'label' : string,
+  'landmark' : [] | [string],
 }
 export interface AllCanisterCyclesResponse {
-  'frontend' : CanisterCycles,
-  'backend' : CanisterCycles,
+  'errors' : Array<CanisterCyclesError>,
+  'canisters' : Array<CanisterCycles>,
+}
+export interface ApiKey {
+  'id' : bigint,
+  'revoked' : boolean,
+  'last_used_at' : [] | [bigint],
+  'name' : string,
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'key_hash' : Uint8Array | number[],
+  'prefix' : string,
+}
+export type ApiKeyError = { 'InvalidInput' : Array<FieldError> } |
+  { 'AlreadyRevoked' : bigint } |
+  { 'RandomnessUnavailable' : string } |
+  { 'ApiKeyNotFound' : bigint };
+export interface AuditEntry {
+  'id' : bigint,
+  'method' : string,
+  'args_summary' : string,
+  'hash' : Uint8Array | number[],
+  'prev_hash' : Uint8Array | number[],
+  'timestamp' : bigint,
+  'caller' : Principal,
+  'outcome' : AuditOutcome,
+}
+export interface AuditLogPage {
+  'total' : bigint,
+  'entries' : Array<AuditEntry>,
 }
+export type AuditOutcome = { 'Success' : null } |
+  { 'Failure' : string };
 export interface CanisterCycles {
   'id' : Principal,
   'name' : string,
   'cycles' : bigint,
 }
+export interface CanisterCyclesError {
+  'id' : Principal,
+  'name' : string,
+  'message' : string,
+}
+export type CartError = { 'ItemNotInCart' : bigint } |
+  { 'InvalidInput' : Array<FieldError> } |
+  { 'VariantNotFound' : { 'product_id' : bigint, 'variant_id' : bigint } } |
+  { 'ProfileNotFound' : null } |
+  { 'ProductNotFound' : bigint } |
+  { 'TooManyItems' : bigint } |
+  { 'InvalidPhoneNumber' : PhoneNumberError };
+export interface CartLine {
+  'variant_label' : [] | [string],
+  'previous_price_per_unit' : [] | [number],
+  'product_id' : bigint,
+  'price_per_unit' : number,
+  'name' : string,
+  'unit' : string,
+  'variant_id' : [] | [bigint],
+  'quantity' : number,
+  'line_total' : number,
+}
+export interface CartView {
+  'updated_at' : bigint,
+  'total_amount' : number,
+  'lines' : Array<CartLine>,
+  'prices_changed' : boolean,
+}
+export interface Category {
+  'id' : bigint,
+  'name' : string,
+  'parent_id' : [] | [bigint],
+}
+export type CategoryError = { 'InvalidInput' : Array<FieldError> } |
+  { 'CategoryNotFound' : bigint } |
+  { 'HasSubcategories' : bigint };
+export interface CategoryPayload {
+  'name' : string,
+  'parent_id' : [] | [bigint],
+}
+export type CertificationError = { 'NotInQuery' : null } |
+  { 'EncodingFailed' : string };
+export interface CertifiedProducts {
+  'certificate' : Uint8Array | number[],
+  'witness' : Uint8Array | number[],
+  'products' : Array<Product>,
+}
+export interface Coupon {
+  'max_uses' : [] | [bigint],
+  'code' : string,
+  'max_uses_per_customer' : [] | [bigint],
+  'description' : string,
+  'min_order_amount' : [] | [number],
+  'scope' : CouponScope,
+  'enabled' : boolean,
+  'valid_until' : [] | [bigint],
+  'discount' : Discount,
+  'valid_from' : [] | [bigint],
+}
+export type CouponError = { 'NotApplicable' : null } |
+  { 'CouponNotFound' : string } |
+  { 'InvalidInput' : Array<FieldError> } |
+  { 'Disabled' : null } |
+  { 'MinimumOrderAmount' : number } |
+  { 'NotYetValid' : null } |
+  { 'UsageLimitReached' : null } |
+  { 'CustomerLimitReached' : null } |
+  { 'Expired' : null };
+export type CouponScope = { 'AllProducts' : null } |
+  { 'Products' : BigUint64Array | bigint[] } |
+  { 'Categories' : BigUint64Array | bigint[] };
+export interface CouponSummary { 'times_used' : bigint, 'coupon' : Coupon }
+export interface CouponTerms {
+  'min_order_amount' : [] | [number],
+  'scope' : CouponScope,
+  'discount' : Discount,
+}
+export interface CreatedApiKey { 'id' : bigint, 'key' : string }
+export type CsvError = { 'InvalidInput' : Array<FieldError> } |
+  { 'UploadTooLarge' : bigint } |
+  { 'UploadNotFound' : string } |
+  { 'InvalidCsv' : string };
+export type CsvSource = { 'Upload' : string } |
+  { 'Inline' : string };
+export type CyclesError = { 'InvalidInput' : Array<FieldError> } |
+  { 'NotMonitored' : Principal } |
+  { 'TooManyCanisters' : bigint } |
+  { 'StorageError' : string };
+export interface CyclesMonitorSettings {
+  'alert_phone_number' : [] | [string],
+  'retention_days' : bigint,
+  'low_balance_threshold' : bigint,
+  'alert_interval_seconds' : bigint,
+}
+export interface CyclesReport {
+  'burn_per_day' : bigint,
+  'below_threshold' : boolean,
+  'name' : string,
+  'canister_id' : Principal,
+  'samples' : Array<CyclesSample>,
+  'projected_days_until_freeze' : [] | [number],
+}
+export interface CyclesSample {
+  'memory_size' : bigint,
+  'canister_id' : Principal,
+  'alerted' : boolean,
+  'cycles' : bigint,
+  'idle_cycles_burned_per_day' : bigint,
+  'timestamp' : bigint,
+  'freezing_threshold_seconds' : bigint,
+}
+export interface DailySummary {
+  'orders_placed' : bigint,
+  'revenue' : number,
+  'date' : string,
+  'created_at' : bigint,
+  'first_time_customers' : bigint,
+  'orders_delivered' : bigint,
+  'orders_cancelled' : bigint,
+  'orders_open' : bigint,
+}
+export type DataDeletionError = { 'InvalidInput' : Array<FieldError> } |
+  { 'ProfileNotFound' : null } |
+  { 'HasActiveOrders' : BigUint64Array | bigint[] } |
+  { 'AlreadyRequested' : null } |
+  { 'InvalidPhoneNumber' : PhoneNumberError } |
+  { 'AlreadyAnonymised' : null };
+export interface DeletionRequest {
+  'requested_at' : bigint,
+  'phone_number' : string,
+}
+export type Discount = { 'Flat' : number } |
+  { 'Percentage' : number };
+export interface ErrorMessage { 'code' : string, 'message' : string }
+export interface FieldError {
+  'field' : string,
+  'kind' : FieldErrorKind,
+  'message' : string,
+}
+export type FieldErrorKind = { 'TooLong' : null } |
+  { 'Duplicate' : null } |
+  { 'Unsupported' : null } |
+  { 'Required' : null } |
+  { 'OutOfRange' : null };
+export interface GeoPoint { 'latitude' : number, 'longitude' : number }
 export type GetUserDataError = { 'FailedToAddToList' : null } |
   { 'DidntFindUserData' : null } |
+  { 'InvalidPhoneNumber' : PhoneNumberError } |
   { 'AnonymousCaller' : null };
+export interface HttpHeader { 'value' : string, 'name' : string }
+export interface HttpRequest {
+  'url' : string,
+  'method' : string,
+  'body' : Uint8Array | number[],
+  'headers' : Array<[string, string]>,
+}
+export interface HttpResponse {
+  'body' : Uint8Array | number[],
+  'headers' : Array<[string, string]>,
+  'upgrade' : [] | [boolean],
+  'status_code' : number,
+}
+export interface HttpResponse_1 {
+  'status' : bigint,
+  'body' : Uint8Array | number[],
+  'headers' : Array<HttpHeader>,
+}
+export type ImageError = { 'InvalidInput' : Array<FieldError> } |
+  { 'TooLarge' : { 'max' : bigint, 'size' : bigint } } |
+  { 'InUse' : bigint } |
+  { 'UploadNotFound' : string } |
+  { 'ImageNotFound' : bigint } |
+  { 'ChecksumMismatch' : null };
+export interface ImportReport {
+  'created' : bigint,
+  'invalid' : bigint,
+  'rows' : Array<ImportRowResult>,
+  'updated' : bigint,
+  'dry_run' : boolean,
+}
+export interface ImportRowResult {
+  'key' : string,
+  'row' : bigint,
+  'status' : ImportRowStatus,
+}
+export type ImportRowStatus = { 'Invalid' : string } |
+  { 'Updated' : null } |
+  { 'Created' : null };
+export interface InitArgs {
+  'monitored_canisters' : [] | [Array<MonitoredCanister>],
+}
+export interface Job {
+  'id' : bigint,
+  'kind' : JobKind,
+  'name' : string,
+  'last_result' : [] | [Result_19],
+  'enabled' : boolean,
+  'last_run_at' : [] | [bigint],
+  'next_run_at' : bigint,
+  'schedule' : JobSchedule,
+}
+export type JobError = { 'InvalidInput' : Array<FieldError> } |
+  { 'JobNotFound' : bigint };
+export type JobKind = { 'DailySummary' : { 'utc_offset_minutes' : number } } |
+  { 'AutoCancelPendingOrders' : { 'timeout_seconds' : bigint } } |
+  { 'RecordCyclesBalances' : null };
+export interface JobPayload {
+  'kind' : JobKind,
+  'name' : string,
+  'enabled' : boolean,
+  'next_run_at' : [] | [bigint],
+  'schedule' : JobSchedule,
+}
+export type JobSchedule = { 'Once' : null } |
+  { 'Recurring' : { 'interval_seconds' : bigint } };
+export interface MonitoredCanister {
+  'canister_id' : Principal,
+  'label' : string,
+}
+export interface Notification {
+  'id' : bigint,
+  'last_error' : [] | [string],
+  'status' : OrderStatus,
+  'next_attempt_at' : bigint,
+  'attempts' : number,
+  'created_at' : bigint,
+  'event' : [] | [NotificationEvent],
+  'state' : NotificationState,
+  'message' : string,
+  'order_id' : bigint,
+  'phone_number' : string,
+}
+export type NotificationChannel = { 'Sms' : null } |
+  { 'WhatsApp' : null } |
+  { 'GenericJson' : null };
+export type NotificationError = { 'NotFailed' : bigint } |
+  { 'NotificationNotFound' : bigint };
+export type NotificationEvent = { 'OrderStatusChanged' : null } |
+  { 'LowCyclesBalance' : { 'canister_id' : Principal, 'cycles' : bigint } };
+export interface NotificationSettings {
+  'webhook_url' : [] | [string],
+  'auth_header_value' : [] | [string],
+  'base_backoff_seconds' : bigint,
+  'enabled' : boolean,
+  'max_attempts' : number,
+  'sender_id' : string,
+  'channel' : NotificationChannel,
+  'auth_header_name' : [] | [string],
+}
+export type NotificationState = { 'Failed' : null } |
+  { 'Delivered' : { 'at' : bigint } } |
+  { 'Pending' : null };
 export interface Order {
   'id' : bigint,
   'status' : OrderStatus,
   'total_amount' : number,
+  'discounts' : [] | [Array<OrderDiscount>],
   'last_updated' : bigint,
   'user_phone_number' : string,
+  'delivery_address_id' : [] | [bigint],
   'delivery_address' : string,
   'timestamp' : bigint,
   'items' : Array<OrderItem>,
   'customer_name' : string,
 }
-export type OrderError = { 'AccessDenied' : null } |
+export interface OrderDiscount {
+  'terms' : [] | [CouponTerms],
+  'description' : string,
+  'coupon_code' : string,
+  'amount' : number,
+}
+export interface OrderEdit {
+  'previous_delivery_address' : string,
+  'edited_at' : bigint,
+  'edited_by' : OrderEditor,
+  'previous_total_amount' : number,
+  'previous_items' : Array<OrderItem>,
+  'order_id' : bigint,
+  'reason' : [] | [string],
+}
+export type OrderEditor = { 'Customer' : null } |
+  { 'Admin' : Principal };
+export type OrderError = { 'InvalidCoupon' : CouponError } |
+  { 'AccessDenied' : null } |
   { 'CannotCancelOrder' : string } |
-  { 'InvalidInput' : string } |
+  { 'InvalidInput' : Array<FieldError> } |
+  { 'PhoneNotVerified' : null } |
+  { 'VariantNotFound' : { 'product_id' : bigint, 'variant_id' : bigint } } |
   { 'InvalidProductInOrder' : bigint } |
+  {
+    'OutOfStock' : {
+      'product_id' : bigint,
+      'available' : bigint,
+      'variant_id' : bigint,
+    }
+  } |
   { 'OrderNotFound' : null } |
+  { 'CannotEditOrder' : string } |
   { 'UserProfileNotFound' : null } |
-  { 'StorageError' : string };
+  { 'AddressNotFound' : bigint } |
+  { 'PricesChanged' : BigUint64Array | bigint[] } |
+  { 'StorageError' : string } |
+  { 'InvalidPhoneNumber' : PhoneNumberError };
 export interface OrderItem {
   'product_id' : bigint,
+  'price_list_id' : [] | [bigint],
+  'variant_id' : [] | [bigint],
   'quantity' : number,
   'price_per_unit_at_order' : number,
 }
-export interface OrderItemInput { 'product_id' : bigint, 'quantity' : number }
+export interface OrderItemInput {
+  'product_id' : bigint,
+  'variant_id' : [] | [bigint],
+  'quantity' : number,
+}
 export type OrderStatus = { 'Delivered' : null } |
   { 'Confirmed' : null } |
   { 'Cancelled' : null } |
   { 'Processing' : null } |
   { 'OutForDelivery' : null } |
   { 'Pending' : null };
+export type OtpError = {
+    'TooManyRequests' : { 'retry_after_seconds' : bigint }
+  } |
+  { 'PhoneChangeFailed' : PhoneChangeError } |
+  { 'RandomnessUnavailable' : string } |
+  { 'ProfileNotFound' : null } |
+  { 'NoPendingCode' : null } |
+  { 'PhoneChangeNotRequested' : null } |
+  { 'SendFailed' : string } |
+  { 'InvalidCode' : { 'attempts_left' : number } } |
+  { 'TooManyAttempts' : null } |
+  { 'NotConfigured' : null } |
+  { 'InvalidPhoneNumber' : PhoneNumberError } |
+  { 'Expired' : null };
+export type OtpPurpose = { 'VerifyProfile' : null } |
+  { 'ChangePhoneNumber' : { 'current_phone_number' : string } };
+export type PhoneChangeError = { 'ProfileNotFound' : null } |
+  { 'RequestNotFound' : null } |
+  { 'NumberAlreadyInUse' : string } |
+  { 'SameNumber' : null } |
+  { 'InvalidPhoneNumber' : PhoneNumberError };
+export interface PhoneChangeRequest {
+  'requested_at' : bigint,
+  'new_phone_number' : string,
+  'current_phone_number' : string,
+}
+export interface PhoneMigrationReport {
+  'profiles_merged' : bigint,
+  'profiles_rewritten' : bigint,
+  'invalid_phone_numbers' : Array<string>,
+  'orders_rewritten' : bigint,
+}
+export type PhoneNumberError = { 'Empty' : null } |
+  { 'InvalidCountryCode' : null } |
+  { 'InvalidCharacter' : string } |
+  { 'InvalidLength' : number };
+export interface PhoneSettings {
+  'default_country_code' : number,
+  'national_number_length' : [] | [number],
+}
+export interface PriceChange {
+  'product_id' : bigint,
+  'recorded_at' : bigint,
+  'effective_from' : bigint,
+  'price' : number,
+}
+export interface PriceList {
+  'id' : bigint,
+  'kind' : PriceListKind,
+  'name' : string,
+  'prices' : Array<ProductPrice>,
+}
+export type PriceListError = { 'InvalidInput' : Array<FieldError> } |
+  { 'PriceListNotFound' : bigint } |
+  { 'InUse' : bigint } |
+  { 'ProfileNotFound' : null } |
+  { 'AlreadyAssigned' : string } |
+  { 'InvalidPhoneNumber' : PhoneNumberError };
+export type PriceListKind = { 'Retail' : null } |
+  { 'Custom' : null } |
+  { 'Wholesale' : null };
+export interface PriceListPayload {
+  'kind' : PriceListKind,
+  'name' : string,
+  'prices' : Array<ProductPrice>,
+}
+export interface PriceTier {
+  'price_per_unit' : number,
+  'min_quantity' : number,
+}
 export interface Product {
   'id' : bigint,
   'name' : string,
+  'tags' : [] | [Array<string>],
   'unit' : string,
   'description' : string,
+  'variants' : [] | [Array<ProductVariant>],
+  'image_id' : [] | [bigint],
+  'quantity_step' : [] | [number],
+  'translations' : [] | [Array<ProductTranslation>],
   'price' : number,
+  'category_id' : [] | [bigint],
+}
+export type ProductError = { 'InvalidInput' : Array<FieldError> } |
+  { 'VariantNotFound' : { 'product_id' : bigint, 'variant_id' : bigint } } |
+  { 'ProductNotFound' : bigint } |
+  {
+    'PriceChangeNotFound' : { 'product_id' : bigint, 'effective_from' : bigint }
+  };
+export interface ProductImage {
+  'id' : bigint,
+  'sha256' : Uint8Array | number[],
+  'size' : bigint,
+  'content_type' : string,
+  'uploaded_at' : bigint,
+}
+export interface ProductPrice {
+  'tiers' : Array<PriceTier>,
+  'product_id' : bigint,
 }
-export type Result = { 'Ok' : bigint } |
+export interface ProductSearch {
+  'lang' : [] | [string],
+  'sort' : [] | [ProductSort],
+  'tags' : [] | [Array<string>],
+  'text' : [] | [string],
+  'category_id' : [] | [bigint],
+}
+export type ProductSort = { 'PriceDescending' : null } |
+  { 'Name' : null } |
+  { 'Relevance' : null } |
+  { 'PriceAscending' : null };
+export interface ProductTranslation {
+  'lang' : string,
+  'name' : string,
+  'unit' : [] | [string],
+  'description' : string,
+}
+export interface ProductVariant {
+  'id' : bigint,
+  'sku' : string,
+  'label' : string,
+  'stock' : [] | [bigint],
+  'price' : number,
+  'pack_size' : number,
+}
+export type ProfileError = { 'InvalidInput' : Array<FieldError> } |
+  { 'AlreadyExists' : string } |
+  { 'InvalidPhoneNumber' : PhoneNumberError };
+export type Result = { 'Ok' : SavedAddress } |
+  { 'Err' : AddressError };
+export type Result_1 = { 'Ok' : bigint } |
+  { 'Err' : ProductError };
+export type Result_10 = { 'Ok' : Category } |
+  { 'Err' : CategoryError };
+export type Result_11 = { 'Ok' : PriceList } |
+  { 'Err' : PriceListError };
+export type Result_12 = { 'Ok' : null } |
+  { 'Err' : ProfileError };
+export type Result_13 = { 'Ok' : UserProfile } |
+  { 'Err' : DataDeletionError };
+export type Result_14 = { 'Ok' : string } |
+  { 'Err' : CsvError };
+export type Result_15 = { 'Ok' : SnapshotManifest } |
+  { 'Err' : SnapshotError };
+export type Result_16 = { 'Ok' : SnapshotChunk } |
+  { 'Err' : SnapshotError };
+export type Result_17 = { 'Ok' : ProductImage } |
+  { 'Err' : ImageError };
+export type Result_18 = { 'Ok' : Array<Order> } |
+  { 'Err' : OrderError };
+export type Result_19 = { 'Ok' : string } |
   { 'Err' : string };
-export type Result_1 = { 'Ok' : Order } |
+export type Result_2 = { 'Ok' : CartView } |
+  { 'Err' : CartError };
+export type Result_20 = { 'Ok' : Array<OrderEdit> } |
   { 'Err' : OrderError };
-export type Result_10 = { 'Ok' : null } |
+export type Result_21 = { 'Ok' : CertifiedProducts } |
+  { 'Err' : CertificationError };
+export type Result_22 = { 'Ok' : UserProfile } |
   { 'Err' : GetUserDataError };
-export type Result_2 = { 'Ok' : bigint } |
+export type Result_23 = { 'Ok' : ImportReport } |
+  { 'Err' : CsvError };
+export type Result_24 = { 'Ok' : string } |
+  { 'Err' : SnapshotError };
+export type Result_25 = { 'Ok' : bigint } |
+  { 'Err' : SnapshotError };
+export type Result_26 = { 'Ok' : string } |
+  { 'Err' : SetupError };
+export type Result_27 = { 'Ok' : PhoneMigrationReport } |
+  { 'Err' : SettingsError };
+export type Result_28 = { 'Ok' : OrderDiscount } |
   { 'Err' : OrderError };
-export type Result_3 = { 'Ok' : null } |
-  { 'Err' : string };
-export type Result_4 = { 'Ok' : UserProfile } |
-  { 'Err' : string };
-export type Result_5 = { 'Ok' : AllCanisterCyclesResponse } |
-  { 'Err' : string };
-export type Result_6 = { 'Ok' : Array<Order> } |
+export type Result_29 = { 'Ok' : null } |
+  { 'Err' : AddressError };
+export type Result_3 = { 'Ok' : UserProfile } |
+  { 'Err' : PriceListError };
+export type Result_30 = { 'Ok' : Coupon } |
+  { 'Err' : CouponError };
+export type Result_31 = { 'Ok' : Job } |
+  { 'Err' : JobError };
+export type Result_32 = { 'Ok' : null } |
+  { 'Err' : CyclesError };
+export type Result_33 = { 'Ok' : null } |
+  { 'Err' : DataDeletionError };
+export type Result_34 = { 'Ok' : null } |
+  { 'Err' : OtpError };
+export type Result_35 = { 'Ok' : null } |
+  { 'Err' : PhoneChangeError };
+export type Result_36 = { 'Ok' : Notification } |
+  { 'Err' : NotificationError };
+export type Result_37 = { 'Ok' : TopUp } |
+  { 'Err' : TopUpError };
+export type Result_38 = { 'Ok' : ApiKey } |
+  { 'Err' : ApiKeyError };
+export type Result_39 = { 'Ok' : MonitoredCanister } |
+  { 'Err' : CyclesError };
+export type Result_4 = { 'Ok' : null } |
+  { 'Err' : SnapshotError };
+export type Result_40 = { 'Ok' : null } |
+  { 'Err' : GetUserDataError };
+export type Result_41 = { 'Ok' : null } |
+  { 'Err' : SettingsError };
+export type Result_42 = { 'Ok' : null } |
+  { 'Err' : TopUpError };
+export type Result_43 = { 'Ok' : Product } |
+  { 'Err' : ProductError };
+export type Result_44 = { 'Ok' : bigint } |
+  { 'Err' : CsvError };
+export type Result_45 = { 'Ok' : bigint } |
+  { 'Err' : ImageError };
+export type Result_46 = { 'Ok' : null } |
+  { 'Err' : bigint };
+export type Result_47 = { 'Ok' : UserProfile } |
+  { 'Err' : OtpError };
+export type Result_5 = { 'Ok' : Order } |
   { 'Err' : OrderError };
+export type Result_6 = { 'Ok' : PriceChange } |
+  { 'Err' : ProductError };
 export type Result_7 = { 'Ok' : UserProfile } |
-  { 'Err' : GetUserDataError };
-export type Result_8 = { 'Ok' : string } |
-  { 'Err' : string };
-export type Result_9 = { 'Ok' : Product } |
-  { 'Err' : string };
+  { 'Err' : PhoneChangeError };
+export type Result_8 = { 'Ok' : bigint } |
+  { 'Err' : OrderError };
+export type Result_9 = { 'Ok' : CreatedApiKey } |
+  { 'Err' : ApiKeyError };
+export interface SavedAddress {
+  'id' : bigint,
+  'geo' : [] | [GeoPoint],
+  'address_lines' : Array<string>,
+  'zone' : [] | [string],
+  'label' : string,
+  'landmark' : [] | [string],
+}
+export type SettingsError = { 'InvalidInput' : Array<FieldError> } |
+  { 'StorageError' : string };
+export type SetupError = { 'AlreadyInitialized' : null } |
+  { 'StorageError' : string };
+export interface SnapshotChunk {
+  'snapshot_checksum' : Uint8Array | number[],
+  'data' : Uint8Array | number[],
+  'version' : number,
+  'checksum' : Uint8Array | number[],
+  'index' : bigint,
+}
+export type SnapshotError = { 'NotExported' : null } |
+  { 'WrongSnapshot' : bigint } |
+  { 'NotFresh' : null } |
+  { 'InvalidManifest' : null } |
+  { 'UnsupportedVersion' : number } |
+  { 'NoImportInProgress' : null } |
+  { 'ChecksumMismatch' : [] | [bigint] } |
+  { 'EncodingFailed' : string } |
+  { 'InvalidSnapshot' : string } |
+  { 'ChunkOutOfRange' : bigint } |
+  { 'Incomplete' : { 'expected' : bigint, 'received' : bigint } };
+export interface SnapshotManifest {
+  'created_at' : bigint,
+  'total_size' : bigint,
+  'version' : number,
+  'chunk_count' : bigint,
+  'checksum' : Uint8Array | number[],
+  'chunk_size' : bigint,
+}
+export interface TopUp {
+  'id' : bigint,
+  'last_error' : [] | [string],
+  'automatic' : boolean,
+  'canister_id' : Principal,
+  'icp_e8s' : bigint,
+  'created_at' : bigint,
+  'requested_by' : Principal,
+  'state' : TopUpState,
+}
+export type TopUpError = {
+    'Failed' : { 'top_up_id' : bigint, 'reason' : string }
+  } |
+  { 'InvalidInput' : Array<FieldError> } |
+  { 'NotMonitored' : Principal } |
+  { 'TopUpNotFound' : bigint } |
+  {
+    'NotMinted' : {
+      'block_index' : bigint,
+      'top_up_id' : bigint,
+      'reason' : string,
+    }
+  } |
+  { 'NotRetryable' : bigint } |
+  { 'StorageError' : string };
+export interface TopUpSettings {
+  'auto_top_up' : boolean,
+  'cmc_canister_id' : Principal,
+  'min_cycles_balance' : bigint,
+  'max_top_up_e8s' : bigint,
+  'ledger_canister_id' : Principal,
+  'auto_top_up_e8s' : bigint,
+}
+export type TopUpState = { 'Failed' : string } |
+  { 'Transferred' : { 'block_index' : bigint } } |
+  { 'Completed' : { 'block_index' : bigint, 'cycles' : bigint } } |
+  { 'Pending' : null };
+export interface TransformArgs {
+  'context' : Uint8Array | number[],
+  'response' : HttpResponse_1,
+}
 export interface UserProfile {
   'name' : string,
   'order_ids' : BigUint64Array | bigint[],
+  'price_list_id' : [] | [bigint],
+  'addresses' : [] | [Array<SavedAddress>],
   'address' : string,
+  'verified_at' : [] | [bigint],
+  'deleted_at' : [] | [bigint],
+  'default_address_id' : [] | [bigint],
+  'notifications_opt_out' : [] | [boolean],
   'phone_number' : string,
 }
+export interface VerificationSettings {
+  'sms_sender_id' : string,
+  'sms_auth_header_value' : [] | [string],
+  'resend_interval_seconds' : bigint,
+  'max_attempts' : number,
+  'code_ttl_seconds' : bigint,
+  'sms_endpoint_url' : [] | [string],
+  'sms_auth_header_name' : [] | [string],
+  'require_verified_phone_for_orders' : boolean,
+}
 export interface _SERVICE {
-  'add_product_admin' : ActorMethod<[AddProductPayload], Result>,
-  'cancel_my_order' : ActorMethod<[bigint, string], Result_1>,
-  'create_order' : ActorMethod<
-    [string, Array<OrderItemInput>, string],
+  'add_address' : ActorMethod<[string, AddressInput], Result>,
+  'add_product_admin' : ActorMethod<[AddProductPayload], Result_1>,
+  'add_to_cart' : ActorMethod<
+    [string, bigint, number, [] | [bigint]],
     Result_2
   >,
-  'create_profile' : ActorMethod<[UserProfile], Result_3>,
-  'delete_profile_admin' : ActorMethod<[string], Result_4>,
-  'get_all_canister_cycles' : ActorMethod<[], Result_5>,
+  'assign_price_list_admin' : ActorMethod<[string, [] | [bigint]], Result_3>,
+  'begin_import_snapshot' : ActorMethod<[SnapshotManifest], Result_4>,
+  'cancel_my_order' : ActorMethod<[bigint, string], Result_5>,
+  'cancel_price_change_admin' : ActorMethod<[bigint, bigint], Result_6>,
+  'change_phone_number_admin' : ActorMethod<[string, string], Result_7>,
+  'checkout_cart' : ActorMethod<
+    [string, string, [] | [bigint], [] | [string]],
+    Result_8
+  >,
+  'clear_cart' : ActorMethod<[string], Result_2>,
+  'create_api_key_admin' : ActorMethod<[string], Result_9>,
+  'create_category_admin' : ActorMethod<[CategoryPayload], Result_10>,
+  'create_order' : ActorMethod<
+    [string, Array<OrderItemInput>, string, [] | [bigint], [] | [string]],
+    Result_8
+  >,
+  'create_price_list_admin' : ActorMethod<[PriceListPayload], Result_11>,
+  'create_profile' : ActorMethod<[UserProfile], Result_12>,
+  'delete_profile_admin' : ActorMethod<[string], Result_13>,
+  'export_customers_csv' : ActorMethod<[[] | [number]], string>,
+  'export_orders_csv' : ActorMethod<[string, string, [] | [number]], Result_14>,
+  'export_products_csv' : ActorMethod<[], string>,
+  'export_snapshot' : ActorMethod<[], Result_15>,
+  'export_snapshot_chunk' : ActorMethod<[bigint], Result_16>,
+  'finish_image_upload' : ActorMethod<
+    [string, string, Uint8Array | number[]],
+    Result_17
+  >,
+  'get_all_canister_cycles' : ActorMethod<[], AllCanisterCyclesResponse>,
   'get_all_customers' : ActorMethod<[], Array<UserProfile>>,
-  'get_all_orders' : ActorMethod<[], Result_6>,
-  'get_my_orders' : ActorMethod<[string], Result_6>,
-  'get_order_details' : ActorMethod<[bigint, string], Result_1>,
-  'get_order_details_admin' : ActorMethod<[bigint], Result_1>,
-  'get_products' : ActorMethod<[], Array<Product>>,
-  'get_profile_by_phone' : ActorMethod<[string], Result_7>,
-  'initialize_products' : ActorMethod<[], Result_8>,
+  'get_all_orders' : ActorMethod<[], Result_18>,
+  'get_audit_log' : ActorMethod<[bigint, bigint], AuditLogPage>,
+  'get_cart' : ActorMethod<[string], Result_2>,
+  'get_categories' : ActorMethod<[], Array<Category>>,
+  'get_coupons_admin' : ActorMethod<[], Array<CouponSummary>>,
+  'get_cycles_history' : ActorMethod<[[] | [bigint]], Array<CyclesReport>>,
+  'get_cycles_monitor_settings' : ActorMethod<[], CyclesMonitorSettings>,
+  'get_daily_summaries' : ActorMethod<[string, string], Array<DailySummary>>,
+  'get_deletion_requests' : ActorMethod<[], Array<DeletionRequest>>,
+  'get_error_messages' : ActorMethod<[string], Array<ErrorMessage>>,
+  'get_failed_notifications' : ActorMethod<[], Array<Notification>>,
+  'get_icp_account' : ActorMethod<[], string>,
+  'get_images_admin' : ActorMethod<[], Array<ProductImage>>,
+  'get_jobs' : ActorMethod<[], Array<Job>>,
+  'get_monitored_canisters' : ActorMethod<[], Array<MonitoredCanister>>,
+  'get_my_orders' : ActorMethod<[string], Result_18>,
+  'get_notification_settings' : ActorMethod<[], NotificationSettings>,
+  'get_order_details' : ActorMethod<[bigint, string], Result_5>,
+  'get_order_details_admin' : ActorMethod<[bigint], Result_5>,
+  'get_order_edits' : ActorMethod<[bigint, string], Result_20>,
+  'get_order_edits_admin' : ActorMethod<[bigint], Result_20>,
+  'get_phone_change_requests' : ActorMethod<[], Array<PhoneChangeRequest>>,
+  'get_phone_settings' : ActorMethod<[], PhoneSettings>,
+  'get_price_history' : ActorMethod<[bigint], Array<PriceChange>>,
+  'get_price_lists_admin' : ActorMethod<[], Array<PriceList>>,
+  'get_products' : ActorMethod<[[] | [string]], Array<Product>>,
+  'get_products_certified' : ActorMethod<[], Result_21>,
+  'get_profile_by_phone' : ActorMethod<[string], Result_22>,
+  'get_top_up_settings' : ActorMethod<[], TopUpSettings>,
+  'get_top_ups' : ActorMethod<[], Array<TopUp>>,
+  'get_verification_settings' : ActorMethod<[], VerificationSettings>,
+  'http_request' : ActorMethod<[HttpRequest], HttpResponse>,
+  'http_request_update' : ActorMethod<[HttpRequest], HttpResponse>,
+  'import_customers_csv' : ActorMethod<[CsvSource, boolean], Result_23>,
+  'import_products_csv' : ActorMethod<[CsvSource, boolean], Result_23>,
+  'import_snapshot' : ActorMethod<[], Result_24>,
+  'import_snapshot_chunk' : ActorMethod<[SnapshotChunk], Result_25>,
+  'initialize_products' : ActorMethod<[], Result_26>,
   'is_dev_check' : ActorMethod<[], boolean>,
-  'update_order_status_admin' : ActorMethod<[bigint, OrderStatus], Result_1>,
-  'update_product_admin' : ActorMethod<[bigint, AddProductPayload], Result_9>,
-  'update_profile' : ActorMethod<[UserProfile], Result_10>,
+  'list_api_keys_admin' : ActorMethod<[], Array<ApiKey>>,
+  'migrate_phone_numbers' : ActorMethod<[], Result_27>,
+  'preview_coupon' : ActorMethod<
+    [string, Array<OrderItemInput>, string],
+    Result_28
+  >,
+  'remove_address' : ActorMethod<[string, bigint], Result_29>,
+  'remove_category_admin' : ActorMethod<[bigint], Result_10>,
+  'remove_coupon_admin' : ActorMethod<[string], Result_30>,
+  'remove_from_cart' : ActorMethod<[string, bigint, [] | [bigint]], Result_2>,
+  'remove_image_admin' : ActorMethod<[bigint], Result_17>,
+  'remove_job_admin' : ActorMethod<[bigint], Result_31>,
+  'remove_monitored_canister_admin' : ActorMethod<[Principal], Result_32>,
+  'remove_price_list_admin' : ActorMethod<[bigint], Result_11>,
+  'request_data_deletion' : ActorMethod<[string], Result_33>,
+  'request_otp' : ActorMethod<[string, OtpPurpose], Result_34>,
+  'request_phone_number_change' : ActorMethod<[string, string], Result_35>,
+  'retry_notification_admin' : ActorMethod<[bigint], Result_36>,
+  'retry_top_up_admin' : ActorMethod<[bigint], Result_37>,
+  'revoke_api_key_admin' : ActorMethod<[bigint], Result_38>,
+  'run_job_now_admin' : ActorMethod<[bigint], Result_31>,
+  'schedule_job_admin' : ActorMethod<[JobPayload], Result_31>,
+  'schedule_price_change_admin' : ActorMethod<
+    [bigint, number, bigint],
+    Result_6
+  >,
+  'search_products' : ActorMethod<[ProductSearch], Array<Product>>,
+  'set_coupon_admin' : ActorMethod<[Coupon], Result_30>,
+  'set_cycles_monitor_settings' : ActorMethod<
+    [CyclesMonitorSettings],
+    Result_32
+  >,
+  'set_default_address' : ActorMethod<[string, bigint], Result_29>,
+  'set_monitored_canister_admin' : ActorMethod<[MonitoredCanister], Result_39>,
+  'set_notification_preference' : ActorMethod<[string, boolean], Result_40>,
+  'set_notification_settings' : ActorMethod<[NotificationSettings], Result_41>,
+  'set_phone_settings' : ActorMethod<[PhoneSettings], Result_41>,
+  'set_top_up_settings' : ActorMethod<[TopUpSettings], Result_42>,
+  'set_variant_stock_admin' : ActorMethod<
+    [bigint, bigint, [] | [bigint]],
+    Result_43
+  >,
+  'set_verification_settings' : ActorMethod<[VerificationSettings], Result_41>,
+  'top_up_canister' : ActorMethod<[Principal, bigint], Result_37>,
+  'transform_outcall_response' : ActorMethod<[TransformArgs], HttpResponse_1>,
+  'update_address' : ActorMethod<[string, bigint, AddressInput], Result>,
+  'update_cart_item' : ActorMethod<
+    [string, bigint, number, [] | [bigint]],
+    Result_2
+  >,
+  'update_category_admin' : ActorMethod<[bigint, CategoryPayload], Result_10>,
+  'update_job_admin' : ActorMethod<[bigint, JobPayload], Result_31>,
+  'update_my_order' : ActorMethod<
+    [bigint, string, Array<OrderItemInput>, [] | [string], [] | [bigint]],
+    Result_5
+  >,
+  'update_order_admin' : ActorMethod<
+    [bigint, Array<OrderItemInput>, [] | [string], [] | [bigint], string],
+    Result_5
+  >,
+  'update_order_status_admin' : ActorMethod<[bigint, OrderStatus], Result_5>,
+  'update_price_list_admin' : ActorMethod<
+    [bigint, PriceListPayload],
+    Result_11
+  >,
+  'update_product_admin' : ActorMethod<[bigint, AddProductPayload], Result_43>,
+  'update_profile' : ActorMethod<[UserProfile], Result_12>,
+  'upload_csv_chunk' : ActorMethod<[string, string], Result_44>,
+  'upload_image_chunk' : ActorMethod<
+    [string, Uint8Array | number[]],
+    Result_45
+  >,
+  'verify_audit_log' : ActorMethod<[], Result_46>,
+  'verify_otp' : ActorMethod<[string, string], Result_47>,
 }
 export declare const idlFactory: IDL.InterfaceFactory;
 export declare const init: (args: { IDL: typeof IDL }) => IDL.Type[];
@@ -1,11 +1,171 @@
 export const idlFactory = ({ IDL }) => {
+  const MonitoredCanister = IDL.Record({
+    'canister_id' : IDL.Principal,
+    'label' : IDL.Text,
+  });
+  const InitArgs = IDL.Record({
+    'monitored_canisters' : IDL.Opt(IDL.Vec(MonitoredCanister)),
+  });
+  const GeoPoint = IDL.Record({
+    'latitude' : IDL.Float64,
+    'longitude' : IDL.Float64,
+  });
+  const AddressInput = IDL.Record({
+    'geo' : IDL.Opt(GeoPoint),
+    'address_lines' : IDL.Vec(IDL.Text),
+    'zone' : IDL.Opt(IDL.Text),
+    'label' : IDL.Text,
+    'landmark' : IDL.Opt(IDL.Text),
+  });
+  const SavedAddress = IDL.Record({
+    'id' : IDL.Nat64,
+    'geo' : IDL.Opt(GeoPoint),
+    'address_lines' : IDL.Vec(IDL.Text),
+    'zone' : IDL.Opt(IDL.Text),
+    'label' : IDL.Text,
+    'landmark' : IDL.Opt(IDL.Text),
+  });
+  const FieldErrorKind = IDL.Variant({
+    'TooLong' : IDL.Null,
+    'Duplicate' : IDL.Null,
+    'Unsupported' : IDL.Null,
+    'Required' : IDL.Null,
+    'OutOfRange' : IDL.Null,
+  });
+  const FieldError = IDL.Record({
+    'field' : IDL.Text,
+    'kind' : FieldErrorKind,
+    'message' : IDL.Text,
+  });
+  const PhoneNumberError = IDL.Variant({
+    'Empty' : IDL.Null,
+    'InvalidCountryCode' : IDL.Null,
+    'InvalidCharacter' : IDL.Text,
+    'InvalidLength' : IDL.Nat8,
+  });
+  const AddressError = IDL.Variant({
+    'InvalidInput' : IDL.Vec(FieldError),
+    'ProfileNotFound' : IDL.Null,
+    'TooManyAddresses' : IDL.Nat64,
+    'AddressNotFound' : IDL.Nat64,
+    'InvalidPhoneNumber' : PhoneNumberError,
+  });
+  const Result = IDL.Variant({ 'Ok' : SavedAddress, 'Err' : AddressError });
+  const ProductVariant = IDL.Record({
+    'id' : IDL.Nat64,
+    'sku' : IDL.Text,
+    'label' : IDL.Text,
+    'stock' : IDL.Opt(IDL.Nat64),
+    'price' : IDL.Float64,
+    'pack_size' : IDL.Float64,
+  });
+  const ProductTranslation = IDL.Record({
+    'lang' : IDL.Text,
+    'name' : IDL.Text,
+    'unit' : IDL.Opt(IDL.Text),
+    'description' : IDL.Text,
+  });
   const AddProductPayload = IDL.Record({
     'name' : IDL.Text,
+    'tags' : IDL.Opt(IDL.Vec(IDL.Text)),
     'unit' : IDL.Text,
     'description' : IDL.Text,
+    'variants' : IDL.Opt(IDL.Vec(ProductVariant)),
+    'image_id' : IDL.Opt(IDL.Nat64),
+    'quantity_step' : IDL.Opt(IDL.Float64),
+    'translations' : IDL.Opt(IDL.Vec(ProductTranslation)),
     'price' : IDL.Float64,
+    'category_id' : IDL.Opt(IDL.Nat64),
+  });
+  const ProductError = IDL.Variant({
+    'InvalidInput' : IDL.Vec(FieldError),
+    'VariantNotFound' : IDL.Record({
+      'product_id' : IDL.Nat64,
+      'variant_id' : IDL.Nat64,
+    }),
+    'ProductNotFound' : IDL.Nat64,
+    'PriceChangeNotFound' : IDL.Record({
+      'product_id' : IDL.Nat64,
+      'effective_from' : IDL.Nat64,
+    }),
+  });
+  const Result_1 = IDL.Variant({ 'Ok' : IDL.Nat64, 'Err' : ProductError });
+  const CartLine = IDL.Record({
+    'variant_label' : IDL.Opt(IDL.Text),
+    'previous_price_per_unit' : IDL.Opt(IDL.Float64),
+    'product_id' : IDL.Nat64,
+    'price_per_unit' : IDL.Float64,
+    'name' : IDL.Text,
+    'unit' : IDL.Text,
+    'variant_id' : IDL.Opt(IDL.Nat64),
+    'quantity' : IDL.Float64,
+    'line_total' : IDL.Float64,
+  });
+  const CartView = IDL.Record({
+    'updated_at' : IDL.Nat64,
+    'total_amount' : IDL.Float64,
+    'lines' : IDL.Vec(CartLine),
+    'prices_changed' : IDL.Bool,
+  });
+  const CartError = IDL.Variant({
+    'ItemNotInCart' : IDL.Nat64,
+    'InvalidInput' : IDL.Vec(FieldError),
+    'VariantNotFound' : IDL.Record({
+      'product_id' : IDL.Nat64,
+      'variant_id' : IDL.Nat64,
+    }),
+    'ProfileNotFound' : IDL.Null,
+    'ProductNotFound' : IDL.Nat64,
+    'TooManyItems' : IDL.Nat64,
+    'InvalidPhoneNumber' : PhoneNumberError,
+  });
+  const Result_2 = IDL.Variant({ 'Ok' : CartView, 'Err' : CartError });
+  const UserProfile = IDL.Record({
+    'name' : IDL.Text,
+    'order_ids' : IDL.Vec(IDL.Nat64),
+    'price_list_id' : IDL.Opt(IDL.Nat64),
+    'addresses' : IDL.Opt(IDL.Vec(SavedAddress)),
+    'address' : IDL.Text,
+    'verified_at' : IDL.Opt(IDL.Nat64),
+    'deleted_at' : IDL.Opt(IDL.Nat64),
+    'default_address_id' : IDL.Opt(IDL.Nat64),
+    'notifications_opt_out' : IDL.Opt(IDL.Bool),
+    'phone_number' : IDL.Text,
+  });
+  const PriceListError = IDL.Variant({
+    'InvalidInput' : IDL.Vec(FieldError),
+    'PriceListNotFound' : IDL.Nat64,
+    'InUse' : IDL.Nat64,
+    'ProfileNotFound' : IDL.Null,
+    'AlreadyAssigned' : IDL.Text,
+    'InvalidPhoneNumber' : PhoneNumberError,
   });
-  const Result = IDL.Variant({ 'Ok' : IDL.Nat64, 'Err' : IDL.Text });
+  const Result_3 = IDL.Variant({ 'Ok' : UserProfile, 'Err' : PriceListError });
+  const SnapshotManifest = IDL.Record({
+    'created_at' : IDL.Nat64,
+    'total_size' : IDL.Nat64,
+    'version' : IDL.Nat32,
+    'chunk_count' : IDL.Nat64,
+    'checksum' : IDL.Vec(IDL.Nat8),
+    'chunk_size' : IDL.Nat64,
+  });
+  const SnapshotError = IDL.Variant({
+    'NotExported' : IDL.Null,
+    'WrongSnapshot' : IDL.Nat64,
+    'NotFresh' : IDL.Null,
+    'InvalidManifest' : IDL.Null,
+    'UnsupportedVersion' : IDL.Nat32,
+    'NoImportInProgress' : IDL.Null,
+    'ChecksumMismatch' : IDL.Opt(IDL.Nat64),
+    'EncodingFailed' : IDL.Text,
+    'InvalidSnapshot' : IDL.Text,
+    'ChunkOutOfRange' : IDL.Nat64,
+    'Incomplete' : IDL.Record({
+      'expected' : IDL.Nat64,
+      'received' : IDL.Nat64,
+    }),
+  });
+  const Result_4 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : SnapshotError });
   const OrderStatus = IDL.Variant({
     'Delivered' : IDL.Null,
     'Confirmed' : IDL.Null,
@@ -14,8 +174,30 @@ export const idlFactory = ({ IDL }) => {
     'OutForDelivery' : IDL.Null,
     'Pending' : IDL.Null,
   });
+  const CouponScope = IDL.Variant({
+    'AllProducts' : IDL.Null,
+    'Products' : IDL.Vec(IDL.Nat64),
+    'Categories' : IDL.Vec(IDL.Nat64),
+  });
+  const Discount = IDL.Variant({
+    'Flat' : IDL.Float64,
+    'Percentage' : IDL.Float64,
+  });
+  const CouponTerms = IDL.Record({
+    'min_order_amount' : IDL.Opt(IDL.Float64),
+    'scope' : CouponScope,
+    'discount' : Discount,
+  });
+  const OrderDiscount = IDL.Record({
+    'terms' : IDL.Opt(CouponTerms),
+    'description' : IDL.Text,
+    'coupon_code' : IDL.Text,
+    'amount' : IDL.Float64,
+  });
   const OrderItem = IDL.Record({
     'product_id' : IDL.Nat64,
+    'price_list_id' : IDL.Opt(IDL.Nat64),
+    'variant_id' : IDL.Opt(IDL.Nat64),
     'quantity' : IDL.Float64,
     'price_per_unit_at_order' : IDL.Float64,
   });
@@ -23,104 +205,903 @@ export const idlFactory = ({ IDL }) => {
     'id' : IDL.Nat64,
     'status' : OrderStatus,
     'total_amount' : IDL.Float64,
+    'discounts' : IDL.Opt(IDL.Vec(OrderDiscount)),
     'last_updated' : IDL.Nat64,
     'user_phone_number' : IDL.Text,
+    'delivery_address_id' : IDL.Opt(IDL.Nat64),
     'delivery_address' : IDL.Text,
     'timestamp' : IDL.Nat64,
     'items' : IDL.Vec(OrderItem),
     'customer_name' : IDL.Text,
   });
+  const CouponError = IDL.Variant({
+    'NotApplicable' : IDL.Null,
+    'CouponNotFound' : IDL.Text,
+    'InvalidInput' : IDL.Vec(FieldError),
+    'Disabled' : IDL.Null,
+    'MinimumOrderAmount' : IDL.Float64,
+    'NotYetValid' : IDL.Null,
+    'UsageLimitReached' : IDL.Null,
+    'CustomerLimitReached' : IDL.Null,
+    'Expired' : IDL.Null,
+  });
   const OrderError = IDL.Variant({
+    'InvalidCoupon' : CouponError,
     'AccessDenied' : IDL.Null,
     'CannotCancelOrder' : IDL.Text,
-    'InvalidInput' : IDL.Text,
+    'InvalidInput' : IDL.Vec(FieldError),
+    'PhoneNotVerified' : IDL.Null,
+    'VariantNotFound' : IDL.Record({
+      'product_id' : IDL.Nat64,
+      'variant_id' : IDL.Nat64,
+    }),
     'InvalidProductInOrder' : IDL.Nat64,
+    'OutOfStock' : IDL.Record({
+      'product_id' : IDL.Nat64,
+      'available' : IDL.Nat64,
+      'variant_id' : IDL.Nat64,
+    }),
     'OrderNotFound' : IDL.Null,
+    'CannotEditOrder' : IDL.Text,
     'UserProfileNotFound' : IDL.Null,
+    'AddressNotFound' : IDL.Nat64,
+    'PricesChanged' : IDL.Vec(IDL.Nat64),
     'StorageError' : IDL.Text,
+    'InvalidPhoneNumber' : PhoneNumberError,
+  });
+  const Result_5 = IDL.Variant({ 'Ok' : Order, 'Err' : OrderError });
+  const PriceChange = IDL.Record({
+    'product_id' : IDL.Nat64,
+    'recorded_at' : IDL.Nat64,
+    'effective_from' : IDL.Nat64,
+    'price' : IDL.Float64,
+  });
+  const Result_6 = IDL.Variant({ 'Ok' : PriceChange, 'Err' : ProductError });
+  const PhoneChangeError = IDL.Variant({
+    'ProfileNotFound' : IDL.Null,
+    'RequestNotFound' : IDL.Null,
+    'NumberAlreadyInUse' : IDL.Text,
+    'SameNumber' : IDL.Null,
+    'InvalidPhoneNumber' : PhoneNumberError,
+  });
+  const Result_7 = IDL.Variant({
+    'Ok' : UserProfile,
+    'Err' : PhoneChangeError,
+  });
+  const Result_8 = IDL.Variant({ 'Ok' : IDL.Nat64, 'Err' : OrderError });
+  const CreatedApiKey = IDL.Record({ 'id' : IDL.Nat64, 'key' : IDL.Text });
+  const ApiKeyError = IDL.Variant({
+    'InvalidInput' : IDL.Vec(FieldError),
+    'AlreadyRevoked' : IDL.Nat64,
+    'RandomnessUnavailable' : IDL.Text,
+    'ApiKeyNotFound' : IDL.Nat64,
   });
-  const Result_1 = IDL.Variant({ 'Ok' : Order, 'Err' : OrderError });
+  const Result_9 = IDL.Variant({ 'Ok' : CreatedApiKey, 'Err' : ApiKeyError });
+  const CategoryPayload = IDL.Record({
+    'name' : IDL.Text,
+    'parent_id' : IDL.Opt(IDL.Nat64),
+  });
+  const Category = IDL.Record({
+    'id' : IDL.Nat64,
+    'name' : IDL.Text,
+    'parent_id' : IDL.Opt(IDL.Nat64),
+  });
+  const CategoryError = IDL.Variant({
+    'InvalidInput' : IDL.Vec(FieldError),
+    'CategoryNotFound' : IDL.Nat64,
+    'HasSubcategories' : IDL.Nat64,
+  });
+  const Result_10 = IDL.Variant({ 'Ok' : Category, 'Err' : CategoryError });
   const OrderItemInput = IDL.Record({
     'product_id' : IDL.Nat64,
+    'variant_id' : IDL.Opt(IDL.Nat64),
     'quantity' : IDL.Float64,
   });
-  const Result_2 = IDL.Variant({ 'Ok' : IDL.Nat64, 'Err' : OrderError });
-  const UserProfile = IDL.Record({
+  const PriceListKind = IDL.Variant({
+    'Retail' : IDL.Null,
+    'Custom' : IDL.Null,
+    'Wholesale' : IDL.Null,
+  });
+  const PriceTier = IDL.Record({
+    'price_per_unit' : IDL.Float64,
+    'min_quantity' : IDL.Float64,
+  });
+  const ProductPrice = IDL.Record({
+    'tiers' : IDL.Vec(PriceTier),
+    'product_id' : IDL.Nat64,
+  });
+  const PriceListPayload = IDL.Record({
+    'kind' : PriceListKind,
     'name' : IDL.Text,
-    'order_ids' : IDL.Vec(IDL.Nat64),
-    'address' : IDL.Text,
-    'phone_number' : IDL.Text,
+    'prices' : IDL.Vec(ProductPrice),
+  });
+  const PriceList = IDL.Record({
+    'id' : IDL.Nat64,
+    'kind' : PriceListKind,
+    'name' : IDL.Text,
+    'prices' : IDL.Vec(ProductPrice),
+  });
+  const Result_11 = IDL.Variant({ 'Ok' : PriceList, 'Err' : PriceListError });
+  const ProfileError = IDL.Variant({
+    'InvalidInput' : IDL.Vec(FieldError),
+    'AlreadyExists' : IDL.Text,
+    'InvalidPhoneNumber' : PhoneNumberError,
+  });
+  const Result_12 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : ProfileError });
+  const DataDeletionError = IDL.Variant({
+    'InvalidInput' : IDL.Vec(FieldError),
+    'ProfileNotFound' : IDL.Null,
+    'HasActiveOrders' : IDL.Vec(IDL.Nat64),
+    'AlreadyRequested' : IDL.Null,
+    'InvalidPhoneNumber' : PhoneNumberError,
+    'AlreadyAnonymised' : IDL.Null,
+  });
+  const Result_13 = IDL.Variant({
+    'Ok' : UserProfile,
+    'Err' : DataDeletionError,
+  });
+  const CsvError = IDL.Variant({
+    'InvalidInput' : IDL.Vec(FieldError),
+    'UploadTooLarge' : IDL.Nat64,
+    'UploadNotFound' : IDL.Text,
+    'InvalidCsv' : IDL.Text,
+  });
+  const Result_14 = IDL.Variant({ 'Ok' : IDL.Text, 'Err' : CsvError });
+  const Result_15 = IDL.Variant({
+    'Ok' : SnapshotManifest,
+    'Err' : SnapshotError,
+  });
+  const SnapshotChunk = IDL.Record({
+    'snapshot_checksum' : IDL.Vec(IDL.Nat8),
+    'data' : IDL.Vec(IDL.Nat8),
+    'version' : IDL.Nat32,
+    'checksum' : IDL.Vec(IDL.Nat8),
+    'index' : IDL.Nat64,
+  });
+  const Result_16 = IDL.Variant({
+    'Ok' : SnapshotChunk,
+    'Err' : SnapshotError,
+  });
+  const ProductImage = IDL.Record({
+    'id' : IDL.Nat64,
+    'sha256' : IDL.Vec(IDL.Nat8),
+    'size' : IDL.Nat64,
+    'content_type' : IDL.Text,
+    'uploaded_at' : IDL.Nat64,
+  });
+  const ImageError = IDL.Variant({
+    'InvalidInput' : IDL.Vec(FieldError),
+    'TooLarge' : IDL.Record({ 'max' : IDL.Nat64, 'size' : IDL.Nat64 }),
+    'InUse' : IDL.Nat64,
+    'UploadNotFound' : IDL.Text,
+    'ImageNotFound' : IDL.Nat64,
+    'ChecksumMismatch' : IDL.Null,
+  });
+  const Result_17 = IDL.Variant({ 'Ok' : ProductImage, 'Err' : ImageError });
+  const CanisterCyclesError = IDL.Record({
+    'id' : IDL.Principal,
+    'name' : IDL.Text,
+    'message' : IDL.Text,
   });
-  const Result_3 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : IDL.Text });
-  const Result_4 = IDL.Variant({ 'Ok' : UserProfile, 'Err' : IDL.Text });
   const CanisterCycles = IDL.Record({
     'id' : IDL.Principal,
     'name' : IDL.Text,
     'cycles' : IDL.Nat,
   });
   const AllCanisterCyclesResponse = IDL.Record({
-    'frontend' : CanisterCycles,
-    'backend' : CanisterCycles,
+    'errors' : IDL.Vec(CanisterCyclesError),
+    'canisters' : IDL.Vec(CanisterCycles),
   });
-  const Result_5 = IDL.Variant({
-    'Ok' : AllCanisterCyclesResponse,
-    'Err' : IDL.Text,
+  const Result_18 = IDL.Variant({ 'Ok' : IDL.Vec(Order), 'Err' : OrderError });
+  const AuditOutcome = IDL.Variant({
+    'Success' : IDL.Null,
+    'Failure' : IDL.Text,
+  });
+  const AuditEntry = IDL.Record({
+    'id' : IDL.Nat64,
+    'method' : IDL.Text,
+    'args_summary' : IDL.Text,
+    'hash' : IDL.Vec(IDL.Nat8),
+    'prev_hash' : IDL.Vec(IDL.Nat8),
+    'timestamp' : IDL.Nat64,
+    'caller' : IDL.Principal,
+    'outcome' : AuditOutcome,
+  });
+  const AuditLogPage = IDL.Record({
+    'total' : IDL.Nat64,
+    'entries' : IDL.Vec(AuditEntry),
+  });
+  const Coupon = IDL.Record({
+    'max_uses' : IDL.Opt(IDL.Nat64),
+    'code' : IDL.Text,
+    'max_uses_per_customer' : IDL.Opt(IDL.Nat64),
+    'description' : IDL.Text,
+    'min_order_amount' : IDL.Opt(IDL.Float64),
+    'scope' : CouponScope,
+    'enabled' : IDL.Bool,
+    'valid_until' : IDL.Opt(IDL.Nat64),
+    'discount' : Discount,
+    'valid_from' : IDL.Opt(IDL.Nat64),
+  });
+  const CouponSummary = IDL.Record({
+    'times_used' : IDL.Nat64,
+    'coupon' : Coupon,
+  });
+  const CyclesSample = IDL.Record({
+    'memory_size' : IDL.Nat64,
+    'canister_id' : IDL.Principal,
+    'alerted' : IDL.Bool,
+    'cycles' : IDL.Nat,
+    'idle_cycles_burned_per_day' : IDL.Nat,
+    'timestamp' : IDL.Nat64,
+    'freezing_threshold_seconds' : IDL.Nat64,
+  });
+  const CyclesReport = IDL.Record({
+    'burn_per_day' : IDL.Nat,
+    'below_threshold' : IDL.Bool,
+    'name' : IDL.Text,
+    'canister_id' : IDL.Principal,
+    'samples' : IDL.Vec(CyclesSample),
+    'projected_days_until_freeze' : IDL.Opt(IDL.Float64),
+  });
+  const CyclesMonitorSettings = IDL.Record({
+    'alert_phone_number' : IDL.Opt(IDL.Text),
+    'retention_days' : IDL.Nat64,
+    'low_balance_threshold' : IDL.Nat,
+    'alert_interval_seconds' : IDL.Nat64,
+  });
+  const DailySummary = IDL.Record({
+    'orders_placed' : IDL.Nat64,
+    'revenue' : IDL.Float64,
+    'date' : IDL.Text,
+    'created_at' : IDL.Nat64,
+    'first_time_customers' : IDL.Nat64,
+    'orders_delivered' : IDL.Nat64,
+    'orders_cancelled' : IDL.Nat64,
+    'orders_open' : IDL.Nat64,
+  });
+  const DeletionRequest = IDL.Record({
+    'requested_at' : IDL.Nat64,
+    'phone_number' : IDL.Text,
+  });
+  const ErrorMessage = IDL.Record({ 'code' : IDL.Text, 'message' : IDL.Text });
+  const NotificationEvent = IDL.Variant({
+    'OrderStatusChanged' : IDL.Null,
+    'LowCyclesBalance' : IDL.Record({
+      'canister_id' : IDL.Principal,
+      'cycles' : IDL.Nat,
+    }),
+  });
+  const NotificationState = IDL.Variant({
+    'Failed' : IDL.Null,
+    'Delivered' : IDL.Record({ 'at' : IDL.Nat64 }),
+    'Pending' : IDL.Null,
+  });
+  const Notification = IDL.Record({
+    'id' : IDL.Nat64,
+    'last_error' : IDL.Opt(IDL.Text),
+    'status' : OrderStatus,
+    'next_attempt_at' : IDL.Nat64,
+    'attempts' : IDL.Nat32,
+    'created_at' : IDL.Nat64,
+    'event' : IDL.Opt(NotificationEvent),
+    'state' : NotificationState,
+    'message' : IDL.Text,
+    'order_id' : IDL.Nat64,
+    'phone_number' : IDL.Text,
+  });
+  const JobKind = IDL.Variant({
+    'DailySummary' : IDL.Record({ 'utc_offset_minutes' : IDL.Int32 }),
+    'AutoCancelPendingOrders' : IDL.Record({ 'timeout_seconds' : IDL.Nat64 }),
+    'RecordCyclesBalances' : IDL.Null,
+  });
+  const Result_19 = IDL.Variant({ 'Ok' : IDL.Text, 'Err' : IDL.Text });
+  const JobSchedule = IDL.Variant({
+    'Once' : IDL.Null,
+    'Recurring' : IDL.Record({ 'interval_seconds' : IDL.Nat64 }),
+  });
+  const Job = IDL.Record({
+    'id' : IDL.Nat64,
+    'kind' : JobKind,
+    'name' : IDL.Text,
+    'last_result' : IDL.Opt(Result_19),
+    'enabled' : IDL.Bool,
+    'last_run_at' : IDL.Opt(IDL.Nat64),
+    'next_run_at' : IDL.Nat64,
+    'schedule' : JobSchedule,
+  });
+  const NotificationChannel = IDL.Variant({
+    'Sms' : IDL.Null,
+    'WhatsApp' : IDL.Null,
+    'GenericJson' : IDL.Null,
+  });
+  const NotificationSettings = IDL.Record({
+    'webhook_url' : IDL.Opt(IDL.Text),
+    'auth_header_value' : IDL.Opt(IDL.Text),
+    'base_backoff_seconds' : IDL.Nat64,
+    'enabled' : IDL.Bool,
+    'max_attempts' : IDL.Nat32,
+    'sender_id' : IDL.Text,
+    'channel' : NotificationChannel,
+    'auth_header_name' : IDL.Opt(IDL.Text),
+  });
+  const OrderEditor = IDL.Variant({
+    'Customer' : IDL.Null,
+    'Admin' : IDL.Principal,
+  });
+  const OrderEdit = IDL.Record({
+    'previous_delivery_address' : IDL.Text,
+    'edited_at' : IDL.Nat64,
+    'edited_by' : OrderEditor,
+    'previous_total_amount' : IDL.Float64,
+    'previous_items' : IDL.Vec(OrderItem),
+    'order_id' : IDL.Nat64,
+    'reason' : IDL.Opt(IDL.Text),
+  });
+  const Result_20 = IDL.Variant({
+    'Ok' : IDL.Vec(OrderEdit),
+    'Err' : OrderError,
+  });
+  const PhoneChangeRequest = IDL.Record({
+    'requested_at' : IDL.Nat64,
+    'new_phone_number' : IDL.Text,
+    'current_phone_number' : IDL.Text,
+  });
+  const PhoneSettings = IDL.Record({
+    'default_country_code' : IDL.Nat16,
+    'national_number_length' : IDL.Opt(IDL.Nat8),
   });
-  const Result_6 = IDL.Variant({ 'Ok' : IDL.Vec(Order), 'Err' : OrderError });
   const Product = IDL.Record({
     'id' : IDL.Nat64,
     'name' : IDL.Text,
+    'tags' : IDL.Opt(IDL.Vec(IDL.Text)),
     'unit' : IDL.Text,
     'description' : IDL.Text,
+    'variants' : IDL.Opt(IDL.Vec(ProductVariant)),
+    'image_id' : IDL.Opt(IDL.Nat64),
+    'quantity_step' : IDL.Opt(IDL.Float64),
+    'translations' : IDL.Opt(IDL.Vec(ProductTranslation)),
     'price' : IDL.Float64,
+    'category_id' : IDL.Opt(IDL.Nat64),
+  });
+  const CertifiedProducts = IDL.Record({
+    'certificate' : IDL.Vec(IDL.Nat8),
+    'witness' : IDL.Vec(IDL.Nat8),
+    'products' : IDL.Vec(Product),
+  });
+  const CertificationError = IDL.Variant({
+    'NotInQuery' : IDL.Null,
+    'EncodingFailed' : IDL.Text,
+  });
+  const Result_21 = IDL.Variant({
+    'Ok' : CertifiedProducts,
+    'Err' : CertificationError,
   });
   const GetUserDataError = IDL.Variant({
     'FailedToAddToList' : IDL.Null,
     'DidntFindUserData' : IDL.Null,
+    'InvalidPhoneNumber' : PhoneNumberError,
     'AnonymousCaller' : IDL.Null,
   });
-  const Result_7 = IDL.Variant({
+  const Result_22 = IDL.Variant({
     'Ok' : UserProfile,
     'Err' : GetUserDataError,
   });
-  const Result_8 = IDL.Variant({ 'Ok' : IDL.Text, 'Err' : IDL.Text });
-  const Result_9 = IDL.Variant({ 'Ok' : Product, 'Err' : IDL.Text });
-  const Result_10 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : GetUserDataError });
+  const TopUpSettings = IDL.Record({
+    'auto_top_up' : IDL.Bool,
+    'cmc_canister_id' : IDL.Principal,
+    'min_cycles_balance' : IDL.Nat,
+    'max_top_up_e8s' : IDL.Nat64,
+    'ledger_canister_id' : IDL.Principal,
+    'auto_top_up_e8s' : IDL.Nat64,
+  });
+  const TopUpState = IDL.Variant({
+    'Failed' : IDL.Text,
+    'Transferred' : IDL.Record({ 'block_index' : IDL.Nat64 }),
+    'Completed' : IDL.Record({ 'block_index' : IDL.Nat64, 'cycles' : IDL.Nat }),
+    'Pending' : IDL.Null,
+  });
+  const TopUp = IDL.Record({
+    'id' : IDL.Nat64,
+    'last_error' : IDL.Opt(IDL.Text),
+    'automatic' : IDL.Bool,
+    'canister_id' : IDL.Principal,
+    'icp_e8s' : IDL.Nat64,
+    'created_at' : IDL.Nat64,
+    'requested_by' : IDL.Principal,
+    'state' : TopUpState,
+  });
+  const VerificationSettings = IDL.Record({
+    'sms_sender_id' : IDL.Text,
+    'sms_auth_header_value' : IDL.Opt(IDL.Text),
+    'resend_interval_seconds' : IDL.Nat64,
+    'max_attempts' : IDL.Nat32,
+    'code_ttl_seconds' : IDL.Nat64,
+    'sms_endpoint_url' : IDL.Opt(IDL.Text),
+    'sms_auth_header_name' : IDL.Opt(IDL.Text),
+    'require_verified_phone_for_orders' : IDL.Bool,
+  });
+  const HttpRequest = IDL.Record({
+    'url' : IDL.Text,
+    'method' : IDL.Text,
+    'body' : IDL.Vec(IDL.Nat8),
+    'headers' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Text)),
+  });
+  const HttpResponse = IDL.Record({
+    'body' : IDL.Vec(IDL.Nat8),
+    'headers' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Text)),
+    'upgrade' : IDL.Opt(IDL.Bool),
+    'status_code' : IDL.Nat16,
+  });
+  const CsvSource = IDL.Variant({ 'Upload' : IDL.Text, 'Inline' : IDL.Text });
+  const ImportRowStatus = IDL.Variant({
+    'Invalid' : IDL.Text,
+    'Updated' : IDL.Null,
+    'Created' : IDL.Null,
+  });
+  const ImportRowResult = IDL.Record({
+    'key' : IDL.Text,
+    'row' : IDL.Nat64,
+    'status' : ImportRowStatus,
+  });
+  const ImportReport = IDL.Record({
+    'created' : IDL.Nat64,
+    'invalid' : IDL.Nat64,
+    'rows' : IDL.Vec(ImportRowResult),
+    'updated' : IDL.Nat64,
+    'dry_run' : IDL.Bool,
+  });
+  const Result_23 = IDL.Variant({ 'Ok' : ImportReport, 'Err' : CsvError });
+  const Result_24 = IDL.Variant({ 'Ok' : IDL.Text, 'Err' : SnapshotError });
+  const Result_25 = IDL.Variant({ 'Ok' : IDL.Nat64, 'Err' : SnapshotError });
+  const SetupError = IDL.Variant({
+    'AlreadyInitialized' : IDL.Null,
+    'StorageError' : IDL.Text,
+  });
+  const Result_26 = IDL.Variant({ 'Ok' : IDL.Text, 'Err' : SetupError });
+  const ApiKey = IDL.Record({
+    'id' : IDL.Nat64,
+    'revoked' : IDL.Bool,
+    'last_used_at' : IDL.Opt(IDL.Nat64),
+    'name' : IDL.Text,
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'key_hash' : IDL.Vec(IDL.Nat8),
+    'prefix' : IDL.Text,
+  });
+  const PhoneMigrationReport = IDL.Record({
+    'profiles_merged' : IDL.Nat64,
+    'profiles_rewritten' : IDL.Nat64,
+    'invalid_phone_numbers' : IDL.Vec(IDL.Text),
+    'orders_rewritten' : IDL.Nat64,
+  });
+  const SettingsError = IDL.Variant({
+    'InvalidInput' : IDL.Vec(FieldError),
+    'StorageError' : IDL.Text,
+  });
+  const Result_27 = IDL.Variant({
+    'Ok' : PhoneMigrationReport,
+    'Err' : SettingsError,
+  });
+  const Result_28 = IDL.Variant({ 'Ok' : OrderDiscount, 'Err' : OrderError });
+  const Result_29 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : AddressError });
+  const Result_30 = IDL.Variant({ 'Ok' : Coupon, 'Err' : CouponError });
+  const JobError = IDL.Variant({
+    'InvalidInput' : IDL.Vec(FieldError),
+    'JobNotFound' : IDL.Nat64,
+  });
+  const Result_31 = IDL.Variant({ 'Ok' : Job, 'Err' : JobError });
+  const CyclesError = IDL.Variant({
+    'InvalidInput' : IDL.Vec(FieldError),
+    'NotMonitored' : IDL.Principal,
+    'TooManyCanisters' : IDL.Nat64,
+    'StorageError' : IDL.Text,
+  });
+  const Result_32 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : CyclesError });
+  const Result_33 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : DataDeletionError });
+  const OtpPurpose = IDL.Variant({
+    'VerifyProfile' : IDL.Null,
+    'ChangePhoneNumber' : IDL.Record({ 'current_phone_number' : IDL.Text }),
+  });
+  const OtpError = IDL.Variant({
+    'TooManyRequests' : IDL.Record({ 'retry_after_seconds' : IDL.Nat64 }),
+    'PhoneChangeFailed' : PhoneChangeError,
+    'RandomnessUnavailable' : IDL.Text,
+    'ProfileNotFound' : IDL.Null,
+    'NoPendingCode' : IDL.Null,
+    'PhoneChangeNotRequested' : IDL.Null,
+    'SendFailed' : IDL.Text,
+    'InvalidCode' : IDL.Record({ 'attempts_left' : IDL.Nat32 }),
+    'TooManyAttempts' : IDL.Null,
+    'NotConfigured' : IDL.Null,
+    'InvalidPhoneNumber' : PhoneNumberError,
+    'Expired' : IDL.Null,
+  });
+  const Result_34 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : OtpError });
+  const Result_35 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : PhoneChangeError });
+  const NotificationError = IDL.Variant({
+    'NotFailed' : IDL.Nat64,
+    'NotificationNotFound' : IDL.Nat64,
+  });
+  const Result_36 = IDL.Variant({
+    'Ok' : Notification,
+    'Err' : NotificationError,
+  });
+  const TopUpError = IDL.Variant({
+    'Failed' : IDL.Record({ 'top_up_id' : IDL.Nat64, 'reason' : IDL.Text }),
+    'InvalidInput' : IDL.Vec(FieldError),
+    'NotMonitored' : IDL.Principal,
+    'TopUpNotFound' : IDL.Nat64,
+    'NotMinted' : IDL.Record({
+      'block_index' : IDL.Nat64,
+      'top_up_id' : IDL.Nat64,
+      'reason' : IDL.Text,
+    }),
+    'NotRetryable' : IDL.Nat64,
+    'StorageError' : IDL.Text,
+  });
+  const Result_37 = IDL.Variant({ 'Ok' : TopUp, 'Err' : TopUpError });
+  const Result_38 = IDL.Variant({ 'Ok' : ApiKey, 'Err' : ApiKeyError });
+  const JobPayload = IDL.Record({
+    'kind' : JobKind,
+    'name' : IDL.Text,
+    'enabled' : IDL.Bool,
+    'next_run_at' : IDL.Opt(IDL.Nat64),
+    'schedule' : JobSchedule,
+  });
+  const ProductSort = IDL.Variant({
+    'PriceDescending' : IDL.Null,
+    'Name' : IDL.Null,
+    'Relevance' : IDL.Null,
+    'PriceAscending' : IDL.Null,
+  });
+  const ProductSearch = IDL.Record({
+    'lang' : IDL.Opt(IDL.Text),
+    'sort' : IDL.Opt(ProductSort),
+    'tags' : IDL.Opt(IDL.Vec(IDL.Text)),
+    'text' : IDL.Opt(IDL.Text),
+    'category_id' : IDL.Opt(IDL.Nat64),
+  });
+  const Result_39 = IDL.Variant({
+    'Ok' : MonitoredCanister,
+    'Err' : CyclesError,
+  });
+  const Result_40 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : GetUserDataError });
+  const Result_41 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : SettingsError });
+  const Result_42 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : TopUpError });
+  const Result_43 = IDL.Variant({ 'Ok' : Product, 'Err' : ProductError });
+  const HttpHeader = IDL.Record({ 'value' : IDL.Text, 'name' : IDL.Text });
+  const HttpResponse_1 = IDL.Record({
+    'status' : IDL.Nat,
+    'body' : IDL.Vec(IDL.Nat8),
+    'headers' : IDL.Vec(HttpHeader),
+  });
+  const TransformArgs = IDL.Record({
+    'context' : IDL.Vec(IDL.Nat8),
+    'response' : HttpResponse_1,
+  });
+  const Result_44 = IDL.Variant({ 'Ok' : IDL.Nat64, 'Err' : CsvError });
+  const Result_45 = IDL.Variant({ 'Ok' : IDL.Nat64, 'Err' : ImageError });
+  const Result_46 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : IDL.Nat64 });
+  const Result_47 = IDL.Variant({ 'Ok' : UserProfile, 'Err' : OtpError });
   return IDL.Service({
-    'add_product_admin' : IDL.Func([AddProductPayload], [Result], []),
-    'cancel_my_order' : IDL.Func([IDL.Nat64, IDL.Text], [Result_1], []),
-    'create_order' : IDL.Func(
-        [IDL.Text, IDL.Vec(OrderItemInput), IDL.Text],
+    'add_address' : IDL.Func([IDL.Text, AddressInput], [Result], []),
+    'add_product_admin' : IDL.Func([AddProductPayload], [Result_1], []),
+    'add_to_cart' : IDL.Func(
+        [IDL.Text, IDL.Nat64, IDL.Float64, IDL.Opt(IDL.Nat64)],
         [Result_2],
         [],
       ),
-    'create_profile' : IDL.Func([UserProfile], [Result_3], []),
-    'delete_profile_admin' : IDL.Func([IDL.Text], [Result_4], []),
-    'get_all_canister_cycles' : IDL.Func([], [Result_5], []),
+    'assign_price_list_admin' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Nat64)],
+        [Result_3],
+        [],
+      ),
+    'begin_import_snapshot' : IDL.Func([SnapshotManifest], [Result_4], []),
+    'cancel_my_order' : IDL.Func([IDL.Nat64, IDL.Text], [Result_5], []),
+    'cancel_price_change_admin' : IDL.Func(
+        [IDL.Nat64, IDL.Nat64],
+        [Result_6],
+        [],
+      ),
+    'change_phone_number_admin' : IDL.Func(
+        [IDL.Text, IDL.Text],
+        [Result_7],
+        [],
+      ),
+    'checkout_cart' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Nat64), IDL.Opt(IDL.Text)],
+        [Result_8],
+        [],
+      ),
+    'clear_cart' : IDL.Func([IDL.Text], [Result_2], []),
+    'create_api_key_admin' : IDL.Func([IDL.Text], [Result_9], []),
+    'create_category_admin' : IDL.Func([CategoryPayload], [Result_10], []),
+    'create_order' : IDL.Func(
+        [
+          IDL.Text,
+          IDL.Vec(OrderItemInput),
+          IDL.Text,
+          IDL.Opt(IDL.Nat64),
+          IDL.Opt(IDL.Text),
+        ],
+        [Result_8],
+        [],
+      ),
+    'create_price_list_admin' : IDL.Func([PriceListPayload], [Result_11], []),
+    'create_profile' : IDL.Func([UserProfile], [Result_12], []),
+    'delete_profile_admin' : IDL.Func([IDL.Text], [Result_13], []),
+    'export_customers_csv' : IDL.Func(
+        [IDL.Opt(IDL.Int32)],
+        [IDL.Text],
+        ['query'],
+      ),
+    'export_orders_csv' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Int32)],
+        [Result_14],
+        ['query'],
+      ),
+    'export_products_csv' : IDL.Func([], [IDL.Text], ['query']),
+    'export_snapshot' : IDL.Func([], [Result_15], []),
+    'export_snapshot_chunk' : IDL.Func([IDL.Nat64], [Result_16], ['query']),
+    'finish_image_upload' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Vec(IDL.Nat8)],
+        [Result_17],
+        [],
+      ),
+    'get_all_canister_cycles' : IDL.Func([], [AllCanisterCyclesResponse], []),
     'get_all_customers' : IDL.Func([], [IDL.Vec(UserProfile)], ['query']),
-    'get_all_orders' : IDL.Func([], [Result_6], ['query']),
-    'get_my_orders' : IDL.Func([IDL.Text], [Result_6], ['query']),
+    'get_all_orders' : IDL.Func([], [Result_18], ['query']),
+    'get_audit_log' : IDL.Func(
+        [IDL.Nat64, IDL.Nat64],
+        [AuditLogPage],
+        ['query'],
+      ),
+    'get_cart' : IDL.Func([IDL.Text], [Result_2], ['query']),
+    'get_categories' : IDL.Func([], [IDL.Vec(Category)], ['query']),
+    'get_coupons_admin' : IDL.Func([], [IDL.Vec(CouponSummary)], ['query']),
+    'get_cycles_history' : IDL.Func(
+        [IDL.Opt(IDL.Nat64)],
+        [IDL.Vec(CyclesReport)],
+        ['query'],
+      ),
+    'get_cycles_monitor_settings' : IDL.Func(
+        [],
+        [CyclesMonitorSettings],
+        ['query'],
+      ),
+    'get_daily_summaries' : IDL.Func(
+        [IDL.Text, IDL.Text],
+        [IDL.Vec(DailySummary)],
+        ['query'],
+      ),
+    'get_deletion_requests' : IDL.Func(
+        [],
+        [IDL.Vec(DeletionRequest)],
+        ['query'],
+      ),
+    'get_error_messages' : IDL.Func(
+        [IDL.Text],
+        [IDL.Vec(ErrorMessage)],
+        ['query'],
+      ),
+    'get_failed_notifications' : IDL.Func(
+        [],
+        [IDL.Vec(Notification)],
+        ['query'],
+      ),
+    'get_icp_account' : IDL.Func([], [IDL.Text], ['query']),
+    'get_images_admin' : IDL.Func([], [IDL.Vec(ProductImage)], ['query']),
+    'get_jobs' : IDL.Func([], [IDL.Vec(Job)], ['query']),
+    'get_monitored_canisters' : IDL.Func(
+        [],
+        [IDL.Vec(MonitoredCanister)],
+        ['query'],
+      ),
+    'get_my_orders' : IDL.Func([IDL.Text], [Result_18], ['query']),
+    'get_notification_settings' : IDL.Func(
+        [],
+        [NotificationSettings],
+        ['query'],
+      ),
     'get_order_details' : IDL.Func(
         [IDL.Nat64, IDL.Text],
-        [Result_1],
+        [Result_5],
+        ['query'],
+      ),
+    'get_order_details_admin' : IDL.Func([IDL.Nat64], [Result_5], ['query']),
+    'get_order_edits' : IDL.Func([IDL.Nat64, IDL.Text], [Result_20], ['query']),
+    'get_order_edits_admin' : IDL.Func([IDL.Nat64], [Result_20], ['query']),
+    'get_phone_change_requests' : IDL.Func(
+        [],
+        [IDL.Vec(PhoneChangeRequest)],
+        ['query'],
+      ),
+    'get_phone_settings' : IDL.Func([], [PhoneSettings], ['query']),
+    'get_price_history' : IDL.Func(
+        [IDL.Nat64],
+        [IDL.Vec(PriceChange)],
+        ['query'],
+      ),
+    'get_price_lists_admin' : IDL.Func([], [IDL.Vec(PriceList)], ['query']),
+    'get_products' : IDL.Func(
+        [IDL.Opt(IDL.Text)],
+        [IDL.Vec(Product)],
+        ['query'],
+      ),
+    'get_products_certified' : IDL.Func([], [Result_21], ['query']),
+    'get_profile_by_phone' : IDL.Func([IDL.Text], [Result_22], ['query']),
+    'get_top_up_settings' : IDL.Func([], [TopUpSettings], ['query']),
+    'get_top_ups' : IDL.Func([], [IDL.Vec(TopUp)], ['query']),
+    'get_verification_settings' : IDL.Func(
+        [],
+        [VerificationSettings],
         ['query'],
       ),
-    'get_order_details_admin' : IDL.Func([IDL.Nat64], [Result_1], ['query']),
-    'get_products' : IDL.Func([], [IDL.Vec(Product)], ['query']),
-    'get_profile_by_phone' : IDL.Func([IDL.Text], [Result_7], ['query']),
-    'initialize_products' : IDL.Func([], [Result_8], []),
+    'http_request' : IDL.Func([HttpRequest], [HttpResponse], ['query']),
+    'http_request_update' : IDL.Func([HttpRequest], [HttpResponse], []),
+    'import_customers_csv' : IDL.Func([CsvSource, IDL.Bool], [Result_23], []),
+    'import_products_csv' : IDL.Func([CsvSource, IDL.Bool], [Result_23], []),
+    'import_snapshot' : IDL.Func([], [Result_24], []),
+    'import_snapshot_chunk' : IDL.Func([SnapshotChunk], [Result_25], []),
+    'initialize_products' : IDL.Func([], [Result_26], []),
     'is_dev_check' : IDL.Func([], [IDL.Bool], ['query']),
+    'list_api_keys_admin' : IDL.Func([], [IDL.Vec(ApiKey)], ['query']),
+    'migrate_phone_numbers' : IDL.Func([], [Result_27], []),
+    'preview_coupon' : IDL.Func(
+        [IDL.Text, IDL.Vec(OrderItemInput), IDL.Text],
+        [Result_28],
+        ['query'],
+      ),
+    'remove_address' : IDL.Func([IDL.Text, IDL.Nat64], [Result_29], []),
+    'remove_category_admin' : IDL.Func([IDL.Nat64], [Result_10], []),
+    'remove_coupon_admin' : IDL.Func([IDL.Text], [Result_30], []),
+    'remove_from_cart' : IDL.Func(
+        [IDL.Text, IDL.Nat64, IDL.Opt(IDL.Nat64)],
+        [Result_2],
+        [],
+      ),
+    'remove_image_admin' : IDL.Func([IDL.Nat64], [Result_17], []),
+    'remove_job_admin' : IDL.Func([IDL.Nat64], [Result_31], []),
+    'remove_monitored_canister_admin' : IDL.Func(
+        [IDL.Principal],
+        [Result_32],
+        [],
+      ),
+    'remove_price_list_admin' : IDL.Func([IDL.Nat64], [Result_11], []),
+    'request_data_deletion' : IDL.Func([IDL.Text], [Result_33], []),
+    'request_otp' : IDL.Func([IDL.Text, OtpPurpose], [Result_34], []),
+    'request_phone_number_change' : IDL.Func(
+        [IDL.Text, IDL.Text],
+        [Result_35],
+        [],
+      ),
+    'retry_notification_admin' : IDL.Func([IDL.Nat64], [Result_36], []),
+    'retry_top_up_admin' : IDL.Func([IDL.Nat64], [Result_37], []),
+    'revoke_api_key_admin' : IDL.Func([IDL.Nat64], [Result_38], []),
+    'run_job_now_admin' : IDL.Func([IDL.Nat64], [Result_31], []),
+    'schedule_job_admin' : IDL.Func([JobPayload], [Result_31], []),
+    'schedule_price_change_admin' : IDL.Func(
+        [IDL.Nat64, IDL.Float64, IDL.Nat64],
+        [Result_6],
+        [],
+      ),
+    'search_products' : IDL.Func(
+        [ProductSearch],
+        [IDL.Vec(Product)],
+        ['query'],
+      ),
+    'set_coupon_admin' : IDL.Func([Coupon], [Result_30], []),
+    'set_cycles_monitor_settings' : IDL.Func(
+        [CyclesMonitorSettings],
+        [Result_32],
+        [],
+      ),
+    'set_default_address' : IDL.Func([IDL.Text, IDL.Nat64], [Result_29], []),
+    'set_monitored_canister_admin' : IDL.Func(
+        [MonitoredCanister],
+        [Result_39],
+        [],
+      ),
+    'set_notification_preference' : IDL.Func(
+        [IDL.Text, IDL.Bool],
+        [Result_40],
+        [],
+      ),
+    'set_notification_settings' : IDL.Func(
+        [NotificationSettings],
+        [Result_41],
+        [],
+      ),
+    'set_phone_settings' : IDL.Func([PhoneSettings], [Result_41], []),
+    'set_top_up_settings' : IDL.Func([TopUpSettings], [Result_42], []),
+    'set_variant_stock_admin' : IDL.Func(
+        [IDL.Nat64, IDL.Nat64, IDL.Opt(IDL.Nat64)],
+        [Result_43],
+        [],
+      ),
+    'set_verification_settings' : IDL.Func(
+        [VerificationSettings],
+        [Result_41],
+        [],
+      ),
+    'top_up_canister' : IDL.Func([IDL.Principal, IDL.Nat64], [Result_37], []),
+    'transform_outcall_response' : IDL.Func(
+        [TransformArgs],
+        [HttpResponse_1],
+        ['query'],
+      ),
+    'update_address' : IDL.Func(
+        [IDL.Text, IDL.Nat64, AddressInput],
+        [Result],
+        [],
+      ),
+    'update_cart_item' : IDL.Func(
+        [IDL.Text, IDL.Nat64, IDL.Float64, IDL.Opt(IDL.Nat64)],
+        [Result_2],
+        [],
+      ),
+    'update_category_admin' : IDL.Func(
+        [IDL.Nat64, CategoryPayload],
+        [Result_10],
+        [],
+      ),
+    'update_job_admin' : IDL.Func([IDL.Nat64, JobPayload], [Result_31], []),
+    'update_my_order' : IDL.Func(
+        [
+          IDL.Nat64,
+          IDL.Text,
+          IDL.Vec(OrderItemInput),
+          IDL.Opt(IDL.Text),
+          IDL.Opt(IDL.Nat64),
+        ],
+        [Result_5],
+        [],
+      ),
+    'update_order_admin' : IDL.Func(
+        [
+          IDL.Nat64,
+          IDL.Vec(OrderItemInput),
+          IDL.Opt(IDL.Text),
+          IDL.Opt(IDL.Nat64),
+          IDL.Text,
+        ],
+        [Result_5],
+        [],
+      ),
     'update_order_status_admin' : IDL.Func(
         [IDL.Nat64, OrderStatus],
-        [Result_1],
+        [Result_5],
+        [],
+      ),
+    'update_price_list_admin' : IDL.Func(
+        [IDL.Nat64, PriceListPayload],
+        [Result_11],
         [],
       ),
     'update_product_admin' : IDL.Func(
         [IDL.Nat64, AddProductPayload],
-        [Result_9],
+        [Result_43],
+        [],
+      ),
+    'update_profile' : IDL.Func([UserProfile], [Result_12], []),
+    'upload_csv_chunk' : IDL.Func([IDL.Text, IDL.Text], [Result_44], []),
+    'upload_image_chunk' : IDL.Func(
+        [IDL.Text, IDL.Vec(IDL.Nat8)],
+        [Result_45],
         [],
       ),
-    'update_profile' : IDL.Func([UserProfile], [Result_10], []),
+    'verify_audit_log' : IDL.Func([], [Result_46], ['query']),
+    'verify_otp' : IDL.Func([IDL.Text, IDL.Text], [Result_47], []),
+  });
+};
+export const init = ({ IDL }) => {
+  const MonitoredCanister = IDL.Record({
+    'canister_id' : IDL.Principal,
+    'label' : IDL.Text,
+  });
+  const InitArgs = IDL.Record({
+    'monitored_canisters' : IDL.Opt(IDL.Vec(MonitoredCanister)),
   });
+  return [IDL.Opt(InitArgs)];
 };
-export const init = ({ IDL }) => { return []; };
@@ -2,6 +2,8 @@ import { backendActorPromise, getAuthenticatedActor } from "./agent";
 import type { Product as FrontendProduct, UserProfile, Order as FrontendOrder, OrderItemInput, OrderStatus } from "./types";
 import { authStore } from "./stores/authStore";
 import { get } from "svelte/store";
+import { BACKEND_RAW_URL } from "./const";
+import { getVerifiedProducts } from "./certified";
 
 // Simple custom toast function
 function showToast({ text, level }: { text: string, level: string }) {
@@ -13,10 +15,10 @@ function showToast({ text, level }: { text: string, level: string }) {
 export async function getProducts(): Promise<FrontendProduct[]> {
   try {
     const actor = await backendActorPromise;
-    const result = await actor.get_products();
-    // Ensure we're handling the response correctly
+    // Prices are checked against the subnet's certificate instead of trusting the replica
+    const result = await getVerifiedProducts(actor);
     // Convert backend product format to frontend format
-    return (result || []).map((item: any) => {
+    return result.map((item) => {
       let imageName;
       if (item.name === "Matha") {
         imageName = 'buttermilk.jpg';
@@ -29,7 +31,9 @@ export async function getProducts(): Promise<FrontendProduct[]> {
         unit: item.unit,
         description: item.description,
         price: Number(item.price),
-        imageUrl: `/images/products/${imageName}`
+        imageUrl: item.image_id?.length
+          ? `${BACKEND_RAW_URL}/api/images/${item.image_id[0]}`
+          : `/images/products/${imageName}`
       };
     });
   } catch (error) {
@@ -174,13 +178,8 @@ export async function getHostingBalance() {
     }
 
     const actor = await getAuthenticatedActor(currentAuth.identity);
-    const result = await actor.get_all_canister_cycles();
-
-    if ("Ok" in result) {
-      return result.Ok;
-    } else {
-      throw new Error(result.Err || "Failed to fetch hosting balance due to an unknown error.");
-    }
+    // Canisters whose status call was rejected are listed in `errors`
+    return await actor.get_all_canister_cycles();
 
   } catch (error) {
     const errorMessage = error instanceof Error ? error.message : "An unknown error occurred while fetching hosting balance.";
@@ -302,10 +301,11 @@ export async function createOrder(phoneNumber: string, items: OrderItemInput[],
     const actor = await backendActorPromise;
     const itemsToSend = items.map(item => ({
       ...item,
-      product_id: BigInt(item.product_id)
+      product_id: BigInt(item.product_id),
+      variant_id: [] as [] | [bigint]
     }));
 
-    const result = await actor.create_order(phoneNumber, itemsToSend, deliveryAddress);
+    const result = await actor.create_order(phoneNumber, itemsToSend, deliveryAddress, [], []);
     if ("Ok" in result) {
       showToast({
         text: "Order created successfully!",
@@ -0,0 +1,116 @@
+import { Actor, Cbor, Certificate, lookupResultToBuffer, reconstruct } from "@dfinity/agent";
+import type { ActorSubclass, HashTree } from "@dfinity/agent";
+import { IDL } from "@dfinity/candid";
+import type { _SERVICE } from "../../../declarations/backend/backend.did";
+
+// Products are certified as the leaves of a tree under this label, keyed by their ID
+// (see src/backend/src/certified.rs)
+const PRODUCTS_LABEL = "products";
+
+// Hash tree node tags as defined by the IC interface specification
+const EMPTY = 0;
+const FORK = 1;
+const LABELED = 2;
+const LEAF = 3;
+
+// The fields the frontend shows. Each leaf holds a whole Candid-encoded product; decoding
+// into a record with fewer fields skips the others.
+const CertifiedProduct = IDL.Record({
+  id: IDL.Nat64,
+  name: IDL.Text,
+  description: IDL.Text,
+  price: IDL.Float64,
+  unit: IDL.Text,
+  image_id: IDL.Opt(IDL.Nat64),
+});
+
+export interface VerifiedProduct {
+  id: bigint;
+  name: string;
+  description: string;
+  price: number;
+  unit: string;
+  image_id: [] | [bigint];
+}
+
+function toArrayBuffer(bytes: Uint8Array | number[] | ArrayBuffer): ArrayBuffer {
+  return new Uint8Array(bytes).buffer;
+}
+
+function equalBytes(a: ArrayBuffer, b: ArrayBuffer): boolean {
+  const x = new Uint8Array(a);
+  const y = new Uint8Array(b);
+  return x.length === y.length && x.every((byte, i) => byte === y[i]);
+}
+
+// Collects the leaves below a node, failing on pruned branches: the witness must reveal
+// every product, or some could be hidden from the customer.
+function collectLeaves(tree: HashTree, leaves: ArrayBuffer[]): void {
+  const node = tree as unknown as [number, ...unknown[]];
+  switch (node[0]) {
+    case EMPTY:
+      return;
+    case FORK:
+      collectLeaves(node[1] as HashTree, leaves);
+      collectLeaves(node[2] as HashTree, leaves);
+      return;
+    case LABELED:
+      collectLeaves(node[2] as HashTree, leaves);
+      return;
+    case LEAF:
+      leaves.push(toArrayBuffer(node[1] as ArrayBuffer));
+      return;
+    default:
+      throw new Error("The product witness is incomplete");
+  }
+}
+
+function productsSubtree(tree: HashTree): HashTree {
+  const node = tree as unknown as [number, ArrayBuffer, HashTree];
+  if (node[0] !== LABELED || new TextDecoder().decode(new Uint8Array(node[1])) !== PRODUCTS_LABEL) {
+    throw new Error("The product witness has an unexpected shape");
+  }
+  return node[2];
+}
+
+/**
+ * Fetches the products with `get_products_certified` and checks them against the subnet's
+ * certificate: the certificate must be valid for this canister, and the witness must hash
+ * to its certified data. The products are then read from the witness itself, so nothing
+ * the answering replica could have altered is shown.
+ */
+export async function getVerifiedProducts(
+  actor: ActorSubclass<_SERVICE>
+): Promise<VerifiedProduct[]> {
+  const result = await actor.get_products_certified();
+  if ("Err" in result) {
+    throw new Error(`Certified products unavailable: ${JSON.stringify(result.Err)}`);
+  }
+  const { certificate, witness } = result.Ok;
+
+  const agent = Actor.agentOf(actor);
+  if (!agent?.rootKey) {
+    throw new Error("The agent has no root key to verify certificates with");
+  }
+  const canisterId = Actor.canisterIdOf(actor);
+  const cert = await Certificate.create({
+    certificate: toArrayBuffer(certificate),
+    rootKey: agent.rootKey,
+    canisterId,
+  });
+  const certifiedData = lookupResultToBuffer(
+    cert.lookup(["canister", toArrayBuffer(canisterId.toUint8Array()), "certified_data"])
+  );
+  if (!certifiedData) {
+    throw new Error("The certificate holds no certified data for the backend");
+  }
+
+  const tree = Cbor.decode<HashTree>(toArrayBuffer(witness));
+  if (!equalBytes(await reconstruct(tree), certifiedData)) {
+    throw new Error("The product witness doesn't match the certified data");
+  }
+
+  const leaves: ArrayBuffer[] = [];
+  collectLeaves(productsSubtree(tree), leaves);
+  return leaves.map((leaf) => IDL.decode([CertifiedProduct], leaf)[0] as unknown as VerifiedProduct);
+}
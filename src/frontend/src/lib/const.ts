@@ -1,3 +1,9 @@
 export const HOST = process.env.DFX_NETWORK != "ic" ? "http://localhost:8080" : "https://ic0.app"
 
 export const FETCH_ROOT_KEY = process.env.DFX_NETWORK != "ic"
+
+// Uploaded images are answered by the backend's http_request without a certificate, which the
+// certifying gateway rejects, so they are loaded from the raw domain
+export const BACKEND_RAW_URL = process.env.DFX_NETWORK != "ic"
+  ? `http://${process.env.CANISTER_ID_BACKEND}.raw.localhost:8080`
+  : `https://${process.env.CANISTER_ID_BACKEND}.raw.icp0.io`